@@ -1,8 +1,10 @@
 use azalea_buf::McBuf;
+use serde::Deserialize;
 use std::collections::HashMap;
 use uuid::Uuid;
 
-#[derive(McBuf, Debug, Clone)]
+#[derive(McBuf, Debug, Clone, PartialEq, Eq)]
+/// A player's account information, like their UUID and skin.
 pub struct GameProfile {
     pub uuid: Uuid,
     pub name: String,
@@ -17,10 +19,96 @@ impl GameProfile {
             properties: HashMap::new(),
         }
     }
+
+    /// Get the URL of the player's skin from their `textures` property.
+    ///
+    /// Returns `None` if the player doesn't have a custom skin or the
+    /// property is malformed.
+    pub fn skin_url(&self) -> Option<String> {
+        self.properties.get("textures")?.skin_url()
+    }
 }
 
-#[derive(McBuf, Debug, Clone)]
+#[derive(McBuf, Debug, Clone, PartialEq, Eq)]
 pub struct ProfilePropertyValue {
     pub value: String,
     pub signature: Option<String>,
 }
+
+impl ProfilePropertyValue {
+    /// Get the skin URL from the value of a `textures` property, which is
+    /// base64 encoded JSON. The signature isn't checked.
+    pub fn skin_url(&self) -> Option<String> {
+        let json = base64::decode(&self.value).ok()?;
+        let textures: TexturesPayload = serde_json::from_slice(&json).ok()?;
+        Some(textures.textures.skin?.url)
+    }
+}
+
+#[derive(Deserialize)]
+struct TexturesPayload {
+    textures: Textures,
+}
+
+#[derive(Deserialize)]
+struct Textures {
+    #[serde(rename = "SKIN")]
+    skin: Option<Texture>,
+}
+
+#[derive(Deserialize)]
+struct Texture {
+    url: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_with_textures(value: String) -> GameProfile {
+        let mut profile = GameProfile::new(Uuid::nil(), "bot".to_string());
+        profile.properties.insert(
+            "textures".to_string(),
+            ProfilePropertyValue {
+                value,
+                signature: None,
+            },
+        );
+        profile
+    }
+
+    #[test]
+    fn test_skin_url() {
+        let json = r#"{
+            "timestamp": 1665000000000,
+            "profileId": "00000000000000000000000000000000",
+            "profileName": "bot",
+            "textures": {
+                "SKIN": {
+                    "url": "http://textures.minecraft.net/texture/abc",
+                    "metadata": { "model": "slim" }
+                }
+            }
+        }"#;
+        let profile = profile_with_textures(base64::encode(json));
+        assert_eq!(
+            profile.skin_url().as_deref(),
+            Some("http://textures.minecraft.net/texture/abc")
+        );
+    }
+
+    #[test]
+    fn test_missing_or_malformed_skin_url() {
+        assert_eq!(
+            GameProfile::new(Uuid::nil(), "bot".to_string()).skin_url(),
+            None
+        );
+        // players with the default skin have no SKIN texture
+        let profile = profile_with_textures(base64::encode(r#"{"textures": {}}"#));
+        assert_eq!(profile.skin_url(), None);
+        let profile = profile_with_textures("not base64!".to_string());
+        assert_eq!(profile.skin_url(), None);
+        let profile = profile_with_textures(base64::encode("not json"));
+        assert_eq!(profile.skin_url(), None);
+    }
+}
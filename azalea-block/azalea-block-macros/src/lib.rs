@@ -475,7 +475,8 @@ pub fn make_block_states(input: TokenStream) -> TokenStream {
             let conversion_code = {
                 if &property_struct_name_ident.to_string() == "bool" {
                     assert_eq!(property_variants_count, 2);
-                    quote! {(b / #division) % #property_variants_count != 0}
+                    // the states for true come before the ones for false
+                    quote! {(b / #division) % #property_variants_count == 0}
                 } else {
                     quote! {#property_struct_name_ident::from((b / #division) % #property_variants_count)}
                 }
@@ -533,6 +534,9 @@ pub fn make_block_states(input: TokenStream) -> TokenStream {
                 fn id(&self) -> &'static str {
                     #block_id
                 }
+                fn as_any(&self) -> &dyn std::any::Any {
+                    self
+                }
             }
 
             impl From<#block_struct_name> for BlockState {
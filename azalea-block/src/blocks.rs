@@ -1,9 +1,13 @@
 use crate::BlockBehavior;
 use azalea_block_macros::make_block_states;
+use std::any::Any;
 
 pub trait Block {
     fn behavior(&self) -> BlockBehavior;
     fn id(&self) -> &'static str;
+    /// Get the block as [`Any`], so it can be downcast to the struct for the
+    /// block to read its properties.
+    fn as_any(&self) -> &dyn Any;
 }
 
 make_block_states! {
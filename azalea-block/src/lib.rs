@@ -72,4 +72,16 @@ mod tests {
         let block: Box<dyn Block> = Box::<dyn Block>::from(BlockState::FloweringAzalea);
         assert_eq!(block.id(), "flowering_azalea");
     }
+
+    #[test]
+    fn test_downcast_block() {
+        let state = BlockState::from(OakDoorBlock {
+            open: true,
+            ..Default::default()
+        });
+        let block = Box::<dyn Block>::from(state);
+        let door = block.as_any().downcast_ref::<OakDoorBlock>().unwrap();
+        assert!(door.open);
+        assert!(block.as_any().downcast_ref::<IronDoorBlock>().is_none());
+    }
 }
@@ -2,6 +2,36 @@ use proc_macro::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{self, parse_macro_input, Data, DeriveInput, FieldsNamed, Ident};
 
+/// Get the discriminant of an enum variant from `#[discriminant = N]` or
+/// `Variant = N`, or `None` if it's implicit.
+fn variant_discriminant(variant: &syn::Variant) -> Option<u32> {
+    if let Some(attr) = variant
+        .attrs
+        .iter()
+        .find(|a| a.path.is_ident("discriminant"))
+    {
+        return match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Int(i),
+                ..
+            })) => Some(i.base10_parse().unwrap()),
+            _ => panic!("#[discriminant] should be an int, like #[discriminant = 2]"),
+        };
+    }
+    variant.discriminant.as_ref().map(|d| match &d.1 {
+        syn::Expr::Lit(e) => match &e.lit {
+            syn::Lit::Int(i) => i.base10_parse().unwrap(),
+            _ => panic!("Error parsing enum discriminant as int"),
+        },
+        syn::Expr::Unary(_) => {
+            panic!("Negative enum discriminants are not supported")
+        }
+        _ => {
+            panic!("Error parsing enum discriminant as literal (is {:?})", d.1)
+        }
+    })
+}
+
 fn create_impl_mcbufreadable(ident: &Ident, data: &Data) -> proc_macro2::TokenStream {
     match data {
         syn::Data::Struct(syn::DataStruct { fields, .. }) => {
@@ -19,7 +49,14 @@ fn create_impl_mcbufreadable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
                     // if it's a string, use buf.write_string
                     match field_type {
                         syn::Type::Path(_) | syn::Type::Array(_) => {
-                            if f.attrs.iter().any(|a| a.path.is_ident("var")) {
+                            if let Some(max_len) = f.attrs.iter().find(|a| a.path.is_ident("max_len")) {
+                                let max_len: syn::LitInt = max_len
+                                    .parse_args()
+                                    .expect("#[max_len] should contain an integer, like #[max_len(256)]");
+                                quote! {
+                                    let #field_name = azalea_buf::read_utf_with_len(buf, #max_len)?;
+                                }
+                            } else if f.attrs.iter().any(|a| a.path.is_ident("var")) {
                                 quote! {
                                     let #field_name = azalea_buf::McBufVarReadable::var_read_from(buf)?;
                                 }
@@ -54,24 +91,13 @@ fn create_impl_mcbufreadable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
             let mut match_contents = quote!();
             let mut variant_discrim: u32 = 0;
             let mut first = true;
-            let mut first_reader = None;
+            // the variant that unknown ids are read as, if there's one marked
+            // with #[fallback]
+            let mut fallback_reader = None;
             for variant in variants {
                 let variant_name = &variant.ident;
-                match &variant.discriminant.as_ref() {
-                    Some(d) => {
-                        variant_discrim = match &d.1 {
-                            syn::Expr::Lit(e) => match &e.lit {
-                                syn::Lit::Int(i) => i.base10_parse().unwrap(),
-                                _ => panic!("Error parsing enum discriminant as int"),
-                            },
-                            syn::Expr::Unary(_) => {
-                                panic!("Negative enum discriminants are not supported")
-                            }
-                            _ => {
-                                panic!("Error parsing enum discriminant as literal (is {:?})", d.1)
-                            }
-                        }
-                    }
+                match variant_discriminant(variant) {
+                    Some(d) => variant_discrim = d,
                     None => {
                         if !first {
                             variant_discrim += 1;
@@ -89,10 +115,10 @@ fn create_impl_mcbufreadable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
                         Ok(Self::#variant_name)
                     },
                 };
-                if first {
-                    first_reader = Some(reader.clone());
-                    first = false;
-                };
+                first = false;
+                if variant.attrs.iter().any(|a| a.path.is_ident("fallback")) {
+                    fallback_reader = Some(reader.clone());
+                }
 
                 match_contents.extend(quote! {
                     #variant_discrim => {
@@ -101,17 +127,20 @@ fn create_impl_mcbufreadable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
                 });
             }
 
-            let first_reader = first_reader.expect("There should be at least one variant");
+            let fallback_reader = fallback_reader.unwrap_or_else(|| {
+                quote! {
+                    Err(azalea_buf::BufReadError::UnexpectedEnumVariant { id: id as i32 })
+                }
+            });
 
             quote! {
             impl azalea_buf::McBufReadable for #ident {
                 fn read_from(buf: &mut std::io::Cursor<&[u8]>) -> Result<Self, azalea_buf::BufReadError>
                 {
-                    let id = azalea_buf::McBufVarReadable::var_read_from(buf)?;
+                    let id: u32 = azalea_buf::McBufVarReadable::var_read_from(buf)?;
                     match id {
                         #match_contents
-                        // you'd THINK this throws an error, but mojang decided to make it default for some reason
-                        _ => #first_reader
+                        _ => #fallback_reader
                     }
                 }
             }
@@ -169,25 +198,22 @@ fn create_impl_mcbufwritable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
         syn::Data::Enum(syn::DataEnum { variants, .. }) => {
             // remember whether it's a data variant so we can do an optimization later
             let mut is_data_enum = false;
+            let mut has_discriminant_attribute = false;
             let mut match_arms = quote!();
             let mut variant_discrim: u32 = 0;
             let mut first = true;
             for variant in variants {
-                match &variant.discriminant.as_ref() {
-                    Some(d) => {
-                        variant_discrim = match &d.1 {
-                            syn::Expr::Lit(e) => match &e.lit {
-                                syn::Lit::Int(i) => i.base10_parse().unwrap(),
-                                _ => panic!("Error parsing enum discriminant as int"),
-                            },
-                            syn::Expr::Unary(_) => {
-                                panic!("Negative enum discriminants are not supported")
-                            }
-                            _ => {
-                                panic!("Error parsing enum discriminant as literal (is {:?})", d.1)
-                            }
-                        }
-                    }
+                if variant
+                    .attrs
+                    .iter()
+                    .any(|a| a.path.is_ident("discriminant"))
+                {
+                    // the discriminant is different from the one rust uses, so
+                    // we can't do `as u32`
+                    has_discriminant_attribute = true;
+                }
+                match variant_discriminant(variant) {
+                    Some(d) => variant_discrim = d,
                     None => {
                         if first {
                             first = false;
@@ -221,7 +247,7 @@ fn create_impl_mcbufwritable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
                     }
                 }
             }
-            if is_data_enum {
+            if is_data_enum || has_discriminant_attribute {
                 quote! {
                     impl azalea_buf::McBufWritable for #ident {
                         fn write_into(&self, buf: &mut impl std::io::Write) -> Result<(), std::io::Error> {
@@ -247,21 +273,21 @@ fn create_impl_mcbufwritable(ident: &Ident, data: &Data) -> proc_macro2::TokenSt
     }
 }
 
-#[proc_macro_derive(McBufReadable, attributes(var))]
+#[proc_macro_derive(McBufReadable, attributes(var, max_len, discriminant, fallback))]
 pub fn derive_mcbufreadable(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
 
     create_impl_mcbufreadable(&ident, &data).into()
 }
 
-#[proc_macro_derive(McBufWritable, attributes(var))]
+#[proc_macro_derive(McBufWritable, attributes(var, max_len, discriminant, fallback))]
 pub fn derive_mcbufwritable(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
 
     create_impl_mcbufwritable(&ident, &data).into()
 }
 
-#[proc_macro_derive(McBuf, attributes(var))]
+#[proc_macro_derive(McBuf, attributes(var, max_len, discriminant, fallback))]
 pub fn derive_mcbuf(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
 
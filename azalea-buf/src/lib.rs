@@ -12,9 +12,12 @@ mod write;
 
 pub use azalea_buf_macros::*;
 pub use definitions::*;
-pub use read::{BufReadError, McBufReadable, McBufVarReadable};
+pub use read::{
+    read_utf_with_len, BufReadError, McBufReadable, McBufVarReadable, MAX_VAR_INT_LENGTH,
+    MAX_VAR_LONG_LENGTH,
+};
 pub use serializable_uuid::*;
-pub use write::{McBufVarWritable, McBufWritable};
+pub use write::{varint_len, McBufVarWritable, McBufWritable};
 
 // const DEFAULT_NBT_QUOTA: u32 = 2097152;
 const MAX_STRING_LENGTH: u16 = 32767;
@@ -142,6 +145,61 @@ mod tests {
         assert_eq!(i32::var_read_from(&mut Cursor::new(&buf)).unwrap(), 7178);
     }
 
+    #[test]
+    fn test_max_size_varint() {
+        let mut buf = Vec::new();
+        (-1).var_write_into(&mut buf).unwrap();
+        assert_eq!(buf, vec![0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(buf.len(), MAX_VAR_INT_LENGTH);
+        assert_eq!(i32::var_read_from(&mut Cursor::new(&buf)).unwrap(), -1);
+    }
+
+    #[test]
+    fn test_too_long_varint() {
+        // the fifth byte says there's another one, which isn't allowed
+        let buf = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        assert!(matches!(
+            i32::var_read_from(&mut Cursor::new(&buf)),
+            Err(BufReadError::InvalidVarInt)
+        ));
+
+        let buf = vec![0xff; 11];
+        assert!(matches!(
+            i64::var_read_from(&mut Cursor::new(&buf)),
+            Err(BufReadError::InvalidVarLong)
+        ));
+    }
+
+    #[test]
+    fn test_varint_len() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            25565,
+            2097151,
+            2097152,
+            i32::MAX,
+            -1,
+            i32::MIN,
+        ] {
+            let mut buf = Vec::new();
+            value.var_write_into(&mut buf).unwrap();
+            assert_eq!(varint_len(value as u32), buf.len());
+        }
+    }
+
+    #[test]
+    fn test_varlong() {
+        for value in [0, 1, 300, i64::MAX, -1, i64::MIN] {
+            let mut buf = Vec::new();
+            value.var_write_into(&mut buf).unwrap();
+            assert!(buf.len() <= MAX_VAR_LONG_LENGTH);
+            assert_eq!(i64::var_read_from(&mut Cursor::new(&buf)).unwrap(), value);
+        }
+    }
+
     #[test]
     fn test_list() {
         let original_vec = vec!["a".to_string(), "bc".to_string(), "def".to_string()];
@@ -186,4 +244,64 @@ mod tests {
 
         assert_eq!(u64::read_from(&mut Cursor::new(&buf)).unwrap(), 123456);
     }
+
+    #[test]
+    fn test_string_declared_length_too_long() {
+        let mut buf = Vec::new();
+        (MAX_STRING_LENGTH as u32 * 4 + 1)
+            .var_write_into(&mut buf)
+            .unwrap();
+        assert!(matches!(
+            String::read_from(&mut Cursor::new(&buf)),
+            Err(BufReadError::StringLengthTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_string_invalid_utf8() {
+        let buf = vec![2, 0xc3, 0x28];
+        assert!(matches!(
+            String::read_from(&mut Cursor::new(&buf)),
+            Err(BufReadError::InvalidUtf8)
+        ));
+    }
+
+    #[test]
+    fn test_string_max_len() {
+        let mut buf = Vec::new();
+        "abcde".to_string().write_into(&mut buf).unwrap();
+        assert_eq!(
+            read_utf_with_len(&mut Cursor::new(&buf), 5).unwrap(),
+            "abcde"
+        );
+        assert!(matches!(
+            read_utf_with_len(&mut Cursor::new(&buf), 4),
+            Err(BufReadError::StringLengthTooLong {
+                length: 5,
+                max_length: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_byte_array() {
+        let mut buf = Vec::new();
+        [1u8, 2, 3, 4].write_into(&mut buf).unwrap();
+        // fixed size arrays don't have a length prefix
+        assert_eq!(buf, vec![1, 2, 3, 4]);
+
+        let result = <[u8; 4]>::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(result, [1, 2, 3, 4]);
+        assert!(<[u8; 5]>::read_from(&mut Cursor::new(&buf)).is_err());
+    }
+
+    #[test]
+    fn test_array() {
+        let original = ["a".to_string(), "bc".to_string(), "def".to_string()];
+        let mut buf = Vec::new();
+        original.write_into(&mut buf).unwrap();
+
+        let result = <[String; 3]>::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(result, original);
+    }
 }
@@ -7,6 +7,11 @@ use std::{
 };
 use thiserror::Error;
 
+/// The most bytes a VarInt can take up.
+pub const MAX_VAR_INT_LENGTH: usize = 5;
+/// The most bytes a VarLong can take up.
+pub const MAX_VAR_LONG_LENGTH: usize = 10;
+
 #[derive(Error, Debug)]
 pub enum BufReadError {
     #[error("Invalid VarInt")]
@@ -54,9 +59,12 @@ fn read_bytes<'a>(buf: &'a mut Cursor<&[u8]>, length: usize) -> Result<&'a [u8],
     Ok(data)
 }
 
-fn read_utf_with_len(buf: &mut Cursor<&[u8]>, max_length: u32) -> Result<String, BufReadError> {
+/// Read a string, returning an error if it's longer than `max_length`
+/// characters. This is what `#[max_len(N)]` uses in `#[derive(McBuf)]`.
+pub fn read_utf_with_len(buf: &mut Cursor<&[u8]>, max_length: u32) -> Result<String, BufReadError> {
     let length = u32::var_read_from(buf)?;
-    // i don't know why it's multiplied by 4 but it's like that in mojang's code so
+    // a character can be up to 4 bytes, so this is the longest the encoded
+    // string can be
     if length > max_length * 4 {
         return Err(BufReadError::StringLengthTooLong {
             length,
@@ -68,8 +76,13 @@ fn read_utf_with_len(buf: &mut Cursor<&[u8]>, max_length: u32) -> Result<String,
     let string = std::str::from_utf8(buffer)
         .map_err(|_| BufReadError::InvalidUtf8)?
         .to_string();
-    if string.len() > length as usize {
-        return Err(BufReadError::StringLengthTooLong { length, max_length });
+    // java strings are utf-16, so that's how the vanilla length is counted
+    let string_length = string.encode_utf16().count() as u32;
+    if string_length > max_length {
+        return Err(BufReadError::StringLengthTooLong {
+            length: string_length,
+            max_length,
+        });
     }
 
     Ok(string)
@@ -117,15 +130,21 @@ impl McBufVarReadable for i32 {
     /// Read a single varint from the reader and return the value
     fn var_read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
         let mut buffer = [0];
-        let mut ans = 0;
-        for i in 0..5 {
+        buf.read_exact(&mut buffer)?;
+        // most varints are a single byte
+        if buffer[0] & 0b1000_0000 == 0 {
+            return Ok(buffer[0] as i32);
+        }
+        let mut ans = (buffer[0] & 0b0111_1111) as i32;
+        for i in 1..MAX_VAR_INT_LENGTH {
             buf.read_exact(&mut buffer)?;
             ans |= ((buffer[0] & 0b0111_1111) as i32) << (7 * i);
             if buffer[0] & 0b1000_0000 == 0 {
-                break;
+                return Ok(ans);
             }
         }
-        Ok(ans)
+        // the last byte still said there was more, so it's too long
+        Err(BufReadError::InvalidVarInt)
     }
 }
 
@@ -134,15 +153,15 @@ impl McBufVarReadable for i64 {
     fn var_read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
         let mut buffer = [0];
         let mut ans = 0;
-        for i in 0..8 {
+        for i in 0..MAX_VAR_LONG_LENGTH {
             buf.read_exact(&mut buffer)
                 .map_err(|_| BufReadError::InvalidVarLong)?;
             ans |= ((buffer[0] & 0b0111_1111) as i64) << (7 * i);
             if buffer[0] & 0b1000_0000 == 0 {
-                break;
+                return Ok(ans);
             }
         }
-        Ok(ans)
+        Err(BufReadError::InvalidVarLong)
     }
 }
 impl McBufVarReadable for u64 {
@@ -327,3 +346,12 @@ impl<T: McBufReadable, const N: usize> McBufReadable for [T; N] {
         })
     }
 }
+
+// [u8; N], read all at once since it's common for things like hashes
+impl<const N: usize> McBufReadable for [u8; N] {
+    fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
+        let mut contents = [0; N];
+        contents.copy_from_slice(read_bytes(buf, N)?);
+        Ok(contents)
+    }
+}
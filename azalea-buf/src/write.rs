@@ -1,4 +1,4 @@
-use super::{UnsizedByteArray, MAX_STRING_LENGTH};
+use super::{UnsizedByteArray, MAX_STRING_LENGTH, MAX_VAR_INT_LENGTH, MAX_VAR_LONG_LENGTH};
 use byteorder::{BigEndian, WriteBytesExt};
 use std::{collections::HashMap, io::Write};
 
@@ -32,22 +32,32 @@ impl McBufWritable for i32 {
     }
 }
 
+/// Get how many bytes a VarInt will take up when it's written, without
+/// writing it.
+pub fn varint_len(value: u32) -> usize {
+    let bits = 32 - value.leading_zeros() as usize;
+    // zero still takes up a byte
+    ((bits + 6) / 7).max(1)
+}
+
 impl McBufVarWritable for i32 {
     fn var_write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
-        let mut buffer = [0];
-        let mut value = *self;
-        if value == 0 {
-            buf.write_all(&buffer).unwrap();
+        let mut value = *self as u32;
+        // most varints are a single byte
+        if value & !0b0111_1111 == 0 {
+            return buf.write_all(&[value as u8]);
         }
+        let mut buffer = [0; MAX_VAR_INT_LENGTH];
+        let mut len = 0;
         while value != 0 {
-            buffer[0] = (value & 0b0111_1111) as u8;
-            value = (value >> 7) & (i32::max_value() >> 6);
+            buffer[len] = (value & 0b0111_1111) as u8;
+            value >>= 7;
             if value != 0 {
-                buffer[0] |= 0b1000_0000;
+                buffer[len] |= 0b1000_0000;
             }
-            buf.write_all(&buffer)?;
+            len += 1;
         }
-        Ok(())
+        buf.write_all(&buffer[..len])
     }
 }
 
@@ -124,19 +134,19 @@ impl McBufVarWritable for u32 {
 
 impl McBufVarWritable for i64 {
     fn var_write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
-        let mut buffer = [0];
-        let mut value = *self;
-        while value != 0 {
-            buffer[0] = (value & 0b0111_1111) as u8;
-            value = (value >> 7) & (i64::max_value() >> 6);
-            if value != 0 {
-                buffer[0] |= 0b1000_0000;
+        let mut value = *self as u64;
+        let mut buffer = [0; MAX_VAR_LONG_LENGTH];
+        let mut len = 0;
+        loop {
+            buffer[len] = (value & 0b0111_1111) as u8;
+            value >>= 7;
+            len += 1;
+            if value == 0 {
+                break;
             }
-            // this only writes a single byte, so write_all isn't necessary
-            // the let _ = is so clippy doesn't complain
-            let _ = buf.write(&buffer)?;
+            buffer[len - 1] |= 0b1000_0000;
         }
-        Ok(())
+        buf.write_all(&buffer[..len])
     }
 }
 
@@ -250,3 +260,10 @@ impl<T: McBufWritable, const N: usize> McBufWritable for [T; N] {
         Ok(())
     }
 }
+
+// [u8; N]
+impl<const N: usize> McBufWritable for [u8; N] {
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        buf.write_all(self)
+    }
+}
@@ -0,0 +1,23 @@
+use azalea_buf::{McBuf, McBufReadable, McBufWritable};
+use std::io::Cursor;
+
+#[test]
+fn test_discriminant_attribute() {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, McBuf)]
+    enum Skipping {
+        First,
+        #[discriminant = 5]
+        Fifth,
+        Sixth,
+    }
+
+    let mut buf = Vec::new();
+    Skipping::Sixth.write_into(&mut buf).unwrap();
+    assert_eq!(buf, vec![6]);
+    assert_eq!(
+        Skipping::read_from(&mut Cursor::new(&[5])).unwrap(),
+        Skipping::Fifth
+    );
+    assert!(Skipping::read_from(&mut Cursor::new(&[1])).is_err());
+    assert_eq!(Skipping::First as u32, 0);
+}
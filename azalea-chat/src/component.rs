@@ -4,10 +4,14 @@ use std::{
 };
 
 use azalea_buf::{BufReadError, McBufReadable, McBufWritable};
-use serde::{de, Deserialize, Deserializer};
+use regex::Regex;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 use crate::{
     base_component::BaseComponent,
+    keybind_component::KeybindComponent,
+    score_component::ScoreComponent,
     style::{ChatFormatting, Style},
     text_component::TextComponent,
     translatable_component::{StringOrComponent, TranslatableComponent},
@@ -17,6 +21,8 @@ use crate::{
 pub enum Component {
     Text(TextComponent),
     Translatable(TranslatableComponent),
+    Keybind(KeybindComponent),
+    Score(ScoreComponent),
 }
 
 lazy_static! {
@@ -28,10 +34,17 @@ lazy_static! {
 
 /// A chat component
 impl Component {
+    /// Create a plain text component with the default style.
+    pub fn text(text: impl Into<String>) -> Self {
+        Component::Text(TextComponent::new(text.into()))
+    }
+
     pub fn get_base_mut(&mut self) -> &mut BaseComponent {
         match self {
             Self::Text(c) => &mut c.base,
             Self::Translatable(c) => &mut c.base,
+            Self::Keybind(c) => &mut c.base,
+            Self::Score(c) => &mut c.base,
         }
     }
 
@@ -39,6 +52,8 @@ impl Component {
         match self {
             Self::Text(c) => &c.base,
             Self::Translatable(c) => &c.base,
+            Self::Keybind(c) => &c.base,
+            Self::Score(c) => &c.base,
         }
     }
 
@@ -57,7 +72,21 @@ impl Component {
         Ok(None)
     }
 
-    /// Convert this component into an ansi string
+    /// Get the plain text of this component, without its siblings.
+    fn text_without_siblings(&self) -> String {
+        match self {
+            Self::Text(c) => c.text.to_string(),
+            Self::Translatable(c) => c.to_string(),
+            Self::Keybind(c) => c.to_string(),
+            Self::Score(c) => c.to_string(),
+        }
+    }
+
+    /// Convert this component into an ansi string.
+    ///
+    /// Unlike [`Component::visit`], the style carries over from each
+    /// component to the next one rather than only to its siblings, since the
+    /// ansi codes are only written when the style changes.
     pub fn to_ansi(&self, default_style: Option<&Style>) -> String {
         // default the default_style to white if it's not set
         let default_style: &Style = default_style.unwrap_or(&DEFAULT_STYLE);
@@ -68,10 +97,7 @@ impl Component {
         let mut running_style = Style::default();
 
         for component in self.clone().into_iter() {
-            let component_text = match &component {
-                Self::Text(c) => c.text.to_string(),
-                Self::Translatable(c) => c.to_string(),
-            };
+            let component_text = component.text_without_siblings();
 
             let component_style = &component.get_base().style;
 
@@ -88,6 +114,97 @@ impl Component {
 
         built_string
     }
+
+    /// Call `f` with every piece of text in this component in order, along
+    /// with the style it should be shown with. Siblings inherit the style of
+    /// their parent, and empty text is skipped.
+    ///
+    /// This is what [`Component::to_html`] and the [`Display`] impl are built
+    /// on, so it's useful for rendering components in your own format.
+    pub fn visit(&self, f: &mut impl FnMut(&str, &Style)) {
+        self.visit_with_parent_style(&Style::default(), f);
+    }
+
+    fn visit_with_parent_style(&self, parent_style: &Style, f: &mut impl FnMut(&str, &Style)) {
+        let component_style = &self.get_base().style;
+        let mut style = if component_style.reset {
+            Style::default()
+        } else {
+            parent_style.clone()
+        };
+        style.apply(component_style);
+
+        let component_text = self.text_without_siblings();
+        if !component_text.is_empty() {
+            f(&component_text, &style);
+        }
+
+        for sibling in &self.get_base().siblings {
+            sibling.visit_with_parent_style(&style, f);
+        }
+    }
+
+    /// Convert this component into HTML, with a `<span>` around every part
+    /// that's styled. Obfuscated text gets the `obfuscated` class instead of
+    /// being randomized, so you can style it however you want.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        self.visit(&mut |text, style| {
+            let escaped_text = escape_html(text);
+            let css = style.to_css();
+            let obfuscated = style.obfuscated == Some(true);
+            if css.is_empty() && !obfuscated {
+                html.push_str(&escaped_text);
+            } else {
+                html.push_str("<span");
+                if obfuscated {
+                    html.push_str(" class=\"obfuscated\"");
+                }
+                if !css.is_empty() {
+                    html.push_str(&format!(" style=\"{css}\""));
+                }
+                html.push('>');
+                html.push_str(&escaped_text);
+                html.push_str("</span>");
+            }
+        });
+        html
+    }
+
+    /// Whether the plain text of this component contains the given string.
+    /// Translatable components are checked against their translated text, or
+    /// the translation key if we don't know the translation.
+    pub fn contains(&self, needle: &str) -> bool {
+        self.to_string().contains(needle)
+    }
+
+    /// Like [`Component::contains`], but ignoring case.
+    pub fn contains_ignore_case(&self, needle: &str) -> bool {
+        self.to_string()
+            .to_lowercase()
+            .contains(&needle.to_lowercase())
+    }
+
+    /// Whether the regex matches anywhere in the plain text of this component.
+    pub fn matches_regex(&self, re: &Regex) -> bool {
+        re.is_match(&self.to_string())
+    }
+}
+
+/// Escape the characters that have a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 impl IntoIterator for Component {
@@ -163,24 +280,35 @@ impl<'de> Deserialize<'de> for Component {
                         Component::Translatable(TranslatableComponent::new(translate, Vec::new()));
                 }
             } else if let Some(score) = json.get("score") {
-                // object = GsonHelper.getAsJsonObject(jsonObject, "score");
-                if score.get("name").is_none() || score.get("objective").is_none() {
-                    return Err(de::Error::missing_field(
-                        "A score component needs at least a name and an objective",
-                    ));
-                }
-                // TODO
-                return Err(de::Error::custom(
-                    "score text components aren't yet supported",
+                let (name, objective) = match (
+                    score.get("name").and_then(|v| v.as_str()),
+                    score.get("objective").and_then(|v| v.as_str()),
+                ) {
+                    (Some(name), Some(objective)) => (name, objective),
+                    _ => {
+                        return Err(de::Error::missing_field(
+                            "A score component needs at least a name and an objective",
+                        ))
+                    }
+                };
+                let value = score
+                    .get("value")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string());
+                component = Component::Score(ScoreComponent::new(
+                    name.to_string(),
+                    objective.to_string(),
+                    value,
                 ));
             } else if json.get("selector").is_some() {
                 return Err(de::Error::custom(
                     "selector text components aren't yet supported",
                 ));
-            } else if json.get("keybind").is_some() {
-                return Err(de::Error::custom(
-                    "keybind text components aren't yet supported",
-                ));
+            } else if let Some(keybind) = json.get("keybind") {
+                let keybind = keybind
+                    .as_str()
+                    .ok_or_else(|| de::Error::custom("\"keybind\" must be a string"))?;
+                component = Component::Keybind(KeybindComponent::new(keybind.to_string()));
             } else {
                 let _nbt = if let Some(nbt) = json.get("nbt") {
                     nbt
@@ -247,12 +375,63 @@ impl McBufReadable for Component {
     }
 }
 
+impl Serialize for Component {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut json = serde_json::Map::new();
+        match self {
+            Self::Text(c) => {
+                json.insert("text".to_string(), Value::String(c.text.clone()));
+            }
+            Self::Translatable(c) => {
+                json.insert("translate".to_string(), Value::String(c.key.clone()));
+                if !c.args.is_empty() {
+                    let mut with = Vec::with_capacity(c.args.len());
+                    for arg in &c.args {
+                        with.push(match arg {
+                            StringOrComponent::String(s) => Value::String(s.clone()),
+                            StringOrComponent::Component(c) => {
+                                serde_json::to_value(c).map_err(ser::Error::custom)?
+                            }
+                        });
+                    }
+                    json.insert("with".to_string(), Value::Array(with));
+                }
+            }
+            Self::Keybind(c) => {
+                json.insert("keybind".to_string(), Value::String(c.keybind.clone()));
+            }
+            Self::Score(c) => {
+                let mut score = serde_json::Map::new();
+                score.insert("name".to_string(), Value::String(c.name.clone()));
+                score.insert("objective".to_string(), Value::String(c.objective.clone()));
+                if let Some(value) = &c.value {
+                    score.insert("value".to_string(), Value::String(value.clone()));
+                }
+                json.insert("score".to_string(), Value::Object(score));
+            }
+        }
+
+        let base = self.get_base();
+        json.extend(base.style.serialize());
+        if !base.siblings.is_empty() {
+            let mut extra = Vec::with_capacity(base.siblings.len());
+            for sibling in &base.siblings {
+                extra.push(serde_json::to_value(sibling).map_err(ser::Error::custom)?);
+            }
+            json.insert("extra".to_string(), Value::Array(extra));
+        }
+
+        json.serialize(serializer)
+    }
+}
+
 impl McBufWritable for Component {
-    fn write_into(&self, _buf: &mut impl Write) -> Result<(), std::io::Error> {
-        // let json = serde_json::to_string(self).unwrap();
-        // json.write_into(_buf);
-        // Ok(())
-        todo!()
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string(self)?;
+        json.write_into(buf)
     }
 }
 
@@ -267,16 +446,12 @@ impl From<String> for Component {
 
 impl Display for Component {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // this contains the final string will all the ansi escape codes
-        for component in self.clone().into_iter() {
-            let component_text = match &component {
-                Self::Text(c) => c.text.to_string(),
-                Self::Translatable(c) => c.to_string(),
-            };
-
-            f.write_str(&component_text)?;
-        }
-
-        Ok(())
+        let mut result = Ok(());
+        self.visit(&mut |text, _| {
+            if result.is_ok() {
+                result = f.write_str(text);
+            }
+        });
+        result
     }
 }
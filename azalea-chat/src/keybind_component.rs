@@ -0,0 +1,27 @@
+use std::fmt;
+
+use crate::base_component::BaseComponent;
+
+/// A component that shows the key that's bound to an action, like
+/// `key.jump`.
+#[derive(Clone, Debug)]
+pub struct KeybindComponent {
+    pub base: BaseComponent,
+    pub keybind: String,
+}
+
+impl KeybindComponent {
+    pub fn new(keybind: String) -> Self {
+        Self {
+            base: BaseComponent::new(),
+            keybind,
+        }
+    }
+}
+
+impl fmt::Display for KeybindComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // we don't know what keys are bound to what, so just show the keybind
+        write!(f, "{}", self.keybind)
+    }
+}
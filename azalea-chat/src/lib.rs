@@ -6,6 +6,8 @@ extern crate lazy_static;
 
 pub mod base_component;
 pub mod component;
+pub mod keybind_component;
+pub mod score_component;
 pub mod style;
 pub mod text_component;
 pub mod translatable_component;
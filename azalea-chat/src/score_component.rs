@@ -0,0 +1,32 @@
+use std::fmt;
+
+use crate::base_component::BaseComponent;
+
+/// A component that shows the score of an entity for an objective.
+#[derive(Clone, Debug)]
+pub struct ScoreComponent {
+    pub base: BaseComponent,
+    /// The name of the score holder, or a selector like `@p`.
+    pub name: String,
+    pub objective: String,
+    /// The value of the score. The server usually fills this in before
+    /// sending the component to us.
+    pub value: Option<String>,
+}
+
+impl ScoreComponent {
+    pub fn new(name: String, objective: String, value: Option<String>) -> Self {
+        Self {
+            base: BaseComponent::new(),
+            name,
+            objective,
+            value,
+        }
+    }
+}
+
+impl fmt::Display for ScoreComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value.as_deref().unwrap_or(""))
+    }
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, fmt};
 
 use azalea_buf::McBuf;
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct TextColor {
@@ -11,9 +11,11 @@ pub struct TextColor {
 
 impl TextColor {
     pub fn parse(value: String) -> Option<TextColor> {
-        if value.starts_with('#') {
-            let n = value.chars().skip(1).collect::<String>();
-            let n = u32::from_str_radix(&n, 16).unwrap();
+        if let Some(hex) = value.strip_prefix('#') {
+            let n = u32::from_str_radix(hex, 16).ok()?;
+            if n > 0xFFFFFF {
+                return None;
+            }
             return Some(TextColor::from_rgb(n));
         }
         let color_option = NAMED_COLORS.get(&value.to_ascii_uppercase());
@@ -23,9 +25,20 @@ impl TextColor {
         None
     }
 
-    fn from_rgb(value: u32) -> TextColor {
+    /// Create a color that isn't one of the named colors, from a value like
+    /// `0xFF00AA`.
+    pub fn from_rgb(value: u32) -> TextColor {
         TextColor { value, name: None }
     }
+
+    /// Get the red, green, and blue parts of the color.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        (
+            (self.value >> 16) as u8,
+            (self.value >> 8) as u8,
+            self.value as u8,
+        )
+    }
 }
 
 lazy_static! {
@@ -59,6 +72,8 @@ impl Ansi {
     pub const ITALIC: &'static str = "\u{1b}[3m";
     pub const UNDERLINED: &'static str = "\u{1b}[4m";
     pub const STRIKETHROUGH: &'static str = "\u{1b}[9m";
+    /// Terminals can't scramble text like Minecraft does, so obfuscated text
+    /// is concealed instead.
     pub const OBFUSCATED: &'static str = "\u{1b}[8m";
     pub const RESET: &'static str = "\u{1b}[m";
 
@@ -249,6 +264,16 @@ impl TextColor {
     pub fn format(&self) -> String {
         format!("#{:06X}", self.value)
     }
+
+    /// Convert the color into the string used in json components, like `red`
+    /// or `#FF55FF`.
+    pub fn serialize(&self) -> String {
+        if let Some(name) = &self.name {
+            name.to_ascii_lowercase()
+        } else {
+            self.format()
+        }
+    }
 }
 
 impl fmt::Display for TextColor {
@@ -318,6 +343,31 @@ impl Style {
         };
     }
 
+    /// Convert the style into the fields of a json component. Fields that
+    /// aren't set are left out.
+    pub fn serialize(&self) -> Map<String, Value> {
+        let mut json = Map::new();
+        if let Some(color) = &self.color {
+            json.insert("color".to_string(), Value::String(color.serialize()));
+        }
+        if let Some(bold) = self.bold {
+            json.insert("bold".to_string(), Value::Bool(bold));
+        }
+        if let Some(italic) = self.italic {
+            json.insert("italic".to_string(), Value::Bool(italic));
+        }
+        if let Some(underlined) = self.underlined {
+            json.insert("underlined".to_string(), Value::Bool(underlined));
+        }
+        if let Some(strikethrough) = self.strikethrough {
+            json.insert("strikethrough".to_string(), Value::Bool(strikethrough));
+        }
+        if let Some(obfuscated) = self.obfuscated {
+            json.insert("obfuscated".to_string(), Value::Bool(obfuscated));
+        }
+        json
+    }
+
     /// Check if a style has no attributes set
     pub fn is_empty(&self) -> bool {
         self.color.is_none()
@@ -399,6 +449,33 @@ impl Style {
         ansi_codes
     }
 
+    /// Convert the style into inline CSS, like
+    /// `color:#ff5555;font-weight:bold`. Obfuscated text can't be done with
+    /// CSS alone, so it's left out.
+    pub fn to_css(&self) -> String {
+        let mut properties = Vec::new();
+        if let Some(color) = &self.color {
+            properties.push(format!("color:#{:06x}", color.value));
+        }
+        if self.bold == Some(true) {
+            properties.push("font-weight:bold".to_string());
+        }
+        if self.italic == Some(true) {
+            properties.push("font-style:italic".to_string());
+        }
+        let mut decorations = Vec::new();
+        if self.underlined == Some(true) {
+            decorations.push("underline");
+        }
+        if self.strikethrough == Some(true) {
+            decorations.push("line-through");
+        }
+        if !decorations.is_empty() {
+            properties.push(format!("text-decoration:{}", decorations.join(" ")));
+        }
+        properties.join(";")
+    }
+
     /// Apply another style to this one
     pub fn apply(&mut self, style: &Style) {
         if let Some(color) = &style.color {
@@ -456,6 +533,39 @@ mod tests {
             TextColor::parse("#a1b2c3".to_string()).unwrap().value,
             10597059
         );
+        assert_eq!(
+            TextColor::parse("#a1b2c3".to_string()).unwrap().rgb(),
+            (0xa1, 0xb2, 0xc3)
+        );
+    }
+    #[test]
+    fn text_color_invalid_hex_colors() {
+        assert_eq!(TextColor::parse("#ggggggg".to_string()), None);
+        assert_eq!(TextColor::parse("#1000000".to_string()), None);
+    }
+    #[test]
+    fn text_color_round_trip() {
+        let color = TextColor::parse("#ff00aa".to_string()).unwrap();
+        assert_eq!(color.name, None);
+        assert_eq!(color.serialize(), "#FF00AA");
+        assert_eq!(TextColor::parse(color.serialize()), Some(color));
+
+        let color = TextColor::parse("dark_purple".to_string()).unwrap();
+        assert_eq!(color.serialize(), "dark_purple");
+        assert_eq!(TextColor::parse(color.serialize()), Some(color));
+    }
+    #[test]
+    fn style_hex_color_round_trip() {
+        let json: Value = serde_json::from_str(r##"{"color": "#ff00aa", "bold": true}"##).unwrap();
+        let style = Style::deserialize(&json);
+        assert_eq!(style.color, Some(TextColor::from_rgb(0xFF00AA)));
+
+        let serialized = Value::Object(style.serialize());
+        assert_eq!(
+            serialized,
+            serde_json::from_str::<Value>(r##"{"color": "#FF00AA", "bold": true}"##).unwrap()
+        );
+        assert_eq!(Style::deserialize(&serialized).color, style.color);
     }
 
     #[test]
@@ -516,6 +626,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn style_strikethrough_and_obfuscated() {
+        let json: Value =
+            serde_json::from_str(r#"{"strikethrough": true, "obfuscated": false}"#).unwrap();
+        let style = Style::deserialize(&json);
+        assert_eq!(style.strikethrough, Some(true));
+        assert_eq!(style.obfuscated, Some(false));
+
+        // they're inherited unless the child style sets them
+        let mut running_style = Style {
+            obfuscated: Some(true),
+            ..Style::default()
+        };
+        running_style.apply(&style);
+        assert_eq!(running_style.strikethrough, Some(true));
+        assert_eq!(running_style.obfuscated, Some(false));
+        running_style.apply(&Style::default());
+        assert_eq!(running_style.strikethrough, Some(true));
+    }
+
+    #[test]
+    fn ansi_strikethrough_and_obfuscated() {
+        let style = Style {
+            strikethrough: Some(true),
+            obfuscated: Some(true),
+            ..Style::default()
+        };
+        assert_eq!(
+            Style::default().compare_ansi(&style, &Style::default()),
+            format!("{}{}", Ansi::STRIKETHROUGH, Ansi::OBFUSCATED)
+        );
+        assert_eq!(Ansi::STRIKETHROUGH, "\u{1b}[9m");
+    }
+
     #[test]
     fn test_from_code() {
         assert_eq!(
@@ -2,6 +2,7 @@ use azalea_chat::{
     component::Component,
     style::{Ansi, ChatFormatting, TextColor},
 };
+use regex::Regex;
 use serde::Deserialize;
 use serde_json::Value;
 
@@ -73,3 +74,176 @@ fn component_from_string() {
     let component = Component::deserialize(&j).unwrap();
     assert_eq!(component.to_ansi(None), "foo");
 }
+
+#[test]
+fn component_contains() {
+    let j: Value = serde_json::from_str(
+        r#"{
+    "text": "Hello ",
+    "color": "red",
+    "extra": [{"text": "World", "bold": true}]
+}"#,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    assert!(component.contains("Hello World"));
+    assert!(!component.contains("hello world"));
+    assert!(component.contains_ignore_case("hello world"));
+    assert!(component.matches_regex(&Regex::new(r"^Hello \w+$").unwrap()));
+    assert!(!component.matches_regex(&Regex::new(r"^World").unwrap()));
+}
+
+#[test]
+fn translatable_component_contains() {
+    let j: Value = serde_json::from_str(
+        r#"{
+    "translate": "chat.type.text",
+    "with": ["bot", "come here"]
+}"#,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    assert!(component.contains("<bot> come here"));
+    assert!(component.contains_ignore_case("COME HERE"));
+}
+
+#[test]
+fn keybind_and_score_components() {
+    let j: Value = serde_json::from_str(
+        r#"{
+    "text": "Press ",
+    "extra": [
+        {"keybind": "key.jump"},
+        {"text": " to get "},
+        {"score": {"name": "bot", "objective": "points", "value": "10"}}
+    ]
+}"#,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    assert_eq!(component.to_string(), "Press key.jump to get 10");
+}
+
+#[test]
+fn score_component_needs_objective() {
+    let j: Value = serde_json::from_str(r#"{"score": {"name": "bot"}}"#).unwrap();
+    assert!(Component::deserialize(&j).is_err());
+}
+
+#[test]
+fn component_round_trip() {
+    let j: Value = serde_json::from_str(
+        r##"{
+    "translate": "chat.type.text",
+    "with": ["bot", {"text": "hi", "color": "dark_blue", "italic": true}],
+    "bold": true,
+    "extra": [
+        {"keybind": "key.jump", "color": "#FF55AA"},
+        {"score": {"name": "bot", "objective": "points"}}
+    ]
+}"##,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    let serialized = serde_json::to_value(&component).unwrap();
+    assert_eq!(serialized, j);
+
+    let round_tripped = Component::deserialize(&serialized).unwrap();
+    assert_eq!(round_tripped.to_string(), component.to_string());
+    assert_eq!(round_tripped.to_ansi(None), component.to_ansi(None));
+}
+
+#[test]
+fn basic_html_test() {
+    let j: Value = serde_json::from_str(
+        r##"{
+    "text": "<hello>",
+    "color": "red",
+    "bold": true,
+    "extra": [
+        {"text": " & ", "bold": false, "color": "#abcdef"},
+        {"text": "world", "italic": true, "underlined": true, "strikethrough": true},
+        {"text": "?", "obfuscated": true}
+    ]
+}"##,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    assert_eq!(
+        component.to_html(),
+        concat!(
+            r#"<span style="color:#ff5555;font-weight:bold">&lt;hello&gt;</span>"#,
+            r#"<span style="color:#abcdef"> &amp; </span>"#,
+            r#"<span style="color:#ff5555;font-weight:bold;font-style:italic;text-decoration:underline line-through">world</span>"#,
+            r#"<span class="obfuscated" style="color:#ff5555;font-weight:bold">?</span>"#,
+        )
+    );
+}
+
+#[test]
+fn plain_html_test() {
+    let component =
+        Component::deserialize(&Value::String("a \"quote\" 'here'".to_string())).unwrap();
+    assert_eq!(component.to_html(), "a &quot;quote&quot; &#39;here&#39;");
+}
+
+#[test]
+fn strikethrough_obfuscated_ansi_test() {
+    let j: Value = serde_json::from_str(
+        r#"{
+    "text": "secret",
+    "obfuscated": true,
+    "extra": [{"text": " gone", "obfuscated": false, "strikethrough": true}]
+}"#,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    assert_eq!(
+        component.to_ansi(None),
+        format!(
+            "{obfuscated}secret{reset}{strikethrough} gone{reset}",
+            obfuscated = Ansi::OBFUSCATED,
+            strikethrough = Ansi::STRIKETHROUGH,
+            reset = Ansi::RESET,
+        )
+    );
+    // the plain text doesn't change
+    assert_eq!(component.to_string(), "secret gone");
+}
+
+#[test]
+fn visit_test() {
+    let j: Value = serde_json::from_str(
+        r#"{
+    "text": "a",
+    "color": "red",
+    "extra": [
+        {"text": "b", "bold": true, "extra": [{"text": "c"}]},
+        {"text": ""},
+        {"text": "d", "italic": true}
+    ]
+}"#,
+    )
+    .unwrap();
+    let component = Component::deserialize(&j).unwrap();
+    let mut runs = Vec::new();
+    component.visit(&mut |text, style| {
+        runs.push((
+            text.to_string(),
+            style.color.as_ref().map(|c| c.value),
+            style.bold,
+            style.italic,
+        ))
+    });
+    let red = ChatFormatting::Red.color();
+    assert_eq!(
+        runs,
+        vec![
+            ("a".to_string(), red, None, None),
+            ("b".to_string(), red, Some(true), None),
+            ("c".to_string(), red, Some(true), None),
+            // "d" doesn't get "b"'s bold since it isn't inside it
+            ("d".to_string(), red, None, Some(true)),
+        ]
+    );
+}
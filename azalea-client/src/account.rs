@@ -2,7 +2,43 @@
 
 use crate::{client::JoinError, Client, Event};
 use azalea_protocol::ServerAddress;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use thiserror::Error;
 use tokio::sync::mpsc::UnboundedReceiver;
+use uuid::Uuid;
+
+/// The longest username the server will accept.
+const MAX_USERNAME_LENGTH: usize = 16;
+
+/// Mojang's API for getting the profile of a player from their username.
+const PROFILE_BY_USERNAME_URL: &str = "https://api.mojang.com/users/profiles/minecraft";
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Invalid username {0:?}, it must be 1 to 16 characters long")]
+    InvalidUsernameLength(String),
+    #[error("Invalid username {0:?}, it must be 1 to 16 letters, numbers, or underscores")]
+    InvalidUsername(String),
+}
+
+#[derive(Error, Debug)]
+pub enum LookupUuidError {
+    #[error("There's no Minecraft account with the username {0:?}")]
+    NotFound(String),
+    #[error("{0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Mojang sent an invalid uuid: {0}")]
+    InvalidUuid(#[from] uuid::Error),
+    #[error("{0:?} can't be the username of a Minecraft account")]
+    InvalidUsername(String),
+}
+
+#[derive(Deserialize)]
+struct MojangProfile {
+    /// The uuid without dashes.
+    id: String,
+}
 
 /// Something that can join Minecraft servers.
 pub struct Account {
@@ -15,6 +51,43 @@ impl Account {
         }
     }
 
+    /// Check that the server won't reject this account when we log in. This
+    /// is called automatically when joining, so we don't connect just to get
+    /// disconnected.
+    ///
+    /// Offline accounts don't have a token, so this only checks the length
+    /// of the username. Servers and proxies in offline mode often allow other
+    /// characters, so use [`Account::validate_strict`] if you want to know
+    /// whether the name could belong to a real Minecraft account.
+    pub fn validate(&self) -> Result<(), AuthError> {
+        // vanilla counts utf-16 code units, so characters outside the basic
+        // multilingual plane count as two
+        let len = self.username.encode_utf16().count();
+        if (1..=MAX_USERNAME_LENGTH).contains(&len) {
+            Ok(())
+        } else {
+            Err(AuthError::InvalidUsernameLength(self.username.clone()))
+        }
+    }
+
+    /// Like [`Account::validate`], but the username also has to be only
+    /// letters, numbers, and underscores, like the names of Minecraft
+    /// accounts.
+    pub fn validate_strict(&self) -> Result<(), AuthError> {
+        self.validate()?;
+        if is_valid_username(&self.username) {
+            Ok(())
+        } else {
+            Err(AuthError::InvalidUsername(self.username.clone()))
+        }
+    }
+
+    /// Get the uuid of the Minecraft account with this username from
+    /// Mojang's API. This doesn't need to log in, so it works for any player.
+    pub async fn lookup_uuid(username: &str) -> Result<Uuid, LookupUuidError> {
+        lookup_uuid_from(PROFILE_BY_USERNAME_URL, username).await
+    }
+
     /// Joins the Minecraft server on the given address using this account.
     pub async fn join(
         &self,
@@ -23,3 +96,122 @@ impl Account {
         Client::join(self, address).await
     }
 }
+
+/// Whether the username is 1 to 16 letters, numbers, or underscores.
+fn is_valid_username(username: &str) -> bool {
+    !username.is_empty()
+        && username.len() <= MAX_USERNAME_LENGTH
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+async fn lookup_uuid_from(base_url: &str, username: &str) -> Result<Uuid, LookupUuidError> {
+    // the username goes in the path, so anything else could make us request
+    // a different url
+    if !is_valid_username(username) {
+        return Err(LookupUuidError::InvalidUsername(username.to_string()));
+    }
+    let response = reqwest::get(format!("{base_url}/{username}")).await?;
+    // Mojang used to respond with no content when the player doesn't exist,
+    // and now responds with a 404
+    if matches!(
+        response.status(),
+        StatusCode::NO_CONTENT | StatusCode::NOT_FOUND
+    ) {
+        return Err(LookupUuidError::NotFound(username.to_string()));
+    }
+    let profile: MojangProfile = response.error_for_status()?.json().await?;
+    Ok(Uuid::parse_str(&profile.id)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Start a server that answers one HTTP request with the given status
+    /// and body, and return its url.
+    async fn serve_once(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // the request is small, so it should arrive in one read
+            let mut request = [0; 1024];
+            let n = stream.read(&mut request).await.unwrap();
+            assert!(request[..n].starts_with(b"GET /"));
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-length: {}\r\ncontent-type: application/json\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_lookup_uuid() {
+        let url = serve_once(
+            "200 OK",
+            r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"}"#,
+        )
+        .await;
+        assert_eq!(
+            lookup_uuid_from(&url, "Notch").await.unwrap(),
+            Uuid::from_u128(0x069a79f4_44e9_4726_a5be_fca90e38aaf5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lookup_uuid_not_found() {
+        let url = serve_once("204 No Content", "").await;
+        assert!(matches!(
+            lookup_uuid_from(&url, "nobody").await,
+            Err(LookupUuidError::NotFound(username)) if username == "nobody"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_uuid_invalid_username() {
+        // nothing is listening here, so this would fail differently if it
+        // made a request
+        for username in ["../users", "a/b", "a?b", "a#b", ""] {
+            assert!(matches!(
+                lookup_uuid_from("http://127.0.0.1:0", username).await,
+                Err(LookupUuidError::InvalidUsername(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Account::offline("bot_123").validate().is_ok());
+        assert!(Account::offline("abcdefghijklmnop").validate().is_ok());
+        assert!(Account::offline("").validate().is_err());
+        assert!(Account::offline("abcdefghijklmnopq").validate().is_err());
+        // offline servers and proxies can allow other characters
+        assert!(Account::offline("has space").validate().is_ok());
+        assert!(Account::offline("ünicode").validate().is_ok());
+        // each emoji is two utf-16 code units
+        assert!(Account::offline(&"😀".repeat(8)).validate().is_ok());
+        assert!(Account::offline(&"😀".repeat(9)).validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_strict() {
+        assert!(Account::offline("bot_123").validate_strict().is_ok());
+        assert!(matches!(
+            Account::offline("abcdefghijklmnopq").validate_strict(),
+            Err(AuthError::InvalidUsernameLength(_))
+        ));
+        assert!(matches!(
+            Account::offline("has space").validate_strict(),
+            Err(AuthError::InvalidUsername(_))
+        ));
+        assert!(Account::offline("ünicode").validate_strict().is_err());
+    }
+}
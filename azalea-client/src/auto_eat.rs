@@ -0,0 +1,371 @@
+//! Eating automatically when we get hungry.
+
+use crate::{Client, Inventory};
+use azalea_protocol::packets::game::serverbound_interact_packet::InteractionHand;
+use azalea_registry::Item;
+
+/// How many points of the hunger bar eating the item fills, or `None` if it
+/// isn't food.
+pub fn nutrition(item: Item) -> Option<u32> {
+    Some(match item {
+        Item::Apple => 4,
+        Item::BakedPotato => 5,
+        Item::Beef => 3,
+        Item::Beetroot => 1,
+        Item::BeetrootSoup => 6,
+        Item::Bread => 5,
+        Item::Carrot => 3,
+        Item::Chicken => 2,
+        Item::ChorusFruit => 4,
+        Item::Cod => 2,
+        Item::CookedBeef => 8,
+        Item::CookedChicken => 6,
+        Item::CookedCod => 5,
+        Item::CookedMutton => 6,
+        Item::CookedPorkchop => 8,
+        Item::CookedRabbit => 5,
+        Item::CookedSalmon => 6,
+        Item::Cookie => 2,
+        Item::DriedKelp => 1,
+        Item::EnchantedGoldenApple => 4,
+        Item::GlowBerries => 2,
+        Item::GoldenApple => 4,
+        Item::GoldenCarrot => 6,
+        Item::HoneyBottle => 6,
+        Item::MelonSlice => 2,
+        Item::MushroomStew => 6,
+        Item::Mutton => 2,
+        Item::PoisonousPotato => 2,
+        Item::Porkchop => 3,
+        Item::Potato => 1,
+        Item::Pufferfish => 1,
+        Item::PumpkinPie => 8,
+        Item::Rabbit => 3,
+        Item::RabbitStew => 10,
+        Item::RottenFlesh => 4,
+        Item::Salmon => 2,
+        Item::SpiderEye => 2,
+        Item::SuspiciousStew => 6,
+        Item::SweetBerries => 2,
+        Item::TropicalFish => 1,
+        _ => return None,
+    })
+}
+
+/// Whether the item is food that's fine to eat without thinking about it.
+/// Food that can poison us or teleport us is left out, and so are golden
+/// apples since they're usually saved for fights.
+pub(crate) fn is_safe_to_eat(item: Item) -> bool {
+    nutrition(item).is_some()
+        && !matches!(
+            item,
+            Item::Chicken
+                | Item::ChorusFruit
+                | Item::EnchantedGoldenApple
+                | Item::GoldenApple
+                | Item::PoisonousPotato
+                | Item::Pufferfish
+                | Item::RottenFlesh
+                | Item::SpiderEye
+                | Item::SuspiciousStew
+        )
+}
+
+/// How many ticks it takes to eat most food.
+const EAT_DURATION: u32 = 32;
+/// How long we wait for eating to finish before giving up, in case the
+/// server never let us start, like if a plugin cancelled it.
+const MAX_EATING_TICKS: u32 = EAT_DURATION + 20;
+
+/// The settings for eating automatically, and what we're in the middle of.
+#[derive(Clone, Debug)]
+pub struct AutoEat {
+    pub enabled: bool,
+    /// We start eating when our hunger bar is at or below this, from 0 to
+    /// 20. Sprinting stops working at 6 and health stops regenerating below
+    /// 18.
+    pub food_threshold: u32,
+    /// Whether we're eating because of auto eat.
+    eating: bool,
+    /// How many ticks we've been eating for.
+    eating_ticks: u32,
+    /// The hotbar slot we were holding before we switched to the food, so we
+    /// can switch back when we're done.
+    previous_slot: Option<u8>,
+    /// The main inventory slot the food came from, if we swapped it into the
+    /// hotbar slot we're holding, so we can swap it back when we're done.
+    swapped_from: Option<usize>,
+}
+
+impl Default for AutoEat {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            food_threshold: 14,
+            eating: false,
+            eating_ticks: 0,
+            previous_slot: None,
+            swapped_from: None,
+        }
+    }
+}
+
+impl Client {
+    /// Start eating if auto eat is on and we're hungry, or switch back to the
+    /// item we were holding if we're done. This is called every tick.
+    ///
+    /// Food in the main inventory is swapped into the hotbar slot we're
+    /// holding, and swapped back when we're done so we're holding the same
+    /// item as before.
+    ///
+    /// We don't start eating while we're using another item. If eating is
+    /// stopped early with [`Client::release_use_item`], we switch back the
+    /// same as if we'd finished. If the server never finishes eating, we stop
+    /// and switch back after [`MAX_EATING_TICKS`].
+    pub(crate) async fn tick_auto_eat(&self) -> Result<(), std::io::Error> {
+        let using_item = self.is_using_item();
+        let finished = {
+            let mut auto_eat = self.auto_eat.lock();
+            if !auto_eat.eating {
+                None
+            } else if using_item && auto_eat.eating_ticks < MAX_EATING_TICKS {
+                auto_eat.eating_ticks += 1;
+                return Ok(());
+            } else {
+                auto_eat.eating = false;
+                auto_eat.eating_ticks = 0;
+                Some((auto_eat.previous_slot.take(), auto_eat.swapped_from.take()))
+            }
+        };
+        if let Some((previous_slot, swapped_from)) = finished {
+            if using_item {
+                // we've been eating for too long, so the server probably
+                // never started
+                self.release_use_item().await?;
+            }
+            if let Some(slot) = previous_slot {
+                self.set_selected_hotbar_slot(slot).await?;
+            }
+            if let Some(slot) = swapped_from {
+                let selected = self.inventory.lock().selected_hotbar_slot;
+                self.swap_with_hotbar(slot, selected).await?;
+            }
+            return Ok(());
+        }
+
+        let food_threshold = {
+            let auto_eat = self.auto_eat.lock();
+            if !auto_eat.enabled || using_item {
+                return Ok(());
+            }
+            auto_eat.food_threshold
+        };
+        let food = self.local_player.lock().health.food;
+        // the server doesn't let us eat when we're full
+        if food > food_threshold || food >= 20 {
+            return Ok(());
+        }
+        let (food, selected) = {
+            let inventory = self.inventory.lock();
+            (inventory.best_food(), inventory.selected_hotbar_slot)
+        };
+        let food = match food {
+            Some(food) => food,
+            None => return Ok(()),
+        };
+        let (food, swapped_from) = match Inventory::hotbar_slot_of(food) {
+            Some(hotbar_slot) => (hotbar_slot, None),
+            None => {
+                // we can only eat from the hotbar, so swap the food with what
+                // we're holding
+                self.swap_with_hotbar(food, selected).await?;
+                (selected, Some(food))
+            }
+        };
+
+        {
+            let mut auto_eat = self.auto_eat.lock();
+            auto_eat.eating = true;
+            auto_eat.previous_slot = (food != selected).then_some(selected);
+            auto_eat.swapped_from = swapped_from;
+        }
+        if food != selected {
+            self.set_selected_hotbar_slot(food).await?;
+        }
+        self.use_item(InteractionHand::MainHand).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_core::{Slot, SlotData};
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+
+    #[tokio::test]
+    async fn test_auto_eat() {
+        let (client, _rx, mut server) = test_client();
+        {
+            let mut inventory = client.inventory.lock();
+            // the hotbar starts at slot 36
+            inventory.slots[36 + 2] = Slot::Present(SlotData {
+                id: Item::Bread as i32,
+                count: 3,
+                nbt: Default::default(),
+            });
+            inventory.selected_hotbar_slot = 5;
+        }
+        client.local_player.lock().health.food = 10;
+
+        // it's off by default
+        client.tick_auto_eat().await.unwrap();
+        assert!(!client.is_using_item());
+
+        client.auto_eat.lock().enabled = true;
+        client.tick_auto_eat().await.unwrap();
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::SetCarriedItem(p) if p.slot == 2
+        ));
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::UseItem(_)
+        ));
+
+        // still eating
+        client.tick_auto_eat().await.unwrap();
+        assert_eq!(client.inventory.lock().selected_hotbar_slot, 2);
+
+        // the server says we finished eating
+        client.item_use_state.lock().using_item = None;
+        client.local_player.lock().health.food = 15;
+        client.tick_auto_eat().await.unwrap();
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::SetCarriedItem(p) if p.slot == 5
+        ));
+        client.tick_auto_eat().await.unwrap();
+        assert!(!client.is_using_item());
+    }
+
+    #[tokio::test]
+    async fn test_auto_eat_gives_up() {
+        let (client, _rx, mut server) = test_client();
+        {
+            let mut inventory = client.inventory.lock();
+            inventory.slots[36 + 2] = Slot::Present(SlotData {
+                id: Item::Bread as i32,
+                count: 3,
+                nbt: Default::default(),
+            });
+            inventory.selected_hotbar_slot = 5;
+        }
+        client.local_player.lock().health.food = 10;
+        client.auto_eat.lock().enabled = true;
+        client.tick_auto_eat().await.unwrap();
+        server.read().await.unwrap();
+        server.read().await.unwrap();
+
+        // the server never tells us we finished eating
+        for _ in 0..MAX_EATING_TICKS {
+            client.tick_auto_eat().await.unwrap();
+        }
+        assert!(client.is_using_item());
+        client.tick_auto_eat().await.unwrap();
+        assert!(!client.is_using_item());
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::PlayerAction(_)
+        ));
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::SetCarriedItem(p) if p.slot == 5
+        ));
+
+        // we try again on the next tick
+        client.tick_auto_eat().await.unwrap();
+        assert!(client.is_using_item());
+    }
+
+    #[tokio::test]
+    async fn test_auto_eat_from_main_inventory() {
+        let (client, _rx, mut server) = test_client();
+        {
+            let mut inventory = client.inventory.lock();
+            inventory.slots[20] = Slot::Present(SlotData {
+                id: Item::Bread as i32,
+                count: 3,
+                nbt: Default::default(),
+            });
+            inventory.slots[36 + 5] = Slot::Present(SlotData {
+                id: Item::Stick as i32,
+                count: 1,
+                nbt: Default::default(),
+            });
+            inventory.selected_hotbar_slot = 5;
+        }
+        client.local_player.lock().health.food = 10;
+        client.auto_eat.lock().enabled = true;
+        client.tick_auto_eat().await.unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::ContainerClick(p) => {
+                assert_eq!(p.slot_num, 20);
+                assert_eq!(p.button_num, 5);
+                assert!(matches!(
+                    p.changed_slots.get(&41),
+                    Some(Slot::Present(data)) if data.id == Item::Bread as i32
+                ));
+            }
+            p => panic!("Expected a container click packet, got {:?}", p),
+        }
+        // we're already holding the food, so there's no need to switch
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::UseItem(_)
+        ));
+        assert!(client.is_using_item());
+        assert!(matches!(
+            &client.inventory.lock().slots[20],
+            Slot::Present(data) if data.id == Item::Stick as i32
+        ));
+
+        // when we're done, the stick goes back to the slot we're holding
+        client.item_use_state.lock().using_item = None;
+        client.local_player.lock().health.food = 15;
+        client.tick_auto_eat().await.unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::ContainerClick(p) => {
+                assert_eq!(p.slot_num, 20);
+                assert_eq!(p.button_num, 5);
+            }
+            p => panic!("Expected a container click packet, got {:?}", p),
+        }
+        let inventory = client.inventory.lock();
+        assert!(matches!(
+            &inventory.slots[36 + 5],
+            Slot::Present(data) if data.id == Item::Stick as i32
+        ));
+        assert!(matches!(
+            &inventory.slots[20],
+            Slot::Present(data) if data.id == Item::Bread as i32
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_auto_eat_not_when_full() {
+        let (client, _rx, _server) = test_client();
+        client.inventory.lock().slots[36] = Slot::Present(SlotData {
+            id: Item::Bread as i32,
+            count: 3,
+            nbt: Default::default(),
+        });
+        {
+            let mut auto_eat = client.auto_eat.lock();
+            auto_eat.enabled = true;
+            auto_eat.food_threshold = 20;
+        }
+        client.tick_auto_eat().await.unwrap();
+        assert!(!client.is_using_item());
+    }
+}
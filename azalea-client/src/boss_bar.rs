@@ -0,0 +1,127 @@
+use azalea_chat::component::Component;
+use azalea_protocol::packets::game::clientbound_boss_event_packet::{
+    BossBarColor, BossBarOverlay, ClientboundBossEventPacket, Operation, Properties,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A boss bar that's shown at the top of the screen. Servers often use these
+/// to display information that isn't related to bosses at all.
+#[derive(Clone, Debug)]
+pub struct BossBar {
+    pub title: Component,
+    /// How full the bar is, from 0 to 1.
+    pub progress: f32,
+    pub color: BossBarColor,
+    pub overlay: BossBarOverlay,
+    pub properties: Properties,
+}
+
+/// Update the boss bars with the data from a boss event packet. Returns the
+/// boss bar after the update, or `None` if it was removed or we never got told
+/// it exists.
+pub fn apply_boss_event(
+    boss_bars: &mut HashMap<Uuid, BossBar>,
+    packet: &ClientboundBossEventPacket,
+) -> Option<BossBar> {
+    match &packet.operation {
+        Operation::Add(add) => {
+            let boss_bar = BossBar {
+                title: add.name.clone(),
+                progress: add.progress,
+                color: add.style.color,
+                overlay: add.style.overlay,
+                properties: add.properties.clone(),
+            };
+            boss_bars.insert(packet.id, boss_bar.clone());
+            return Some(boss_bar);
+        }
+        Operation::Remove => {
+            boss_bars.remove(&packet.id);
+            return None;
+        }
+        _ => {}
+    }
+
+    let boss_bar = boss_bars.get_mut(&packet.id)?;
+    match &packet.operation {
+        Operation::UpdateProgress(progress) => boss_bar.progress = *progress,
+        Operation::UpdateName(name) => boss_bar.title = name.clone(),
+        Operation::UpdateStyle(style) => {
+            boss_bar.color = style.color;
+            boss_bar.overlay = style.overlay;
+        }
+        Operation::UpdateProperties(properties) => boss_bar.properties = properties.clone(),
+        Operation::Add(_) | Operation::Remove => unreachable!(),
+    }
+    Some(boss_bar.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_protocol::packets::game::clientbound_boss_event_packet::{AddOperation, Style};
+
+    fn packet(id: Uuid, operation: Operation) -> ClientboundBossEventPacket {
+        ClientboundBossEventPacket { id, operation }
+    }
+
+    #[test]
+    fn test_boss_bar_lifecycle() {
+        let id = Uuid::from_u128(1);
+        let mut boss_bars = HashMap::new();
+
+        apply_boss_event(
+            &mut boss_bars,
+            &packet(
+                id,
+                Operation::Add(AddOperation {
+                    name: Component::from("Ender Dragon".to_string()),
+                    progress: 1.0,
+                    style: Style {
+                        color: BossBarColor::Pink,
+                        overlay: BossBarOverlay::Progress,
+                    },
+                    properties: Properties {
+                        darken_screen: false,
+                        play_music: true,
+                        create_world_fog: true,
+                    },
+                }),
+            ),
+        );
+        assert_eq!(boss_bars[&id].title.to_string(), "Ender Dragon");
+
+        let boss_bar =
+            apply_boss_event(&mut boss_bars, &packet(id, Operation::UpdateProgress(0.5))).unwrap();
+        assert_eq!(boss_bar.progress, 0.5);
+
+        let boss_bar = apply_boss_event(
+            &mut boss_bars,
+            &packet(
+                id,
+                Operation::UpdateStyle(Style {
+                    color: BossBarColor::Red,
+                    overlay: BossBarOverlay::Notched10,
+                }),
+            ),
+        )
+        .unwrap();
+        assert_eq!(boss_bar.color, BossBarColor::Red);
+        assert_eq!(boss_bar.overlay, BossBarOverlay::Notched10);
+
+        assert!(apply_boss_event(&mut boss_bars, &packet(id, Operation::Remove)).is_none());
+        assert!(boss_bars.is_empty());
+    }
+
+    #[test]
+    fn test_update_unknown_boss_bar() {
+        let mut boss_bars = HashMap::new();
+        assert!(apply_boss_event(
+            &mut boss_bars,
+            &packet(Uuid::from_u128(2), Operation::UpdateProgress(0.5))
+        )
+        .is_none());
+        assert!(boss_bars.is_empty());
+    }
+}
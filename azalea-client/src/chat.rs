@@ -0,0 +1,165 @@
+use crate::Client;
+use azalea_crypto::MessageSignature;
+use azalea_protocol::packets::game::{
+    clientbound_player_chat_packet::{LastSeenMessagesEntry, LastSeenMessagesUpdate},
+    serverbound_chat_packet::ServerboundChatPacket,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The maximum number of messages we can tell the server we've seen.
+const LAST_SEEN_MESSAGES_MAX_LENGTH: usize = 5;
+/// If we've received more than this many messages without acknowledging them,
+/// we have to send an acknowledgement even if we're not chatting.
+const MAX_UNACKNOWLEDGED_MESSAGES: u32 = 64;
+
+/// Keeps track of the signed messages we've seen recently, since the server
+/// requires us to acknowledge them whenever we send a chat message.
+#[derive(Clone, Debug, Default)]
+pub struct LastSeenMessagesTracker {
+    /// The most recent message from each sender, newest first.
+    last_seen: Vec<LastSeenMessagesEntry>,
+    /// The most recent message we received since we last sent an
+    /// acknowledgement.
+    last_received: Option<LastSeenMessagesEntry>,
+    unacknowledged_count: u32,
+}
+
+impl LastSeenMessagesTracker {
+    /// Mark a message as seen. Returns true if we've received so many
+    /// messages without acknowledging them that we should send a
+    /// `ServerboundChatAckPacket`.
+    pub fn push(&mut self, entry: LastSeenMessagesEntry) -> bool {
+        // only the latest message from each sender is kept
+        self.last_seen
+            .retain(|seen| seen.profile_id != entry.profile_id);
+        self.last_seen.insert(0, entry.clone());
+        self.last_seen.truncate(LAST_SEEN_MESSAGES_MAX_LENGTH);
+        self.last_received = Some(entry);
+
+        self.unacknowledged_count += 1;
+        self.unacknowledged_count > MAX_UNACKNOWLEDGED_MESSAGES
+    }
+
+    /// The messages we've seen, newest first.
+    pub fn last_seen(&self) -> &[LastSeenMessagesEntry] {
+        &self.last_seen
+    }
+
+    /// Create the acknowledgement for the messages we've seen and reset the
+    /// unacknowledged messages.
+    pub fn generate_update(&mut self) -> LastSeenMessagesUpdate {
+        self.unacknowledged_count = 0;
+        LastSeenMessagesUpdate {
+            last_seen: self.last_seen.clone(),
+            last_received: self.last_received.take(),
+        }
+    }
+}
+
+impl Client {
+    /// Send a chat message to the server, signed with the given signature
+    /// and salt. The acknowledgement of the messages we've seen is added
+    /// automatically.
+    ///
+    /// Servers that enforce secure chat will reject the message if the
+    /// signature is invalid.
+    pub async fn send_chat_signed(
+        &self,
+        message: &str,
+        salt: u64,
+        signature: MessageSignature,
+    ) -> Result<(), std::io::Error> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis() as u64;
+        let last_seen_messages = self.last_seen_messages.lock().generate_update();
+        self.write_packet(
+            ServerboundChatPacket {
+                message: message.to_string(),
+                timestamp,
+                salt,
+                signature,
+                signed_preview: false,
+                last_seen_messages,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Send an unsigned chat message to the server. This only works on
+    /// servers that don't enforce secure chat.
+    pub async fn chat(&self, message: &str) -> Result<(), std::io::Error> {
+        self.send_chat_signed(message, 0, MessageSignature::default())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn entry(sender: u128, signature: u8) -> LastSeenMessagesEntry {
+        LastSeenMessagesEntry {
+            profile_id: Uuid::from_u128(sender),
+            last_signature: MessageSignature {
+                bytes: vec![signature],
+            },
+        }
+    }
+
+    fn signatures(tracker: &LastSeenMessagesTracker) -> Vec<u8> {
+        tracker
+            .last_seen()
+            .iter()
+            .map(|e| e.last_signature.bytes[0])
+            .collect()
+    }
+
+    #[test]
+    fn test_keeps_latest_per_sender() {
+        let mut tracker = LastSeenMessagesTracker::default();
+        tracker.push(entry(1, 1));
+        tracker.push(entry(2, 2));
+        tracker.push(entry(1, 3));
+        assert_eq!(signatures(&tracker), vec![3, 2]);
+    }
+
+    #[test]
+    fn test_window_is_limited() {
+        let mut tracker = LastSeenMessagesTracker::default();
+        for i in 0..10 {
+            tracker.push(entry(i as u128, i));
+        }
+        assert_eq!(signatures(&tracker), vec![9, 8, 7, 6, 5]);
+    }
+
+    #[test]
+    fn test_generate_update() {
+        let mut tracker = LastSeenMessagesTracker::default();
+        tracker.push(entry(1, 1));
+        tracker.push(entry(2, 2));
+
+        let update = tracker.generate_update();
+        assert_eq!(update.last_seen.len(), 2);
+        assert_eq!(update.last_received.unwrap().last_signature.bytes, vec![2]);
+
+        // the last received message is only sent once, but the window stays
+        let update = tracker.generate_update();
+        assert_eq!(update.last_seen.len(), 2);
+        assert!(update.last_received.is_none());
+    }
+
+    #[test]
+    fn test_needs_acknowledgement() {
+        let mut tracker = LastSeenMessagesTracker::default();
+        for i in 0..MAX_UNACKNOWLEDGED_MESSAGES {
+            assert!(!tracker.push(entry(1, i as u8)));
+        }
+        assert!(tracker.push(entry(1, 0)));
+        tracker.generate_update();
+        assert!(!tracker.push(entry(1, 0)));
+    }
+}
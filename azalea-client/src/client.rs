@@ -1,18 +1,53 @@
-use crate::{movement::MoveDirection, Account, Player};
+use crate::{
+    account::AuthError,
+    auto_eat::AutoEat,
+    boss_bar::{self, BossBar},
+    chat::LastSeenMessagesTracker,
+    completions,
+    interact::{ItemUseState, FINISHED_USING_ITEM_EVENT_ID},
+    inventory::Inventory,
+    local_player::{Abilities, Health, LocalPlayerState, MobEffectInstance},
+    login_plugin::LoginPluginHandlers,
+    movement::{MoveDirection, MovementInput},
+    pathfinder::{PathfindError, Pathfinder},
+    pending::Pending,
+    resource_pack::ResourcePackPolicy,
+    scheduler::Scheduler,
+    scoreboard::Scoreboard,
+    settings::ClientSettings,
+    sound::SoundKind,
+    subscribe::{EventKind, EventSender, Subscriber},
+    tab_list::TabList,
+    world_info::{Weather, WorldInfo},
+    Account, Player,
+};
 use azalea_auth::game_profile::GameProfile;
 use azalea_chat::component::Component;
-use azalea_core::{ChunkPos, ResourceLocation, Vec3};
+use azalea_core::{BlockPos, ChunkPos, EquipmentSlot, GameType, ResourceLocation, Slot, Vec3};
+use azalea_physics::PhysicsProfile;
 use azalea_protocol::{
-    connect::{Connection, ConnectionError, ReadConnection, WriteConnection},
+    connect::{
+        BoxedReadStream, BoxedWriteStream, Connection, ConnectionError, GameConnection,
+        ReadConnection, WriteConnection,
+    },
     packets::{
         game::{
+            clientbound_animate_packet::AnimationAction,
+            clientbound_boss_event_packet::Operation as BossEventOperation,
+            clientbound_game_event_packet::GameEvent,
+            clientbound_level_particles_packet::ClientboundLevelParticlesPacket,
             clientbound_player_chat_packet::ClientboundPlayerChatPacket,
+            clientbound_player_chat_packet::LastSeenMessagesEntry,
+            clientbound_sound_packet::SoundSource,
             clientbound_system_chat_packet::ClientboundSystemChatPacket,
             serverbound_accept_teleportation_packet::ServerboundAcceptTeleportationPacket,
+            serverbound_chat_ack_packet::ServerboundChatAckPacket,
             serverbound_custom_payload_packet::ServerboundCustomPayloadPacket,
+            serverbound_interact_packet::InteractionHand,
             serverbound_keep_alive_packet::ServerboundKeepAlivePacket,
             serverbound_move_player_pos_rot_packet::ServerboundMovePlayerPosRotPacket,
-            ClientboundGamePacket, ServerboundGamePacket,
+            serverbound_resource_pack_packet::ServerboundResourcePackPacket, ClientboundGamePacket,
+            ServerboundGamePacket,
         },
         handshake::client_intention_packet::ClientIntentionPacket,
         login::{
@@ -23,26 +58,35 @@ use azalea_protocol::{
         ConnectionProtocol, PROTOCOL_VERSION,
     },
     read::ReadPacketError,
-    resolver, ServerAddress,
+    resolver,
+    stats::{ConnectionCounters, ConnectionStats},
+    ServerAddress,
 };
+use azalea_registry::{EntityType, MobEffect};
 use azalea_world::{
     entity::{EntityData, EntityMut, EntityRef},
-    Dimension,
+    BlockEntity, Dimension, RegistryHolder,
 };
+use futures::FutureExt;
 use log::{debug, error, warn};
 use parking_lot::Mutex;
 use std::{
+    any::Any,
+    collections::HashMap,
     fmt::Debug,
+    future::Future,
     io::{self, Cursor},
+    panic::AssertUnwindSafe,
     sync::Arc,
 };
 use thiserror::Error;
 use tokio::{
-    io::AsyncWriteExt,
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::mpsc::{self, UnboundedReceiver},
     task::JoinHandle,
     time::{self},
 };
+use uuid::Uuid;
 
 /// Events are sent before they're processed, so for example game ticks happen
 /// at the beginning of a tick before anything has happened.
@@ -53,6 +97,85 @@ pub enum Event {
     /// Happens 20 times per second, but only when the world is loaded.
     Tick,
     Packet(Box<ClientboundGamePacket>),
+    /// We finished using an item, like eating food or drinking a potion.
+    ItemUseFinished,
+    /// A boss bar was added or changed. The boss bar is `None` if it was
+    /// removed.
+    BossBarUpdate(Uuid, Option<BossBar>),
+    /// An objective, score, or display slot on the scoreboard changed.
+    ScoreboardUpdate,
+    /// The server asked us to use a resource pack. The first string is the
+    /// url and the second is the hash. We respond to it based on
+    /// [`Client::resource_pack_policy`].
+    ResourcePackPrompt(String, String),
+    /// We reached the next block in the path to the goal given to
+    /// [`Client::goto`].
+    PathProgress(BlockPos),
+    /// We reached the goal given to [`Client::goto`].
+    GoalReached(BlockPos),
+    /// There's no way to get to the goal given to [`Client::goto`].
+    PathFailed(BlockPos),
+    /// The server spawned some particles.
+    Particle(Box<ClientboundLevelParticlesPacket>),
+    /// The server played a sound. If the sound is following an entity, `pos`
+    /// is where that entity was when the sound started.
+    Sound {
+        kind: SoundKind,
+        source: SoundSource,
+        pos: Vec3,
+        volume: f32,
+        pitch: f32,
+    },
+    /// A packet couldn't be decoded, or there was an error or panic while
+    /// handling it. The packet was skipped, but we're still connected.
+    HandlerError(String),
+    /// We got a status effect, or an effect we already had changed.
+    EffectAdded(MobEffect, MobEffectInstance),
+    /// One of our status effects ran out or was removed.
+    EffectRemoved(MobEffect),
+    /// Our experience level went up. This is the new level.
+    LevelUp(u32),
+    /// Our game mode was changed.
+    GameModeChanged(GameType),
+    /// It started or stopped raining, or the rain or thunder got stronger or
+    /// weaker.
+    Weather(Weather),
+    /// The server set our velocity, like when we get hit or are near an
+    /// explosion. This is the new velocity in blocks per tick.
+    Knockback(Vec3),
+    /// We were disconnected from the server, either because the server
+    /// kicked us or because [`Client::disconnect`] was called. After
+    /// [`Client::disconnect`] this is the last event, but when we're kicked
+    /// the client keeps running until the server closes the connection, so
+    /// other events like [`Event::Tick`] can still come after it.
+    Disconnect(Component),
+    /// The server sent us a plugin message (also called a custom payload) on
+    /// a channel like `minecraft:brand` or `bungeecord:main`. The data isn't
+    /// parsed, since every channel has its own format.
+    PluginMessage {
+        channel: ResourceLocation,
+        data: Vec<u8>,
+    },
+    /// An entity (which could be us) swung its arm, got hurt, woke up, or
+    /// got hit with a critical hit.
+    EntityAnimation {
+        entity_id: u32,
+        kind: AnimationAction,
+    },
+    /// An entity (which could be us) started holding or wearing different
+    /// items. Only the slots that changed are included, and the entity's
+    /// full equipment is in [`EntityData::equipment`].
+    EntityEquipmentChanged {
+        entity_id: u32,
+        slots: Vec<(EquipmentSlot, Slot)>,
+    },
+    /// The entity with this id was removed from the world, like when it died
+    /// or went out of our render distance.
+    EntityRemoved(u32),
+    /// We moved to a different dimension, like `minecraft:the_nether`. The
+    /// chunks and entities from the old dimension are gone by the time this
+    /// is sent.
+    DimensionChanged(ResourceLocation),
 }
 
 #[derive(Debug, Clone)]
@@ -73,13 +196,53 @@ impl ChatPacket {
 /// A player that you can control that is currently in a Minecraft server.
 #[derive(Clone)]
 pub struct Client {
-    game_profile: GameProfile,
-    pub read_conn: Arc<tokio::sync::Mutex<ReadConnection<ClientboundGamePacket>>>,
-    pub write_conn: Arc<tokio::sync::Mutex<WriteConnection<ServerboundGamePacket>>>,
+    /// The profile that the server accepted when we logged in. The UUID in it
+    /// is the one the server uses for our player.
+    pub game_profile: GameProfile,
+    pub read_conn: Arc<tokio::sync::Mutex<ReadConnection<ClientboundGamePacket, BoxedReadStream>>>,
+    pub write_conn:
+        Arc<tokio::sync::Mutex<WriteConnection<ServerboundGamePacket, BoxedWriteStream>>>,
     pub player: Arc<Mutex<Player>>,
     pub dimension: Arc<Mutex<Dimension>>,
     pub physics_state: Arc<Mutex<PhysicsState>>,
+    pub item_use_state: Arc<Mutex<ItemUseState>>,
+    pub boss_bars: Arc<Mutex<HashMap<Uuid, BossBar>>>,
+    pub scoreboard: Arc<Mutex<Scoreboard>>,
+    pub tab_list: Arc<Mutex<TabList>>,
+    /// The signed chat messages we've seen recently, which we have to
+    /// acknowledge when sending chat messages.
+    pub last_seen_messages: Arc<Mutex<LastSeenMessagesTracker>>,
+    /// How we respond when the server asks us to use a resource pack.
+    pub resource_pack_policy: Arc<Mutex<ResourcePackPolicy>>,
+    /// Whether we eat automatically when we get hungry. This is off by
+    /// default.
+    pub auto_eat: Arc<Mutex<AutoEat>>,
+    /// The brand, language, view distance, and other options we tell the
+    /// server about when we log in.
+    pub settings: Arc<Mutex<ClientSettings>>,
+    /// Where we're walking to with [`Client::goto`].
+    pub pathfinder: Arc<Mutex<Pathfinder>>,
+    /// The dimension, game mode, and other things about the world we're in.
+    /// This is `None` until we get the login packet.
+    pub world_info: Arc<Mutex<Option<WorldInfo>>>,
+    /// The entity data, equipment, and effects of our own player.
+    pub local_player: Arc<Mutex<LocalPlayerState>>,
+    /// The items in our inventory and the hotbar slot we're holding.
+    pub inventory: Arc<Mutex<Inventory>>,
+    /// The dimension types and biomes the server told us about when we
+    /// logged in.
+    pub registries: Arc<Mutex<RegistryHolder>>,
+    /// How many ticks we've run, and the actions waiting to run on later
+    /// ticks.
+    pub scheduler: Arc<Mutex<Scheduler>>,
+    /// The tab completions we asked the server for and are waiting for.
+    pub(crate) pending_completions: Arc<Mutex<Pending<u32, Vec<String>>>>,
+    connection_counters: Arc<ConnectionCounters>,
+    pub(crate) event_subscribers: Arc<Mutex<Vec<Subscriber>>>,
     tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// Used for sending the [`Event::Disconnect`] when we disconnect. This is
+    /// `None` after disconnecting so the event channels can close.
+    event_sender: Arc<Mutex<Option<EventSender>>>,
 }
 
 #[derive(Default)]
@@ -87,14 +250,42 @@ pub struct PhysicsState {
     /// Minecraft only sends a movement packet either after 20 ticks or if the player moved enough. This is that tick counter.
     pub position_remainder: u32,
 
-    pub move_direction: MoveDirection,
+    pub movement_input: MovementInput,
     pub forward_impulse: f32,
     pub left_impulse: f32,
+
+    /// The constants used for simulating our movement. You probably don't
+    /// want to change this unless you know what you're doing.
+    pub physics_profile: PhysicsProfile,
+
+    /// Whether [`Client::send_position`] was already called this tick.
+    pub(crate) sent_position_this_tick: bool,
 }
 
 /// Whether we should ignore errors when decoding packets.
 const IGNORE_ERRORS: bool = !cfg!(debug_assertions);
 
+/// How many packets can be read from the server before they're handled.
+///
+/// Packets are read and handled in separate tasks, so a burst of packets (like
+/// when a lot of chunks are loaded at once) doesn't have to wait for each one
+/// to be handled before the next is read. When the queue is full, we stop
+/// reading until the handler catches up, which lets the server know it should
+/// slow down instead of us buffering packets until we run out of memory. A
+/// bigger queue handles longer bursts smoothly but uses more memory.
+pub const DEFAULT_PACKET_QUEUE_CAPACITY: usize = 256;
+
+/// Get the message a panic was started with, if it has one.
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum JoinError {
     #[error("{0}")]
@@ -105,6 +296,32 @@ pub enum JoinError {
     ReadPacket(#[from] azalea_protocol::read::ReadPacketError),
     #[error("{0}")]
     Io(#[from] io::Error),
+    #[error("{0}")]
+    Auth(#[from] AuthError),
+    #[error("Disconnected while logging in: {0}")]
+    Disconnect(Component),
+    /// The server rejected us because it speaks a different protocol version
+    /// than [`PROTOCOL_VERSION`]. We only know how to decode packets from
+    /// that version, so joining anyway would just desync us.
+    #[error(
+        "The server doesn't support protocol version {}: {0}",
+        PROTOCOL_VERSION
+    )]
+    UnsupportedProtocolVersion(Component),
+}
+
+/// Whether a disconnect reason is the one vanilla servers send when the
+/// protocol version in the handshake doesn't match theirs.
+fn is_protocol_version_mismatch(reason: &Component) -> bool {
+    match reason {
+        Component::Translatable(c) => matches!(
+            c.key.as_str(),
+            "multiplayer.disconnect.incompatible"
+                | "multiplayer.disconnect.outdated_client"
+                | "multiplayer.disconnect.outdated_server"
+        ),
+        _ => false,
+    }
 }
 
 #[derive(Error, Debug)]
@@ -123,6 +340,19 @@ impl Client {
         account: &Account,
         address: &ServerAddress,
     ) -> Result<(Self, UnboundedReceiver<Event>), JoinError> {
+        Self::join_with_login_plugins(account, address, &LoginPluginHandlers::default()).await
+    }
+
+    /// Connect to a Minecraft server with an account, answering the plugin
+    /// requests that are sent while logging in with the given handlers. This
+    /// is necessary for things like Velocity's modern forwarding.
+    pub async fn join_with_login_plugins(
+        account: &Account,
+        address: &ServerAddress,
+        login_plugins: &LoginPluginHandlers,
+    ) -> Result<(Self, UnboundedReceiver<Event>), JoinError> {
+        account.validate()?;
+
         let resolved_address = resolver::resolve_address(address).await?;
 
         let mut conn = Connection::new(&resolved_address).await?;
@@ -183,18 +413,57 @@ impl Client {
                     }
                     ClientboundLoginPacket::LoginDisconnect(p) => {
                         debug!("Got disconnect {:?}", p);
+                        if is_protocol_version_mismatch(&p.reason) {
+                            return Err(JoinError::UnsupportedProtocolVersion(p.reason));
+                        }
+                        return Err(JoinError::Disconnect(p.reason));
                     }
                     ClientboundLoginPacket::CustomQuery(p) => {
                         debug!("Got custom query {:?}", p);
+                        conn.write(login_plugins.respond(&p).get()).await?;
                     }
                 },
-                Err(e) => {
-                    panic!("Error: {:?}", e);
-                }
+                Err(e) => return Err(e.into()),
             }
         };
 
-        let (read_conn, write_conn) = conn.into_split();
+        Ok(Self::from_connection(game_profile, conn))
+    }
+
+    /// Create a client from a connection that's already in the game state,
+    /// and start handling packets from it.
+    ///
+    /// This is mostly useful for tests, since it lets you use any stream
+    /// (like [`tokio::io::duplex`]) instead of connecting to a real server.
+    pub fn from_connection<RS, WS>(
+        game_profile: GameProfile,
+        conn: GameConnection<RS, WS>,
+    ) -> (Self, UnboundedReceiver<Event>)
+    where
+        RS: AsyncRead + Unpin + Send + Sync + 'static,
+        WS: AsyncWrite + Unpin + Send + Sync + 'static,
+    {
+        Self::from_connection_with_capacity(game_profile, conn, DEFAULT_PACKET_QUEUE_CAPACITY)
+    }
+
+    /// Like [`Client::from_connection`], but with a custom number of packets
+    /// that can be waiting to be handled. See
+    /// [`DEFAULT_PACKET_QUEUE_CAPACITY`] for what this changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `packet_queue_capacity` is 0.
+    pub fn from_connection_with_capacity<RS, WS>(
+        game_profile: GameProfile,
+        conn: GameConnection<RS, WS>,
+        packet_queue_capacity: usize,
+    ) -> (Self, UnboundedReceiver<Event>)
+    where
+        RS: AsyncRead + Unpin + Send + Sync + 'static,
+        WS: AsyncWrite + Unpin + Send + Sync + 'static,
+    {
+        let connection_counters = conn.counters();
+        let (read_conn, write_conn) = conn.boxed().into_split();
 
         let read_conn = Arc::new(tokio::sync::Mutex::new(read_conn));
         let write_conn = Arc::new(tokio::sync::Mutex::new(write_conn));
@@ -209,24 +478,52 @@ impl Client {
             player: Arc::new(Mutex::new(Player::default())),
             dimension: Arc::new(Mutex::new(Dimension::default())),
             physics_state: Arc::new(Mutex::new(PhysicsState::default())),
+            item_use_state: Arc::new(Mutex::new(ItemUseState::default())),
+            boss_bars: Arc::new(Mutex::new(HashMap::new())),
+            scoreboard: Arc::new(Mutex::new(Scoreboard::default())),
+            tab_list: Arc::new(Mutex::new(TabList::default())),
+            last_seen_messages: Arc::new(Mutex::new(LastSeenMessagesTracker::default())),
+            resource_pack_policy: Arc::new(Mutex::new(ResourcePackPolicy::default())),
+            auto_eat: Arc::new(Mutex::new(AutoEat::default())),
+            settings: Arc::new(Mutex::new(ClientSettings::default())),
+            pathfinder: Arc::new(Mutex::new(Pathfinder::default())),
+            world_info: Arc::new(Mutex::new(None)),
+            local_player: Arc::new(Mutex::new(LocalPlayerState::default())),
+            inventory: Arc::new(Mutex::new(Inventory::default())),
+            registries: Arc::new(Mutex::new(RegistryHolder::default())),
+            scheduler: Arc::new(Mutex::new(Scheduler::default())),
+            pending_completions: Arc::new(Mutex::new(Pending::default())),
+            event_subscribers: Arc::new(Mutex::new(Vec::new())),
+            connection_counters,
             tasks: Arc::new(Mutex::new(Vec::new())),
+            event_sender: Arc::new(Mutex::new(None)),
         };
 
+        let tx = EventSender::new(tx, client.event_subscribers.clone());
+        *client.event_sender.lock() = Some(tx.clone());
+
         // just start up the game loop and we're ready!
 
         // if you get an error right here that means you're doing something with locks wrong
         // read the error to see where the issue is
         // you might be able to just drop the lock or put it in its own scope to fix
         {
+            let (packet_tx, packet_rx) = mpsc::channel(packet_queue_capacity);
             let mut tasks = client.tasks.lock();
+            tasks.push(tokio::spawn(Self::read_loop(
+                client.clone(),
+                packet_tx,
+                tx.clone(),
+            )));
             tasks.push(tokio::spawn(Self::protocol_loop(
                 client.clone(),
+                packet_rx,
                 tx.clone(),
             )));
             tasks.push(tokio::spawn(Self::game_tick_loop(client.clone(), tx)));
         }
 
-        Ok((client, rx))
+        (client, rx)
     }
 
     /// Write a packet directly to the server.
@@ -235,121 +532,201 @@ impl Client {
         Ok(())
     }
 
+    /// Write a packet directly to the server. This is the same as
+    /// [`Client::write_packet`], but you don't have to call `.get()` on the
+    /// packet.
+    pub async fn write(
+        &self,
+        packet: impl Into<ServerboundGamePacket>,
+    ) -> Result<(), std::io::Error> {
+        self.write_packet(packet.into()).await
+    }
+
+    /// Write a packet with the given id and data to the server. This lets
+    /// you send packets that azalea doesn't support yet.
+    ///
+    /// Raw packets bypass all of the client's state tracking, so for example
+    /// sending a movement packet this way won't update our position.
+    pub async fn write_raw(&self, id: u32, data: Vec<u8>) -> Result<(), std::io::Error> {
+        self.write_conn.lock().await.write_raw(id, &data).await
+    }
+
+    /// Send a plugin message (also called a custom payload) to the server on
+    /// the given channel. Plugin messages are used by proxies like
+    /// BungeeCord and by mods to send data that isn't part of the vanilla
+    /// protocol.
+    pub async fn send_plugin_message(
+        &self,
+        channel: ResourceLocation,
+        data: &[u8],
+    ) -> Result<(), std::io::Error> {
+        self.write_packet(
+            ServerboundCustomPayloadPacket {
+                identifier: channel,
+                data: data.to_vec().into(),
+            }
+            .get(),
+        )
+        .await
+    }
+
     /// Disconnect from the server, ending all tasks.
     pub async fn shutdown(self) -> Result<(), std::io::Error> {
-        self.write_conn.lock().await.write_stream.shutdown().await?;
-        let tasks = self.tasks.lock();
-        for task in tasks.iter() {
+        self.disconnect().await
+    }
+
+    /// Disconnect from the server. This stops reading packets and ticking,
+    /// sends any packets that haven't been flushed yet, and closes the
+    /// connection. An [`Event::Disconnect`] is sent afterwards so whatever is
+    /// reading events knows the client is done.
+    pub async fn disconnect(&self) -> Result<(), std::io::Error> {
+        for task in self.tasks.lock().drain(..) {
             task.abort();
         }
-        Ok(())
+        let result = {
+            let mut write_conn = self.write_conn.lock().await;
+            match write_conn.flush().await {
+                Ok(()) => write_conn.write_stream.shutdown().await,
+                Err(e) => Err(e),
+            }
+        };
+        // taking the sender drops it, so the event channels close once the
+        // aborted tasks are gone
+        if let Some(tx) = self.event_sender.lock().take() {
+            tx.send(Event::Disconnect(Component::text("Client disconnected")));
+        }
+        result
+    }
+
+    /// Stop whatever we're doing. This makes [`Client::goto`] return
+    /// [`PathfindError::Cancelled`], stops walking and jumping, and stops
+    /// using the item we're holding right click with.
+    pub async fn stop(&self) -> Result<(), std::io::Error> {
+        self.pathfinder.lock().finish(Err(PathfindError::Cancelled));
+        // the state is shared, so changing it through a clone changes ours
+        let mut client = self.clone();
+        client.walk(MoveDirection::None);
+        client.set_jumping(false);
+        self.release_use_item().await
     }
 
-    async fn protocol_loop(client: Client, tx: UnboundedSender<Event>) {
+    /// Read packets from the server and queue them to be handled until the
+    /// connection closes. If the queue is full, this waits for the handler
+    /// to catch up before reading more.
+    ///
+    /// IO errors, the connection closing, and packets that can't be split
+    /// apart are fatal and stop the loop, since there's no way to know where
+    /// the next packet starts. A packet that can't be decoded, or an error or
+    /// panic while handling one, only affects that packet. It's logged and
+    /// sent as [`Event::HandlerError`] before moving on to the next packet,
+    /// unless errors aren't being ignored (in debug builds), in which case we
+    /// panic.
+    async fn read_loop(
+        client: Client,
+        packet_tx: mpsc::Sender<ClientboundGamePacket>,
+        tx: EventSender,
+    ) {
         loop {
             let r = client.read_conn.lock().await.read().await;
             match r {
-                Ok(packet) => match Self::handle(&packet, &client, &tx).await {
-                    Ok(_) => {}
-                    Err(e) => {
-                        error!("Error handling packet: {}", e);
-                        if IGNORE_ERRORS {
-                            continue;
-                        } else {
-                            panic!("Error handling packet: {}", e);
-                        }
-                    }
-                },
-                Err(e) => {
-                    if IGNORE_ERRORS {
-                        warn!("{}", e);
-                        match e {
-                            ReadPacketError::FrameSplitter { .. } => panic!("Error: {:?}", e),
-                            _ => continue,
-                        }
-                    } else {
-                        panic!("{}", e);
+                Ok(packet) => {
+                    if packet_tx.send(packet).await.is_err() {
+                        // the handler stopped
+                        break;
                     }
                 }
+                Err(
+                    e @ (ReadPacketError::IoError { .. }
+                    | ReadPacketError::ConnectionClosed
+                    | ReadPacketError::FrameSplitter { .. }),
+                ) => {
+                    error!("Stopped reading packets: {}", e);
+                    break;
+                }
+                Err(e) => Self::report_handler_error(e.to_string(), &tx),
+            }
+        }
+    }
+
+    /// Handle the packets that were read by [`Client::read_loop`] until the
+    /// connection closes.
+    async fn protocol_loop(
+        client: Client,
+        mut packet_rx: mpsc::Receiver<ClientboundGamePacket>,
+        tx: EventSender,
+    ) {
+        while let Some(packet) = packet_rx.recv().await {
+            let error = match AssertUnwindSafe(Self::handle(&packet, &client, &tx))
+                .catch_unwind()
+                .await
+            {
+                Ok(Ok(())) => continue,
+                Ok(Err(e)) => format!("Error handling packet: {}", e),
+                Err(panic) => {
+                    format!("Panicked while handling packet: {}", panic_message(&*panic))
+                }
             };
+            Self::report_handler_error(error, &tx);
+        }
+    }
+
+    /// Log an error from decoding or handling a packet and send it as an
+    /// [`Event::HandlerError`]. In debug builds this panics instead.
+    fn report_handler_error(error: String, tx: &EventSender) {
+        error!("{}", error);
+        if !IGNORE_ERRORS {
+            panic!("{}", error);
         }
+        tx.send(Event::HandlerError(error));
+    }
+
+    /// Forget everything we know about the world we were in, so nothing from
+    /// it is left over when the server sends us to a new one with another
+    /// login packet (like proxies do when switching servers). The dimension
+    /// itself is replaced by the login packet handler, since that's where we
+    /// learn its height.
+    pub(crate) fn reset_state(&self) {
+        *self.local_player.lock() = LocalPlayerState::default();
+        *self.inventory.lock() = Inventory::default();
+        *self.tab_list.lock() = TabList::default();
+        self.boss_bars.lock().clear();
+        *self.scoreboard.lock() = Scoreboard::default();
+        *self.item_use_state.lock() = ItemUseState::default();
+        // the fall context is set by the user, so it's kept
+        self.pathfinder
+            .lock()
+            .finish(Err(PathfindError::Interrupted));
     }
 
     async fn handle(
         packet: &ClientboundGamePacket,
         client: &Client,
-        tx: &UnboundedSender<Event>,
+        tx: &EventSender,
     ) -> Result<(), HandleError> {
-        tx.send(Event::Packet(Box::new(packet.clone()))).unwrap();
+        if tx.wants(EventKind::PACKET) {
+            tx.send(Event::Packet(Box::new(packet.clone())));
+        }
         match packet {
             ClientboundGamePacket::Login(p) => {
                 debug!("Got login packet {:?}", p);
+                client.reset_state();
 
                 {
-                    // // write p into login.txt
-                    // std::io::Write::write_all(
-                    //     &mut std::fs::File::create("login.txt").unwrap(),
-                    //     format!("{:#?}", p).as_bytes(),
-                    // )
-                    // .unwrap();
-
-                    // TODO: have registry_holder be a struct because this sucks rn
-                    // best way would be to add serde support to azalea-nbt
-
-                    let registry_holder = p
-                        .registry_holder
-                        .as_compound()
-                        .expect("Registry holder is not a compound")
-                        .get("")
-                        .expect("No \"\" tag")
-                        .as_compound()
-                        .expect("\"\" tag is not a compound");
-                    let dimension_types = registry_holder
-                        .get("minecraft:dimension_type")
-                        .expect("No dimension_type tag")
-                        .as_compound()
-                        .expect("dimension_type is not a compound")
-                        .get("value")
-                        .expect("No dimension_type value")
-                        .as_list()
-                        .expect("dimension_type value is not a list");
-                    let dimension_type = dimension_types
-                        .iter()
-                        .find(|t| {
-                            t.as_compound()
-                                .expect("dimension_type value is not a compound")
-                                .get("name")
-                                .expect("No name tag")
-                                .as_string()
-                                .expect("name is not a string")
-                                == p.dimension_type.to_string()
-                        })
-                        .unwrap_or_else(|| {
-                            panic!("No dimension_type with name {}", p.dimension_type)
-                        })
-                        .as_compound()
-                        .unwrap()
-                        .get("element")
-                        .expect("No element tag")
-                        .as_compound()
-                        .expect("element is not a compound");
-                    let height = (*dimension_type
-                        .get("height")
-                        .expect("No height tag")
-                        .as_int()
-                        .expect("height tag is not an int"))
-                    .try_into()
-                    .expect("height is not a u32");
-                    let min_y = *dimension_type
-                        .get("min_y")
-                        .expect("No min_y tag")
-                        .as_int()
-                        .expect("min_y tag is not an int");
+                    let registries = RegistryHolder::from_nbt(&p.registry_holder)
+                        .map_err(anyhow::Error::from)?;
+                    let dimension_type =
+                        registries
+                            .dimension_type(&p.dimension_type)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("No dimension type with name {}", p.dimension_type)
+                            })?;
 
                     let mut dimension_lock = client.dimension.lock();
                     // the 16 here is our render distance
                     // i'll make this an actual setting later
-                    *dimension_lock = Dimension::new(16, height, min_y);
+                    *dimension_lock =
+                        Dimension::new(16, dimension_type.height, dimension_type.min_y);
+                    *client.registries.lock() = registries;
 
                     let entity = EntityData::new(client.game_profile.uuid, Vec3::default());
                     dimension_lock.add_entity(p.player_id, entity);
@@ -358,49 +735,73 @@ impl Client {
 
                     player_lock.set_entity_id(p.player_id);
                 }
+                *client.world_info.lock() = Some(WorldInfo::from_login(p));
 
+                let (brand_data, information_packet) = {
+                    let settings = client.settings.lock();
+                    (settings.brand_data(), settings.information_packet())
+                };
                 client
                     .write_packet(
                         ServerboundCustomPayloadPacket {
                             identifier: ResourceLocation::new("brand").unwrap(),
-                            // they don't have to know :)
-                            data: "vanilla".into(),
+                            data: brand_data,
                         }
                         .get(),
                     )
                     .await?;
+                client.write_packet(information_packet.get()).await?;
 
-                tx.send(Event::Login).unwrap();
+                tx.send(Event::Login);
             }
             ClientboundGamePacket::UpdateViewDistance(p) => {
                 debug!("Got view distance packet {:?}", p);
+                if let Some(world_info) = client.world_info.lock().as_mut() {
+                    world_info.view_distance = p.view_distance as u32;
+                }
             }
             ClientboundGamePacket::CustomPayload(p) => {
                 debug!("Got custom payload packet {:?}", p);
+                tx.send(Event::PluginMessage {
+                    channel: p.identifier.clone(),
+                    data: p.data.to_vec(),
+                });
             }
             ClientboundGamePacket::ChangeDifficulty(p) => {
                 debug!("Got difficulty packet {:?}", p);
+                if let Some(world_info) = client.world_info.lock().as_mut() {
+                    world_info.difficulty = Some(p.difficulty.clone());
+                    world_info.difficulty_locked = p.locked;
+                }
             }
             ClientboundGamePacket::Commands(_p) => {
                 debug!("Got declare commands packet");
             }
             ClientboundGamePacket::PlayerAbilities(p) => {
                 debug!("Got player abilities packet {:?}", p);
+                client.local_player.lock().abilities = Abilities::from(p);
             }
             ClientboundGamePacket::SetCarriedItem(p) => {
                 debug!("Got set carried item packet {:?}", p);
+                client.inventory.lock().selected_hotbar_slot = p.slot;
             }
             ClientboundGamePacket::UpdateTags(_p) => {
                 debug!("Got update tags packet");
             }
             ClientboundGamePacket::Disconnect(p) => {
                 debug!("Got disconnect packet {:?}", p);
+                tx.send(Event::Disconnect(p.reason.clone()));
             }
             ClientboundGamePacket::UpdateRecipes(_p) => {
                 debug!("Got update recipes packet");
             }
-            ClientboundGamePacket::EntityEvent(_p) => {
+            ClientboundGamePacket::EntityEvent(p) => {
                 // debug!("Got entity event packet {:?}", p);
+                let player_entity_id = client.player.lock().entity_id;
+                if p.entity_id == player_entity_id && p.event_id == FINISHED_USING_ITEM_EVENT_ID {
+                    client.item_use_state.lock().using_item = None;
+                    tx.send(Event::ItemUseFinished);
+                }
             }
             ClientboundGamePacket::Recipe(_p) => {
                 debug!("Got recipe packet");
@@ -498,6 +899,7 @@ impl Client {
             }
             ClientboundGamePacket::PlayerInfo(p) => {
                 debug!("Got player info packet {:?}", p);
+                client.tab_list.lock().apply_player_info(p);
             }
             ClientboundGamePacket::SetChunkCacheCenter(p) => {
                 debug!("Got chunk cache center packet {:?}", p);
@@ -511,11 +913,33 @@ impl Client {
                 let pos = ChunkPos::new(p.x, p.z);
                 // let chunk = Chunk::read_with_world_height(&mut p.chunk_data);
                 // debug("chunk {:?}")
-                client
-                    .dimension
-                    .lock()
+                let mut dimension = client.dimension.lock();
+                dimension
                     .replace_with_packet_data(&pos, &mut Cursor::new(&p.chunk_data.data))
                     .unwrap();
+                for block_entity in &p.chunk_data.block_entities {
+                    let kind =
+                        match azalea_registry::BlockEntityType::try_from(block_entity.type_ as u32)
+                        {
+                            Ok(kind) => kind,
+                            Err(_) => {
+                                warn!("Unknown block entity type {}", block_entity.type_);
+                                continue;
+                            }
+                        };
+                    let block_pos = BlockPos::new(
+                        p.x * 16 + (block_entity.packed_xz >> 4) as i32,
+                        block_entity.y as i16 as i32,
+                        p.z * 16 + (block_entity.packed_xz & 15) as i32,
+                    );
+                    dimension.set_block_entity(
+                        &block_pos,
+                        BlockEntity {
+                            kind,
+                            data: block_entity.data.clone(),
+                        },
+                    );
+                }
             }
             ClientboundGamePacket::LightUpdate(p) => {
                 debug!("Got light update packet {:?}", p);
@@ -525,14 +949,30 @@ impl Client {
                 let entity = EntityData::from(p);
                 client.dimension.lock().add_entity(p.id, entity);
             }
-            ClientboundGamePacket::SetEntityData(_p) => {
+            ClientboundGamePacket::SetEntityData(p) => {
                 // debug!("Got set entity data packet {:?}", p);
+                if let Some(mut entity) = client.dimension.lock().entity_mut(p.id) {
+                    entity.apply_metadata(&p.packed_items);
+                }
+                if p.id == client.entity_id() {
+                    client
+                        .local_player
+                        .lock()
+                        .apply_entity_data(&p.packed_items);
+                }
             }
             ClientboundGamePacket::UpdateAttributes(_p) => {
                 // debug!("Got update attributes packet {:?}", p);
             }
-            ClientboundGamePacket::EntityVelocity(_p) => {
+            ClientboundGamePacket::EntityVelocity(p) => {
                 // debug!("Got entity velocity packet {:?}", p);
+                let velocity = p.velocity();
+                if let Some(mut entity) = client.dimension.lock().entity_mut(p.entity_id) {
+                    entity.delta = velocity;
+                }
+                if p.entity_id == client.entity_id() {
+                    tx.send(Event::Knockback(velocity));
+                }
             }
             ClientboundGamePacket::SetEntityLink(p) => {
                 debug!("Got set entity link packet {:?}", p);
@@ -547,18 +987,27 @@ impl Client {
             }
             ClientboundGamePacket::SetTime(p) => {
                 debug!("Got set time packet {:?}", p);
+                if let Some(world_info) = client.world_info.lock().as_mut() {
+                    world_info.apply_set_time(p);
+                }
             }
             ClientboundGamePacket::SetDefaultSpawnPosition(p) => {
                 debug!("Got set default spawn position packet {:?}", p);
             }
             ClientboundGamePacket::ContainerSetContent(p) => {
                 debug!("Got container set content packet {:?}", p);
+                client.inventory.lock().apply_container_set_content(p);
             }
             ClientboundGamePacket::SetHealth(p) => {
                 debug!("Got set health packet {:?}", p);
+                client.local_player.lock().apply_set_health(p);
             }
             ClientboundGamePacket::SetExperience(p) => {
                 debug!("Got set experience packet {:?}", p);
+                let leveled_up = client.local_player.lock().apply_set_experience(p);
+                if leveled_up {
+                    tx.send(Event::LevelUp(p.experience_level));
+                }
             }
             ClientboundGamePacket::TeleportEntity(p) => {
                 let mut dimension_lock = client.dimension.lock();
@@ -605,66 +1054,192 @@ impl Client {
             }
             ClientboundGamePacket::RemoveEntities(p) => {
                 debug!("Got remove entities packet {:?}", p);
+                for &entity_id in &p.entity_ids {
+                    // the server might remove entities we never knew about
+                    if client.dimension.lock().remove_entity(entity_id).is_some() {
+                        tx.send(Event::EntityRemoved(entity_id));
+                    }
+                }
             }
             ClientboundGamePacket::PlayerChat(p) => {
                 // debug!("Got player chat packet {:?}", p);
-                tx.send(Event::Chat(ChatPacket::Player(Box::new(p.clone()))))
-                    .unwrap();
+                let needs_acknowledgement =
+                    client
+                        .last_seen_messages
+                        .lock()
+                        .push(LastSeenMessagesEntry {
+                            profile_id: p.message.signed_header.sender,
+                            last_signature: p.message.header_signature.clone(),
+                        });
+                if needs_acknowledgement {
+                    let last_seen_messages = client.last_seen_messages.lock().generate_update();
+                    client
+                        .write_packet(ServerboundChatAckPacket { last_seen_messages }.get())
+                        .await?;
+                }
+                tx.send(Event::Chat(ChatPacket::Player(Box::new(p.clone()))));
             }
             ClientboundGamePacket::SystemChat(p) => {
                 debug!("Got system chat packet {:?}", p);
-                tx.send(Event::Chat(ChatPacket::System(p.clone()))).unwrap();
+                tx.send(Event::Chat(ChatPacket::System(p.clone())));
             }
             ClientboundGamePacket::Sound(p) => {
                 debug!("Got sound packet {:?}", p);
+                tx.send(Event::Sound {
+                    kind: SoundKind::Registry(p.sound),
+                    source: p.source,
+                    pos: p.pos(),
+                    volume: p.volume,
+                    pitch: p.pitch,
+                });
             }
             ClientboundGamePacket::LevelEvent(p) => {
                 debug!("Got level event packet {:?}", p);
             }
             ClientboundGamePacket::BlockUpdate(p) => {
                 debug!("Got block update packet {:?}", p);
-                let mut dimension = client.dimension.lock();
-                dimension.set_block_state(&p.pos, p.block_state);
+                if client
+                    .item_use_state
+                    .lock()
+                    .update_server_state(p.pos, p.block_state)
+                {
+                    client.set_block_states([(p.pos, p.block_state)]);
+                }
             }
             ClientboundGamePacket::Animate(p) => {
                 debug!("Got animate packet {:?}", p);
+                tx.send(Event::EntityAnimation {
+                    entity_id: p.id,
+                    kind: p.action,
+                });
             }
             ClientboundGamePacket::SectionBlocksUpdate(p) => {
                 debug!("Got section blocks update packet {:?}", p);
-                let mut dimension = client.dimension.lock();
-                for state in &p.states {
-                    dimension.set_block_state(&(p.section_pos + state.pos), state.state);
-                }
+                let states = {
+                    let mut item_use_state = client.item_use_state.lock();
+                    p.states
+                        .iter()
+                        .map(|state| (p.section_pos + state.pos, state.state))
+                        .filter(|&(pos, state)| item_use_state.update_server_state(pos, state))
+                        .collect::<Vec<_>>()
+                };
+                client.set_block_states(states);
             }
             ClientboundGamePacket::GameEvent(p) => {
                 debug!("Got game event packet {:?}", p);
+                let event = p.game_event();
+                if let GameEvent::ChangeGameMode(game_mode) = event {
+                    client.set_game_mode(game_mode, tx);
+                }
+                let weather = {
+                    let mut world_info = client.world_info.lock();
+                    world_info.as_mut().and_then(|world_info| {
+                        if world_info.apply_game_event(&event) {
+                            Some(world_info.weather)
+                        } else {
+                            None
+                        }
+                    })
+                };
+                if let Some(weather) = weather {
+                    tx.send(Event::Weather(weather));
+                }
             }
             ClientboundGamePacket::LevelParticles(p) => {
                 debug!("Got level particles packet {:?}", p);
+                if tx.wants(EventKind::PARTICLE) {
+                    tx.send(Event::Particle(Box::new(p.clone())));
+                }
             }
             ClientboundGamePacket::ServerData(p) => {
                 debug!("Got server data packet {:?}", p);
             }
             ClientboundGamePacket::SetEquipment(p) => {
                 debug!("Got set equipment packet {:?}", p);
+                let entity_id = p.entity as u32;
+                if entity_id == client.entity_id() {
+                    client.local_player.lock().apply_equipment(&p.slots);
+                }
+                if let Some(mut entity) = client.dimension.lock().entity_mut(entity_id) {
+                    for (slot, item) in &p.slots.slots {
+                        entity.set_equipment(*slot, item.clone());
+                    }
+                }
+                tx.send(Event::EntityEquipmentChanged {
+                    entity_id,
+                    slots: p.slots.slots.clone(),
+                });
             }
             ClientboundGamePacket::UpdateMobEffect(p) => {
                 debug!("Got update mob effect packet {:?}", p);
+                if p.entity_id == client.entity_id() {
+                    let effect = {
+                        let mut local_player = client.local_player.lock();
+                        local_player.apply_update_mob_effect(p);
+                        local_player.effects[&p.effect].clone()
+                    };
+                    tx.send(Event::EffectAdded(p.effect, effect));
+                }
             }
             ClientboundGamePacket::AddExperienceOrb(_) => {}
             ClientboundGamePacket::AwardStats(_) => {}
-            ClientboundGamePacket::BlockChangedAck(_) => {}
+            ClientboundGamePacket::BlockChangedAck(p) => {
+                debug!("Got block changed ack packet {:?}", p);
+                let acknowledged = client.item_use_state.lock().acknowledge(p.sequence as u32);
+                client.set_block_states(acknowledged);
+            }
             ClientboundGamePacket::BlockDestruction(_) => {}
-            ClientboundGamePacket::BlockEntityData(_) => {}
+            ClientboundGamePacket::BlockEntityData(p) => {
+                debug!("Got block entity data packet {:?}", p);
+                client.dimension.lock().set_block_entity(
+                    &p.pos,
+                    BlockEntity {
+                        kind: p.block_entity_type,
+                        data: p.tag.clone(),
+                    },
+                );
+            }
             ClientboundGamePacket::BlockEvent(_) => {}
-            ClientboundGamePacket::BossEvent(_) => {}
+            ClientboundGamePacket::BossEvent(p) => {
+                debug!("Got boss event packet {:?}", p);
+                let boss_bar = boss_bar::apply_boss_event(&mut client.boss_bars.lock(), p);
+                match boss_bar {
+                    Some(boss_bar) => tx.send(Event::BossBarUpdate(p.id, Some(boss_bar))),
+                    None if matches!(p.operation, BossEventOperation::Remove) => {
+                        tx.send(Event::BossBarUpdate(p.id, None))
+                    }
+                    None => warn!("Got an update for unknown boss bar {}", p.id),
+                }
+            }
             ClientboundGamePacket::ChatPreview(_) => {}
-            ClientboundGamePacket::CommandSuggestions(_) => {}
+            ClientboundGamePacket::CommandSuggestions(p) => {
+                completions::handle_suggestions(client, p);
+            }
             ClientboundGamePacket::ContainerSetData(_) => {}
-            ClientboundGamePacket::ContainerSetSlot(_) => {}
-            ClientboundGamePacket::Cooldown(_) => {}
+            ClientboundGamePacket::ContainerSetSlot(p) => {
+                debug!("Got container set slot packet {:?}", p);
+                client.inventory.lock().apply_container_set_slot(p);
+            }
+            ClientboundGamePacket::Cooldown(p) => {
+                debug!("Got cooldown packet {:?}", p);
+                let mut item_use_state = client.item_use_state.lock();
+                if p.duration == 0 {
+                    item_use_state.cooldowns.remove(&p.item);
+                } else {
+                    item_use_state.cooldowns.insert(p.item, p.duration);
+                }
+            }
             ClientboundGamePacket::CustomChatCompletions(_) => {}
-            ClientboundGamePacket::CustomSound(_) => {}
+            ClientboundGamePacket::CustomSound(p) => {
+                debug!("Got custom sound packet {:?}", p);
+                tx.send(Event::Sound {
+                    kind: SoundKind::Custom(p.name.clone()),
+                    source: p.source,
+                    pos: p.pos(),
+                    volume: p.volume,
+                    pitch: p.pitch,
+                });
+            }
             ClientboundGamePacket::DeleteChat(_) => {}
             ClientboundGamePacket::Explode(_) => {}
             ClientboundGamePacket::ForgetLevelChunk(_) => {}
@@ -682,9 +1257,65 @@ impl Client {
             ClientboundGamePacket::PlayerCombatEnter(_) => {}
             ClientboundGamePacket::PlayerCombatKill(_) => {}
             ClientboundGamePacket::PlayerLookAt(_) => {}
-            ClientboundGamePacket::RemoveMobEffect(_) => {}
-            ClientboundGamePacket::ResourcePack(_) => {}
-            ClientboundGamePacket::Respawn(_) => {}
+            ClientboundGamePacket::RemoveMobEffect(p) => {
+                debug!("Got remove mob effect packet {:?}", p);
+                if p.entity_id == client.entity_id() {
+                    client.local_player.lock().apply_remove_mob_effect(p);
+                    tx.send(Event::EffectRemoved(p.effect));
+                }
+            }
+            ClientboundGamePacket::ResourcePack(p) => {
+                debug!("Got resource pack packet {:?}", p);
+                tx.send(Event::ResourcePackPrompt(p.url.clone(), p.hash.clone()));
+                let responses = client.resource_pack_policy.lock().responses();
+                for action in responses {
+                    client
+                        .write_packet(ServerboundResourcePackPacket { action }.get())
+                        .await?;
+                }
+            }
+            ClientboundGamePacket::Respawn(p) => {
+                debug!("Got respawn packet {:?}", p);
+                let changed_dimension = match client.world_info.lock().as_mut() {
+                    Some(world_info) => {
+                        let changed_dimension = world_info.dimension != p.dimension;
+                        world_info.apply_respawn(p);
+                        changed_dimension
+                    }
+                    None => true,
+                };
+                if changed_dimension {
+                    // the new dimension might be a different height, so we
+                    // start over with an empty one
+                    let (height, min_y) = {
+                        let registries = client.registries.lock();
+                        let dimension_type = registries
+                            .dimension_type(&p.dimension_type)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("No dimension type with name {}", p.dimension_type)
+                            })?;
+                        (dimension_type.height, dimension_type.min_y)
+                    };
+                    let player_entity_id = client.entity_id();
+                    {
+                        let mut dimension_lock = client.dimension.lock();
+                        *dimension_lock = Dimension::new(16, height, min_y);
+                        let entity = EntityData::new(client.game_profile.uuid, Vec3::default());
+                        dimension_lock.add_entity(player_entity_id, entity);
+                    }
+                    // positions from the old dimension don't mean anything
+                    // here
+                    client
+                        .pathfinder
+                        .lock()
+                        .finish(Err(PathfindError::Interrupted));
+                    client.item_use_state.lock().predictions.clear();
+                }
+                client.set_game_mode(p.player_game_type, tx);
+                if changed_dimension {
+                    tx.send(Event::DimensionChanged(p.dimension.clone()));
+                }
+            }
             ClientboundGamePacket::SelectAdvancementsTab(_) => {}
             ClientboundGamePacket::SetActionBarText(_) => {}
             ClientboundGamePacket::SetBorderCenter(_) => {}
@@ -693,21 +1324,52 @@ impl Client {
             ClientboundGamePacket::SetBorderWarningDelay(_) => {}
             ClientboundGamePacket::SetBorderWarningDistance(_) => {}
             ClientboundGamePacket::SetCamera(_) => {}
-            ClientboundGamePacket::SetChunkCacheRadius(_) => {}
             ClientboundGamePacket::SetDisplayChatPreview(_) => {}
-            ClientboundGamePacket::SetDisplayObjective(_) => {}
-            ClientboundGamePacket::SetEntityMotion(_) => {}
-            ClientboundGamePacket::SetObjective(_) => {}
+            ClientboundGamePacket::SetDisplayObjective(p) => {
+                debug!("Got set display objective packet {:?}", p);
+                client.scoreboard.lock().apply_display_objective(p);
+                tx.send(Event::ScoreboardUpdate);
+            }
+            ClientboundGamePacket::SetObjective(p) => {
+                debug!("Got set objective packet {:?}", p);
+                client.scoreboard.lock().apply_objective(p);
+                tx.send(Event::ScoreboardUpdate);
+            }
             ClientboundGamePacket::SetPassengers(_) => {}
             ClientboundGamePacket::SetPlayerTeam(_) => {}
-            ClientboundGamePacket::SetScore(_) => {}
+            ClientboundGamePacket::SetScore(p) => {
+                debug!("Got set score packet {:?}", p);
+                client.scoreboard.lock().apply_score(p);
+                tx.send(Event::ScoreboardUpdate);
+            }
             ClientboundGamePacket::SetSimulationDistance(_) => {}
             ClientboundGamePacket::SetSubtitleText(_) => {}
             ClientboundGamePacket::SetTitleText(_) => {}
             ClientboundGamePacket::SetTitlesAnimation(_) => {}
-            ClientboundGamePacket::SoundEntity(_) => {}
+            ClientboundGamePacket::SoundEntity(p) => {
+                debug!("Got sound entity packet {:?}", p);
+                let pos = {
+                    let dimension = client.dimension.lock();
+                    dimension.entity(p.id).map(|entity| *entity.pos())
+                };
+                // we can't tell where the sound is if we don't know about the entity
+                if let Some(pos) = pos {
+                    tx.send(Event::Sound {
+                        kind: SoundKind::Registry(p.sound),
+                        source: p.source,
+                        pos,
+                        volume: p.volume,
+                        pitch: p.pitch,
+                    });
+                }
+            }
             ClientboundGamePacket::StopSound(_) => {}
-            ClientboundGamePacket::TabList(_) => {}
+            ClientboundGamePacket::TabList(p) => {
+                debug!("Got tab list packet {:?}", p);
+                let mut tab_list = client.tab_list.lock();
+                tab_list.header = Some(p.header.clone());
+                tab_list.footer = Some(p.footer.clone());
+            }
             ClientboundGamePacket::TagQuery(_) => {}
             ClientboundGamePacket::TakeItemEntity(_) => {}
         }
@@ -716,7 +1378,7 @@ impl Client {
     }
 
     /// Runs game_tick every 50 milliseconds.
-    async fn game_tick_loop(mut client: Client, tx: UnboundedSender<Event>) {
+    async fn game_tick_loop(mut client: Client, tx: EventSender) {
         let mut game_tick_interval = time::interval(time::Duration::from_millis(50));
         // TODO: Minecraft bursts up to 10 ticks and then skips, we should too
         game_tick_interval.set_missed_tick_behavior(time::MissedTickBehavior::Burst);
@@ -727,7 +1389,7 @@ impl Client {
     }
 
     /// Runs every 50 milliseconds.
-    async fn game_tick(client: &mut Client, tx: &UnboundedSender<Event>) {
+    async fn game_tick(client: &mut Client, tx: &EventSender) {
         // return if there's no chunk at the player's position
         {
             let dimension_lock = client.dimension.lock();
@@ -744,34 +1406,241 @@ impl Client {
             }
         }
 
-        tx.send(Event::Tick).unwrap();
+        tx.send(Event::Tick);
+
+        // these run before we start holding back packets, so an action that
+        // takes a while doesn't stop us from answering keep alives
+        let actions = client.scheduler.lock().tick();
+        for action in actions {
+            if let Err(panic) = AssertUnwindSafe(action(client.clone()))
+                .catch_unwind()
+                .await
+            {
+                warn!(
+                    "Panicked while running a scheduled action: {}",
+                    panic_message(&*panic)
+                );
+            }
+        }
+
+        // send all the packets from this tick at once
+        client.write_conn.lock().await.auto_flush = false;
 
         // TODO: if we're a passenger, send the required packets
 
+        client.physics_state.lock().sent_position_this_tick = false;
         if let Err(e) = client.send_position().await {
             warn!("Error sending position: {:?}", e);
         }
-        client.ai_step();
+        if let Some((door, direction)) = client.tick_pathfinder(tx) {
+            if let Err(e) = client
+                .use_block(InteractionHand::MainHand, door, direction)
+                .await
+            {
+                warn!("Error opening door: {:?}", e);
+            }
+        }
+        if let Err(e) = client.tick_auto_eat().await {
+            warn!("Error eating: {:?}", e);
+        }
+        if let Err(e) = client.ai_step().await {
+            warn!("Error moving: {:?}", e);
+        }
+        client.item_use_state.lock().tick_cooldowns();
+        client.local_player.lock().tick_effects();
+        if let Some(world_info) = client.world_info.lock().as_mut() {
+            world_info.tick_time();
+        }
+
+        {
+            let mut write_conn = client.write_conn.lock().await;
+            write_conn.auto_flush = true;
+            if let Err(e) = write_conn.flush().await {
+                warn!("Error flushing packets: {:?}", e);
+            }
+        }
 
         // TODO: minecraft does ambient sounds here
     }
 
+    /// Get how many ticks we've run since we joined. We don't tick until the
+    /// chunk we're in is loaded.
+    pub fn tick_count(&self) -> u64 {
+        self.scheduler.lock().tick_count
+    }
+
+    /// Run `action` after this many ticks, like for waiting until a block is
+    /// done being mined. Actions scheduled for the same tick run in the order
+    /// they were scheduled, before we move on that tick.
+    ///
+    /// The tick waits for each action to finish, so actions that wait for
+    /// something like an event should spawn a task instead. If an action
+    /// panics, the panic is logged and the tick goes on.
+    ///
+    /// ```no_run
+    /// # async fn example(client: azalea_client::Client) {
+    /// client.schedule_in(20, |client| async move {
+    ///     client.chat("one second later").await.unwrap();
+    /// });
+    /// # }
+    /// ```
+    pub fn schedule_in<F, Fut>(&self, ticks: u32, action: F)
+    where
+        F: FnOnce(Client) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.scheduler
+            .lock()
+            .schedule_in(ticks, Box::new(move |client| action(client).boxed()));
+    }
+
+    /// Get how many packets and bytes we've sent and received since we
+    /// connected to the server.
+    pub fn stats(&self) -> ConnectionStats {
+        self.connection_counters.snapshot()
+    }
+
+    /// Get a copy of all the boss bars that are currently shown.
+    pub fn boss_bars(&self) -> HashMap<Uuid, BossBar> {
+        self.boss_bars.lock().clone()
+    }
+
+    /// Get the lines on the scoreboard sidebar, sorted the same way vanilla
+    /// shows them.
+    pub fn sidebar(&self) -> Vec<(Component, i32)> {
+        self.scoreboard.lock().sidebar()
+    }
+
+    /// Get the text shown above the player list, if the server set one.
+    pub fn tab_list_header(&self) -> Option<Component> {
+        self.tab_list.lock().header.clone()
+    }
+
+    /// Get the text shown below the player list, if the server set one.
+    pub fn tab_list_footer(&self) -> Option<Component> {
+        self.tab_list.lock().footer.clone()
+    }
+
+    /// Get the dimension, game mode, and other things about the world we're
+    /// in, or `None` if we haven't gotten the login packet yet.
+    pub fn world_info(&self) -> Option<WorldInfo> {
+        self.world_info.lock().clone()
+    }
+
+    /// Get our game mode, or `None` if we haven't gotten the login packet yet.
+    pub fn game_mode(&self) -> Option<GameType> {
+        self.world_info
+            .lock()
+            .as_ref()
+            .map(|world_info| world_info.game_mode)
+    }
+
+    /// Get how many ticks the world has existed for, or `None` if we haven't
+    /// gotten the login packet yet.
+    pub fn world_age(&self) -> Option<u64> {
+        self.world_info
+            .lock()
+            .as_ref()
+            .map(|world_info| world_info.game_time)
+    }
+
+    /// Get the time of the current day from 0 to 24000, or `None` if we
+    /// haven't gotten the login packet yet. See [`WorldInfo::is_day`] and
+    /// [`WorldInfo::is_night`].
+    pub fn time_of_day(&self) -> Option<u32> {
+        self.world_info
+            .lock()
+            .as_ref()
+            .map(|world_info| world_info.time_of_day())
+    }
+
+    /// Update our game mode and send [`Event::GameModeChanged`] if it's
+    /// different.
+    fn set_game_mode(&self, game_mode: GameType, tx: &EventSender) {
+        let changed = match self.world_info.lock().as_mut() {
+            Some(world_info) if world_info.game_mode != game_mode => {
+                world_info.game_mode = game_mode;
+                true
+            }
+            _ => false,
+        };
+        if changed {
+            tx.send(Event::GameModeChanged(game_mode));
+        }
+    }
+
+    /// Get how full our experience bar is (from 0 to 1), our level, and our
+    /// total experience points.
+    pub fn experience(&self) -> (f32, u32, u32) {
+        let experience = self.local_player.lock().experience;
+        (experience.progress, experience.level, experience.total)
+    }
+
+    /// Get our health, hunger, and saturation.
+    pub fn health(&self) -> Health {
+        self.local_player.lock().health
+    }
+
+    /// Get what we're allowed to do because of our game mode, like whether
+    /// we can fly.
+    pub fn abilities(&self) -> Abilities {
+        self.local_player.lock().abilities
+    }
+
+    /// Get a copy of the status effects we have right now.
+    pub fn active_effects(&self) -> HashMap<MobEffect, MobEffectInstance> {
+        self.local_player.lock().effects.clone()
+    }
+
+    /// Get a copy of the players in the tab list, indexed by their uuid.
+    pub fn players(&self) -> HashMap<Uuid, Player> {
+        self.tab_list.lock().players.clone()
+    }
+
+    /// Find the closest entity to us that matches the filter, and return its
+    /// id, position, and distance from us. We're never included, and if
+    /// several entities are equally close the one with the lowest id wins.
+    ///
+    /// Returns `None` if there are no matching entities or we haven't spawned
+    /// yet.
+    pub fn closest_entity(&self, filter: impl Fn(&EntityData) -> bool) -> Option<(u32, Vec3, f64)> {
+        let entity_id = self.entity_id();
+        let dimension = self.dimension.lock();
+        let pos = *dimension.entity(entity_id)?.pos();
+        dimension
+            .closest_entity(&pos, |id, entity| id != entity_id && filter(entity))
+            .map(|(id, entity, distance)| (id, *entity.pos(), distance))
+    }
+
+    /// Find the closest player to us, and return their uuid, position, and
+    /// distance from us. See [`Client::closest_entity`].
+    pub fn closest_player(&self) -> Option<(Uuid, Vec3, f64)> {
+        let entity_id = self.entity_id();
+        let dimension = self.dimension.lock();
+        let pos = *dimension.entity(entity_id)?.pos();
+        dimension
+            .closest_entity(&pos, |id, entity| {
+                id != entity_id && entity.kind == EntityType::Player
+            })
+            .map(|(_, entity, distance)| (entity.uuid, *entity.pos(), distance))
+    }
+
+    /// The id of our own player's entity. Packets about an entity are about
+    /// us if they have this id.
+    pub fn entity_id(&self) -> u32 {
+        self.player.lock().entity_id
+    }
+
     /// Returns the entity associated to the player.
     pub fn entity_mut<'d>(&self, dimension: &'d mut Dimension) -> EntityMut<'d> {
-        let entity_id = {
-            let player_lock = self.player.lock();
-            player_lock.entity_id
-        };
+        let entity_id = self.entity_id();
         dimension
             .entity_mut(entity_id)
             .expect("Player entity should be in the given dimension")
     }
     /// Returns the entity associated to the player.
     pub fn entity<'d>(&self, dimension: &'d Dimension) -> EntityRef<'d> {
-        let entity_id = {
-            let player_lock = self.player.lock();
-            player_lock.entity_id
-        };
+        let entity_id = self.entity_id();
         dimension
             .entity(entity_id)
             .expect("Player entity should be in the given dimension")
@@ -783,3 +1652,621 @@ impl<T> From<std::sync::PoisonError<T>> for HandleError {
         HandleError::Poison(e.to_string())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use azalea_block::BlockState;
+    use azalea_chat::translatable_component::{StringOrComponent, TranslatableComponent};
+    use azalea_protocol::packets::game::{
+        clientbound_add_entity_packet::ClientboundAddEntityPacket,
+        clientbound_animate_packet::ClientboundAnimatePacket,
+        clientbound_block_entity_data_packet::ClientboundBlockEntityDataPacket,
+        clientbound_boss_event_packet::ClientboundBossEventPacket,
+        clientbound_custom_payload_packet::ClientboundCustomPayloadPacket,
+        clientbound_entity_velocity_packet::ClientboundEntityVelocityPacket,
+        clientbound_keep_alive_packet::ClientboundKeepAlivePacket,
+        clientbound_remove_entities_packet::ClientboundRemoveEntitiesPacket,
+        clientbound_respawn_packet::ClientboundRespawnPacket,
+        clientbound_set_entity_data_packet::ClientboundSetEntityDataPacket,
+        clientbound_set_equipment_packet::{ClientboundSetEquipmentPacket, EquipmentSlots},
+    };
+    use azalea_protocol::packets::{
+        handshake::ServerboundHandshakePacket,
+        login::clientbound_login_disconnect_packet::ClientboundLoginDisconnectPacket,
+    };
+    use tokio::io::{DuplexStream, ReadHalf, WriteHalf};
+
+    /// The server's side of a connection to a client in tests.
+    pub(crate) type TestServer = Connection<
+        ServerboundGamePacket,
+        ClientboundGamePacket,
+        ReadHalf<DuplexStream>,
+        WriteHalf<DuplexStream>,
+    >;
+
+    /// Create a client that's connected to an in-memory stream instead of a
+    /// real server, and the other end of that stream.
+    pub(crate) fn test_client() -> (Client, UnboundedReceiver<Event>, TestServer) {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client, rx) = Client::from_connection(
+            GameProfile::new(Uuid::nil(), "bot".to_string()),
+            GameConnection::from_stream(client_stream),
+        );
+        (client, rx, Connection::from_stream(server_stream))
+    }
+
+    #[tokio::test]
+    async fn test_join_with_wrong_protocol_version() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut conn: Connection<ServerboundHandshakePacket, ClientboundLoginPacket, _, _> =
+                Connection::from_stream(stream);
+            let ServerboundHandshakePacket::ClientIntention(p) = conn.read().await.unwrap();
+            assert_eq!(p.protocol_version, PROTOCOL_VERSION);
+            // pretend to be an older server
+            let reason = Component::Translatable(TranslatableComponent::new(
+                "multiplayer.disconnect.outdated_server".to_string(),
+                vec![StringOrComponent::String("1.18.2".to_string())],
+            ));
+            conn.write(ClientboundLoginDisconnectPacket { reason }.get())
+                .await
+                .unwrap();
+        });
+
+        let address = ServerAddress {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+        match Client::join(&Account::offline("bot"), &address).await {
+            Err(JoinError::UnsupportedProtocolVersion(_)) => {}
+            Err(e) => panic!("Expected an unsupported protocol version error, got {e}"),
+            Ok(_) => panic!("Expected joining to fail"),
+        }
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive() {
+        let (_client, _rx, mut server) = test_client();
+        server
+            .write(ClientboundKeepAlivePacket { id: 123 }.get())
+            .await
+            .unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::KeepAlive(p) => assert_eq!(p.id, 123),
+            p => panic!("Expected a keep alive packet, got {:?}", p),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_block_entity_data() {
+        let (client, _rx, mut server) = test_client();
+        client
+            .dimension
+            .lock()
+            .set_chunk(&ChunkPos::new(0, 0), Some(azalea_world::Chunk::default()))
+            .unwrap();
+        let pos = BlockPos::new(1, 64, 2);
+        server
+            .write(
+                ClientboundBlockEntityDataPacket {
+                    pos,
+                    block_entity_type: azalea_registry::BlockEntityType::Beacon,
+                    tag: azalea_nbt::Tag::Compound(
+                        [("Primary".to_string(), azalea_nbt::Tag::Int(1))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        // packets are handled in order, so once the keep alive is answered
+        // the block entity is there
+        server
+            .write(ClientboundKeepAlivePacket { id: 0 }.get())
+            .await
+            .unwrap();
+        server.read().await.unwrap();
+        let block_entity = client.dimension.lock().block_entity(&pos).unwrap();
+        assert_eq!(block_entity.kind, azalea_registry::BlockEntityType::Beacon);
+        assert_eq!(
+            block_entity.data.as_compound().unwrap().get("Primary"),
+            Some(&azalea_nbt::Tag::Int(1))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_closest_entity() {
+        let (client, _rx, _server) = test_client();
+        assert!(client.closest_player().is_none());
+
+        client.player.lock().set_entity_id(1);
+        let mut dimension = client.dimension.lock();
+        dimension.add_entity(1, EntityData::new(Uuid::from_u128(1), Vec3::default()));
+        let mut cow = EntityData::new(
+            Uuid::from_u128(2),
+            Vec3 {
+                x: 1.,
+                y: 0.,
+                z: 0.,
+            },
+        );
+        cow.kind = EntityType::Cow;
+        dimension.add_entity(2, cow);
+        // two players that are the same distance away
+        dimension.add_entity(
+            4,
+            EntityData::new(
+                Uuid::from_u128(4),
+                Vec3 {
+                    x: 0.,
+                    y: 0.,
+                    z: 3.,
+                },
+            ),
+        );
+        dimension.add_entity(
+            3,
+            EntityData::new(
+                Uuid::from_u128(3),
+                Vec3 {
+                    x: -3.,
+                    y: 0.,
+                    z: 0.,
+                },
+            ),
+        );
+        drop(dimension);
+
+        let (uuid, pos, distance) = client.closest_player().unwrap();
+        assert_eq!(uuid, Uuid::from_u128(3));
+        assert_eq!(
+            pos,
+            Vec3 {
+                x: -3.,
+                y: 0.,
+                z: 0.
+            }
+        );
+        assert_eq!(distance, 3.);
+
+        assert_eq!(client.closest_entity(|_| true).unwrap().0, 2);
+        assert!(client
+            .closest_entity(|entity| entity.kind == EntityType::Zombie)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_boss_bar_update() {
+        let (_client, mut rx, mut server) = test_client();
+        for (id, operation) in [
+            (1, BossEventOperation::UpdateProgress(0.5)),
+            (2, BossEventOperation::Remove),
+        ] {
+            server
+                .write(
+                    ClientboundBossEventPacket {
+                        id: Uuid::from_u128(id),
+                        operation,
+                    }
+                    .get(),
+                )
+                .await
+                .unwrap();
+        }
+        loop {
+            match rx.recv().await.unwrap() {
+                // the update for the boss bar we don't know about is skipped
+                // instead of looking like it was removed
+                Event::BossBarUpdate(id, boss_bar) => {
+                    assert_eq!(id, Uuid::from_u128(2));
+                    assert!(boss_bar.is_none());
+                    break;
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remove_entities() {
+        let (client, mut rx, mut server) = test_client();
+        client
+            .dimension
+            .lock()
+            .add_entity(3, EntityData::new(Uuid::nil(), Vec3::default()));
+        server
+            .write(
+                ClientboundRemoveEntitiesPacket {
+                    entity_ids: vec![4, 3],
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        loop {
+            match rx.recv().await.unwrap() {
+                // 4 was never added, so there's no event for it
+                Event::EntityRemoved(entity_id) => {
+                    assert_eq!(entity_id, 3);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        assert!(client.dimension.lock().entity(3).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_entity_animation() {
+        let (_client, mut rx, mut server) = test_client();
+        server
+            .write(
+                ClientboundAnimatePacket {
+                    id: 42,
+                    action: AnimationAction::CriticalHit,
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        loop {
+            match rx.recv().await.unwrap() {
+                Event::EntityAnimation { entity_id, kind } => {
+                    assert_eq!(entity_id, 42);
+                    assert_eq!(kind, AnimationAction::CriticalHit);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entity_equipment() {
+        let (client, mut rx, mut server) = test_client();
+        client
+            .dimension
+            .lock()
+            .add_entity(2, EntityData::new(Uuid::nil(), Vec3::default()));
+        server
+            .write(
+                ClientboundSetEquipmentPacket {
+                    entity: 2,
+                    slots: EquipmentSlots {
+                        slots: vec![(EquipmentSlot::Head, Slot::Empty)],
+                    },
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        loop {
+            match rx.recv().await.unwrap() {
+                Event::EntityEquipmentChanged { entity_id, slots } => {
+                    assert_eq!(entity_id, 2);
+                    assert_eq!(slots.len(), 1);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        let dimension = client.dimension.lock();
+        let entity = dimension.entity(2).unwrap();
+        assert!(matches!(
+            entity.equipment().get(&EquipmentSlot::Head),
+            Some(Slot::Empty)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_entity_data_flags() {
+        let (client, _rx, mut server) = test_client();
+        client
+            .dimension
+            .lock()
+            .add_entity(2, EntityData::new(Uuid::nil(), Vec3::default()));
+        server
+            .write(
+                ClientboundSetEntityDataPacket {
+                    id: 2,
+                    packed_items: vec![azalea_world::entity::EntityDataItem {
+                        index: 0,
+                        // glowing
+                        value: azalea_world::entity::EntityDataValue::Byte(0x40),
+                    }]
+                    .into(),
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        server
+            .write(ClientboundKeepAlivePacket { id: 0 }.get())
+            .await
+            .unwrap();
+        server.read().await.unwrap();
+        let dimension = client.dimension.lock();
+        let entity = dimension.entity(2).unwrap();
+        assert!(entity.is_glowing());
+        assert!(!entity.is_invisible());
+    }
+
+    #[tokio::test]
+    async fn test_add_entity_dimensions() {
+        let (client, _rx, mut server) = test_client();
+        for (id, entity_type) in [(2, EntityType::Zombie), (3, EntityType::Chicken)] {
+            server
+                .write(
+                    ClientboundAddEntityPacket {
+                        id,
+                        uuid: Uuid::from_u128(id as u128),
+                        entity_type,
+                        x: 0.,
+                        y: 64.,
+                        z: 0.,
+                        x_rot: 0,
+                        y_rot: 0,
+                        y_head_rot: 0,
+                        data: 0,
+                        x_vel: 0,
+                        y_vel: 0,
+                        z_vel: 0,
+                    }
+                    .get(),
+                )
+                .await
+                .unwrap();
+        }
+        server
+            .write(ClientboundKeepAlivePacket { id: 0 }.get())
+            .await
+            .unwrap();
+        server.read().await.unwrap();
+
+        let dimension = client.dimension.lock();
+        let zombie = dimension.entity(2).unwrap();
+        assert_eq!(zombie.dimensions.height, 1.95);
+        assert_eq!(zombie.bounding_box.max_y, 64. + 1.95f32 as f64);
+        assert_eq!(zombie.eye_height(), 1.74);
+        let chicken = dimension.entity(3).unwrap();
+        assert!((chicken.eye_height() - 0.644).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_reset_state() {
+        let (client, _rx, _server) = test_client();
+        client.inventory.lock().selected_hotbar_slot = 4;
+        client.tab_list.lock().header = Some("header".to_string().into());
+        client.local_player.lock().experience.level = 30;
+
+        client.reset_state();
+        assert_eq!(client.inventory.lock().selected_hotbar_slot, 0);
+        assert!(client.tab_list.lock().header.is_none());
+        assert_eq!(client.local_player.lock().experience.level, 0);
+    }
+
+    #[tokio::test]
+    async fn test_respawn_in_other_dimension() {
+        let (client, mut rx, mut server) = test_client();
+        client.player.lock().set_entity_id(1);
+        let nether = azalea_world::DimensionType {
+            id: 1,
+            min_y: 0,
+            height: 256,
+            logical_height: 128,
+            has_skylight: false,
+            has_ceiling: true,
+            ultrawarm: true,
+            natural: false,
+            coordinate_scale: 8.,
+        };
+        client.registries.lock().dimension_types.insert(
+            ResourceLocation::new("minecraft:the_nether").unwrap(),
+            nether,
+        );
+        {
+            let mut dimension = client.dimension.lock();
+            *dimension = Dimension::new(16, 384, -64);
+            dimension
+                .set_chunk(&ChunkPos::new(0, 0), Some(azalea_world::Chunk::default()))
+                .unwrap();
+        }
+        client
+            .item_use_state
+            .lock()
+            .predict(BlockPos::new(1, 2, 3), BlockState::Stone);
+        let goto = tokio::spawn({
+            let client = client.clone();
+            async move { client.goto(BlockPos::new(5, 0, 5)).await }
+        });
+        while client.pathfinder.lock().goal().is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        server
+            .write(
+                ClientboundRespawnPacket {
+                    dimension_type: ResourceLocation::new("minecraft:the_nether").unwrap(),
+                    dimension: ResourceLocation::new("minecraft:the_nether").unwrap(),
+                    seed: 0,
+                    player_game_type: GameType::SURVIVAL,
+                    previous_player_game_type: None.into(),
+                    is_debug: false,
+                    is_flat: false,
+                    keep_all_player_data: false,
+                    last_death_location: None,
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        loop {
+            match rx.recv().await.unwrap() {
+                Event::DimensionChanged(dimension) => {
+                    assert_eq!(dimension.to_string(), "minecraft:the_nether");
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        assert_eq!(goto.await.unwrap(), Err(PathfindError::Interrupted));
+        assert!(client.item_use_state.lock().predictions.is_empty());
+        let dimension = client.dimension.lock();
+        assert_eq!(dimension.min_y(), 0);
+        assert_eq!(dimension.height(), 256);
+        assert!(dimension.get_block_state(&BlockPos::new(0, 0, 0)).is_none());
+        assert!(dimension.entity(1).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_plugin_messages() {
+        let (client, mut rx, mut server) = test_client();
+        let channel = ResourceLocation::new("bungeecord:main").unwrap();
+        server
+            .write(
+                ClientboundCustomPayloadPacket {
+                    identifier: channel.clone(),
+                    data: vec![1, 2, 3].into(),
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        loop {
+            match rx.recv().await.unwrap() {
+                Event::PluginMessage { channel: c, data } => {
+                    assert_eq!(c, channel);
+                    assert_eq!(data, vec![1, 2, 3]);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+
+        client
+            .send_plugin_message(channel.clone(), b"PlayerCount")
+            .await
+            .unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::CustomPayload(p) => {
+                assert_eq!(p.identifier, channel);
+                assert_eq!(p.data.to_vec(), b"PlayerCount".to_vec());
+            }
+            p => panic!("Expected a custom payload packet, got {:?}", p),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_packet_queue() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let (client, _rx) = Client::from_connection_with_capacity(
+            GameProfile::new(Uuid::nil(), "bot".to_string()),
+            GameConnection::from_stream(client_stream),
+            1,
+        );
+        let mut server: TestServer = Connection::from_stream(server_stream);
+        // keep alives are answered right away, so holding the write
+        // connection makes the handler wait at the first one
+        let write_conn = client.write_conn.lock().await;
+        for id in 0..10 {
+            server
+                .write(ClientboundKeepAlivePacket { id }.get())
+                .await
+                .unwrap();
+        }
+        while client.stats().packets_read < 3 {
+            tokio::task::yield_now().await;
+        }
+        time::sleep(time::Duration::from_millis(50)).await;
+        // one packet is being handled, one is in the queue, and one is
+        // waiting for space in the queue, so nothing else should be read
+        assert_eq!(client.stats().packets_read, 3);
+
+        drop(write_conn);
+        for id in 0..10 {
+            match server.read().await.unwrap() {
+                ServerboundGamePacket::KeepAlive(p) => assert_eq!(p.id, id),
+                p => panic!("Expected a keep alive packet, got {:?}", p),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_knockback() {
+        let (client, mut rx, mut server) = test_client();
+        client.player.lock().set_entity_id(1);
+        client
+            .dimension
+            .lock()
+            .add_entity(1, EntityData::new(Uuid::nil(), Vec3::default()));
+        server
+            .write(
+                ClientboundEntityVelocityPacket {
+                    entity_id: 1,
+                    x_vel: 4000,
+                    y_vel: 2000,
+                    z_vel: -8000,
+                }
+                .get(),
+            )
+            .await
+            .unwrap();
+        let velocity = Vec3 {
+            x: 0.5,
+            y: 0.25,
+            z: -1.,
+        };
+        loop {
+            match rx.recv().await.unwrap() {
+                Event::Knockback(knockback) => {
+                    assert_eq!(knockback, velocity);
+                    break;
+                }
+                _ => continue,
+            }
+        }
+        assert_eq!(client.entity(&client.dimension.lock()).delta, velocity);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect() {
+        let (client, mut rx, mut server) = test_client();
+        client.write_conn.lock().await.auto_flush = false;
+        client
+            .write(ServerboundKeepAlivePacket { id: 5 })
+            .await
+            .unwrap();
+        client.disconnect().await.unwrap();
+        // the packet that was waiting to be flushed is still sent
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::KeepAlive(p) => assert_eq!(p.id, 5),
+            p => panic!("Expected a keep alive packet, got {:?}", p),
+        }
+        assert!(server.read().await.is_err());
+        let mut last_event = None;
+        while let Some(event) = rx.recv().await {
+            last_event = Some(event);
+        }
+        match last_event {
+            Some(Event::Disconnect(reason)) => {
+                assert_eq!(reason.to_string(), "Client disconnected")
+            }
+            e => panic!("Expected a disconnect event, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_closed() {
+        let (client, _rx, server) = test_client();
+        drop(server);
+        // the client can't write to a stream that's been closed
+        let result = client.write(ServerboundKeepAlivePacket { id: 0 }).await;
+        assert!(result.is_err());
+    }
+}
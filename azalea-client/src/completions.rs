@@ -0,0 +1,137 @@
+//! Asking the server how a command could be finished, like when pressing tab
+//! in the chat box.
+
+use crate::{pending, Client};
+use azalea_protocol::packets::game::{
+    clientbound_command_suggestions_packet::ClientboundCommandSuggestionsPacket,
+    serverbound_command_suggestion_packet::ServerboundCommandSuggestionPacket,
+};
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long [`Client::request_completions`] waits for the server to answer.
+pub const DEFAULT_COMPLETIONS_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Error, Debug)]
+pub enum CompletionsError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("The server didn't send completions in time")]
+    Timeout,
+}
+
+impl Client {
+    /// Ask the server how the command we're typing could be finished. The
+    /// text should usually start with a `/`. The suggestions replace the
+    /// word at the end of the text, so asking for `/gamem` gives
+    /// `gamemode`.
+    ///
+    /// If the server doesn't answer within [`DEFAULT_COMPLETIONS_TIMEOUT`],
+    /// [`CompletionsError::Timeout`] is returned.
+    pub async fn request_completions(&self, text: &str) -> Result<Vec<String>, CompletionsError> {
+        self.request_completions_with_timeout(text, DEFAULT_COMPLETIONS_TIMEOUT)
+            .await
+    }
+
+    /// Like [`Client::request_completions`], but with a custom timeout.
+    pub async fn request_completions_with_timeout(
+        &self,
+        text: &str,
+        timeout: Duration,
+    ) -> Result<Vec<String>, CompletionsError> {
+        let (id, receiver) = self.pending_completions.lock().register_next();
+        if let Err(e) = self
+            .write_packet(
+                ServerboundCommandSuggestionPacket {
+                    id,
+                    command: text.to_string(),
+                }
+                .get(),
+            )
+            .await
+        {
+            // the server will never answer a request it didn't get
+            self.pending_completions.lock().forget(&id);
+            return Err(e.into());
+        }
+        pending::wait(&self.pending_completions, id, receiver, timeout)
+            .await
+            .ok_or(CompletionsError::Timeout)
+    }
+}
+
+/// Pass the suggestions from the server to the request that asked for them.
+/// Answers to requests we aren't waiting for anymore are ignored.
+pub(crate) fn handle_suggestions(client: &Client, packet: &ClientboundCommandSuggestionsPacket) {
+    let suggestions = packet.suggestions.iter().map(|s| s.text.clone()).collect();
+    client
+        .pending_completions
+        .lock()
+        .resolve(&packet.id, suggestions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_protocol::packets::game::{
+        clientbound_command_suggestions_packet::CommandSuggestion, ServerboundGamePacket,
+    };
+
+    #[tokio::test]
+    async fn test_request_completions() {
+        let (client, _rx, mut server) = test_client();
+        let request = tokio::spawn({
+            let client = client.clone();
+            async move { client.request_completions("/gamem").await }
+        });
+        let id = match server.read().await.unwrap() {
+            ServerboundGamePacket::CommandSuggestion(p) => {
+                assert_eq!(p.command, "/gamem");
+                p.id
+            }
+            p => panic!("Expected a command suggestion packet, got {:?}", p),
+        };
+        // an answer to some other request is ignored
+        for id in [id + 1, id] {
+            server
+                .write(
+                    ClientboundCommandSuggestionsPacket {
+                        id,
+                        start: 1,
+                        length: 5,
+                        suggestions: vec![CommandSuggestion {
+                            text: format!("gamemode{}", id),
+                            tooltip: None,
+                        }],
+                    }
+                    .get(),
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            request.await.unwrap().unwrap(),
+            vec![format!("gamemode{}", id)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completions_timeout() {
+        let (client, _rx, _server) = test_client();
+        let result = client
+            .request_completions_with_timeout("/gamem", Duration::from_millis(10))
+            .await;
+        assert!(matches!(result, Err(CompletionsError::Timeout)));
+        assert!(!client.pending_completions.lock().resolve(&0, Vec::new()));
+    }
+
+    #[tokio::test]
+    async fn test_completions_write_error() {
+        let (client, _rx, server) = test_client();
+        drop(server);
+        let result = client.request_completions("/gamem").await;
+        assert!(matches!(result, Err(CompletionsError::Io(_))));
+        assert!(client.pending_completions.lock().is_empty());
+    }
+}
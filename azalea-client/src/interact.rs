@@ -0,0 +1,307 @@
+use crate::{auto_eat, Client};
+use azalea_block::BlockState;
+use azalea_core::{BlockPos, Direction, Slot, Vec3};
+use azalea_protocol::packets::game::{
+    serverbound_interact_packet::InteractionHand,
+    serverbound_player_action_packet::{self, ServerboundPlayerActionPacket},
+    serverbound_use_item_on_packet::{BlockHitResult, ServerboundUseItemOnPacket},
+    serverbound_use_item_packet::ServerboundUseItemPacket,
+};
+use azalea_registry::Item;
+use std::collections::HashMap;
+
+/// The entity event id that the server sends when the player finishes using
+/// an item (like eating food or drinking a potion).
+pub(crate) const FINISHED_USING_ITEM_EVENT_ID: u8 = 9;
+
+/// Whether the item is used over time when it's right clicked, like food or
+/// a bow. Other items are used instantly or don't do anything.
+pub fn has_use_duration(item: Item) -> bool {
+    auto_eat::nutrition(item).is_some()
+        || matches!(
+            item,
+            Item::Bow
+                | Item::Crossbow
+                | Item::Shield
+                | Item::Trident
+                | Item::Potion
+                | Item::MilkBucket
+                | Item::Spyglass
+                | Item::GoatHorn
+        )
+}
+
+#[derive(Default)]
+pub struct ItemUseState {
+    /// The sequence number for the next block or item interaction. The server
+    /// uses this to acknowledge our actions.
+    pub sequence: u32,
+    /// The hand we're currently holding right click with, if any.
+    pub using_item: Option<InteractionHand>,
+    /// The number of ticks left until we can use each item again.
+    pub cooldowns: HashMap<Item, u32>,
+    /// The blocks we changed locally with [`Client::predict_block_state`]
+    /// before the server confirmed them, and what the server last told us
+    /// was actually there.
+    pub predictions: HashMap<BlockPos, BlockPrediction>,
+}
+
+/// A block that we changed before the server acknowledged the interaction
+/// that changed it.
+#[derive(Clone, Copy, Debug)]
+pub struct BlockPrediction {
+    /// The sequence number of the interaction that changed the block.
+    pub sequence: u32,
+    /// The state the server says the block has. This is what the block gets
+    /// set to when the interaction is acknowledged.
+    pub server_state: BlockState,
+}
+
+impl ItemUseState {
+    /// Get the next sequence number for an interaction.
+    pub fn next_sequence(&mut self) -> u32 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// Remember that we're about to change the block at `pos` as part of the
+    /// current interaction. If the block was already predicted, we keep the
+    /// server state we had before.
+    pub fn predict(&mut self, pos: BlockPos, server_state: BlockState) {
+        let sequence = self.sequence;
+        self.predictions
+            .entry(pos)
+            .and_modify(|prediction| prediction.sequence = sequence)
+            .or_insert(BlockPrediction {
+                sequence,
+                server_state,
+            });
+    }
+
+    /// The server told us the block at `pos` changed. Returns whether the
+    /// change should be applied now, which is only the case if we aren't
+    /// waiting for the server to acknowledge a prediction there.
+    pub fn update_server_state(&mut self, pos: BlockPos, state: BlockState) -> bool {
+        match self.predictions.get_mut(&pos) {
+            Some(prediction) => {
+                prediction.server_state = state;
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// The server acknowledged every interaction up to `sequence`. Returns the
+    /// blocks that should be set back to what the server says they are.
+    pub fn acknowledge(&mut self, sequence: u32) -> Vec<(BlockPos, BlockState)> {
+        let mut acknowledged = Vec::new();
+        self.predictions.retain(|pos, prediction| {
+            if prediction.sequence <= sequence {
+                acknowledged.push((*pos, prediction.server_state));
+                false
+            } else {
+                true
+            }
+        });
+        acknowledged
+    }
+
+    /// Decrease all the cooldowns by one tick, removing the ones that ended.
+    pub fn tick_cooldowns(&mut self) {
+        self.cooldowns.retain(|_, ticks| {
+            *ticks = ticks.saturating_sub(1);
+            *ticks > 0
+        });
+    }
+}
+
+impl Client {
+    /// Start using (right clicking) the item in the given hand. This is what
+    /// you'd use to eat food, draw a bow, or throw a snowball.
+    ///
+    /// Items like food keep being used until the server tells us we finished,
+    /// at which point an [`Event::ItemUseFinished`] is sent. Items like bows
+    /// are used until you call [`Client::release_use_item`]. Other items,
+    /// like snowballs, are used instantly, so [`Client::is_using_item`] stays
+    /// false. If the item doesn't do anything when used, the server ignores
+    /// it, so this is a no-op.
+    ///
+    /// [`Event::ItemUseFinished`]: crate::Event::ItemUseFinished
+    pub async fn use_item(&self, hand: InteractionHand) -> Result<(), std::io::Error> {
+        let used_over_time = match self.inventory.lock().held_item(hand) {
+            Slot::Present(data) => data.item().map_or(false, has_use_duration),
+            Slot::Empty => false,
+        };
+        let sequence = {
+            let mut item_use_state = self.item_use_state.lock();
+            if used_over_time {
+                item_use_state.using_item = Some(hand);
+            }
+            item_use_state.next_sequence()
+        };
+        self.write_packet(ServerboundUseItemPacket { hand, sequence }.get())
+            .await
+    }
+
+    /// Right click on the given side of a block, like to open a door or press
+    /// a button. We click on the middle of that side of the block.
+    pub async fn use_block(
+        &self,
+        hand: InteractionHand,
+        pos: BlockPos,
+        direction: Direction,
+    ) -> Result<(), std::io::Error> {
+        let sequence = self.item_use_state.lock().next_sequence();
+        let normal = direction.normal();
+        let location = Vec3 {
+            x: pos.x as f64 + 0.5 + normal.x as f64 * 0.5,
+            y: pos.y as f64 + 0.5 + normal.y as f64 * 0.5,
+            z: pos.z as f64 + 0.5 + normal.z as f64 * 0.5,
+        };
+        self.write_packet(
+            ServerboundUseItemOnPacket {
+                hand,
+                block_hit: BlockHitResult {
+                    block_pos: pos,
+                    direction,
+                    location,
+                    inside: false,
+                },
+                sequence,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Stop using the item that we're currently using, for example to shoot a
+    /// bow. Does nothing if we aren't using an item.
+    pub async fn release_use_item(&self) -> Result<(), std::io::Error> {
+        if self.item_use_state.lock().using_item.take().is_none() {
+            return Ok(());
+        }
+        self.write_packet(
+            ServerboundPlayerActionPacket {
+                action: serverbound_player_action_packet::Action::ReleaseUseItem,
+                pos: BlockPos::default(),
+                direction: Direction::Down,
+                sequence: 0,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Change a block locally before the server tells us about it, like when
+    /// we place or break a block. This should be called right after the
+    /// interaction that changes the block. Block updates from the server for
+    /// this position are held back until the server acknowledges the
+    /// interaction, so the block doesn't flicker back and forth.
+    ///
+    /// Azalea doesn't predict any block changes itself, since nothing in it
+    /// places or breaks blocks yet, so only positions passed to this are
+    /// held back.
+    pub fn predict_block_state(&self, pos: BlockPos, state: BlockState) {
+        let server_state = match self.dimension.lock().get_block_state(&pos) {
+            Some(server_state) => server_state,
+            // the chunk isn't loaded
+            None => return,
+        };
+        self.item_use_state.lock().predict(pos, server_state);
+        self.set_block_states([(pos, state)]);
+    }
+
+    /// Whether we're currently holding right click with an item.
+    pub fn is_using_item(&self) -> bool {
+        self.item_use_state.lock().using_item.is_some()
+    }
+
+    /// Whether the given item is on cooldown, like an ender pearl that was
+    /// just thrown.
+    pub fn is_on_cooldown(&self, item: Item) -> bool {
+        self.item_use_state.lock().cooldowns.contains_key(&item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_core::SlotData;
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+
+    fn hold(client: &Client, item: Item) {
+        let mut inventory = client.inventory.lock();
+        let selected = 36 + inventory.selected_hotbar_slot as usize;
+        inventory.slots[selected] = Slot::Present(SlotData {
+            id: item as i32,
+            count: 1,
+            nbt: Default::default(),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_use_item_over_time() {
+        let (client, _rx, mut server) = test_client();
+        hold(&client, Item::Bow);
+        client.use_item(InteractionHand::MainHand).await.unwrap();
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::UseItem(_)
+        ));
+        assert!(client.is_using_item());
+
+        client.release_use_item().await.unwrap();
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::PlayerAction(_)
+        ));
+        assert!(!client.is_using_item());
+    }
+
+    #[tokio::test]
+    async fn test_use_item_instantly() {
+        let (client, _rx, mut server) = test_client();
+        for item in [Item::Stick, Item::Stone, Item::Snowball] {
+            hold(&client, item);
+            client.use_item(InteractionHand::MainHand).await.unwrap();
+            assert!(matches!(
+                server.read().await.unwrap(),
+                ServerboundGamePacket::UseItem(_)
+            ));
+            assert!(!client.is_using_item(), "{:?}", item);
+        }
+        // nothing in the offhand
+        client.use_item(InteractionHand::OffHand).await.unwrap();
+        assert!(!client.is_using_item());
+    }
+
+    #[test]
+    fn test_has_use_duration() {
+        assert!(has_use_duration(Item::Bread));
+        assert!(has_use_duration(Item::Shield));
+        assert!(!has_use_duration(Item::Stick));
+        assert!(!has_use_duration(Item::EnderPearl));
+    }
+
+    #[test]
+    fn test_predictions() {
+        let mut state = ItemUseState::default();
+        let pos = BlockPos::new(1, 2, 3);
+        state.next_sequence();
+        state.predict(pos, BlockState::Air);
+        // the server sending the old block again shouldn't undo our prediction
+        assert!(!state.update_server_state(pos, BlockState::Stone));
+        assert!(state.update_server_state(BlockPos::new(0, 0, 0), BlockState::Stone));
+
+        // a second interaction on the same block keeps the server state
+        state.next_sequence();
+        state.predict(pos, BlockState::Dirt);
+        assert!(state.acknowledge(1).is_empty());
+        let acknowledged = state.acknowledge(2);
+        assert_eq!(acknowledged.len(), 1);
+        assert_eq!(acknowledged[0].0, pos);
+        assert_eq!(acknowledged[0].1, BlockState::Stone);
+        assert!(state.predictions.is_empty());
+    }
+}
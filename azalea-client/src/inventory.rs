@@ -0,0 +1,501 @@
+use crate::{auto_eat, Client};
+use azalea_block::BlockState;
+use azalea_core::{BlockPos, Direction, ResourceLocation, Slot};
+use azalea_protocol::packets::game::{
+    clientbound_container_set_content_packet::ClientboundContainerSetContentPacket,
+    clientbound_container_set_slot_packet::ClientboundContainerSetSlotPacket,
+    serverbound_container_click_packet::{ClickType, ServerboundContainerClickPacket},
+    serverbound_interact_packet::InteractionHand,
+    serverbound_player_action_packet::{self, ServerboundPlayerActionPacket},
+    serverbound_set_carried_item_packet::ServerboundSetCarriedItemPacket,
+};
+use azalea_world::mining::{self, Tool};
+
+/// The id of the container that's always open, which is our own inventory.
+const PLAYER_INVENTORY_CONTAINER_ID: u8 = 0;
+/// The container id the server uses to set a slot in our inventory without
+/// the inventory being open. This is -2 in vanilla.
+const SET_INVENTORY_SLOT_CONTAINER_ID: u8 = (-2i8) as u8;
+
+/// How many slots are in the player inventory menu, including the crafting
+/// grid and armor.
+const INVENTORY_SIZE: usize = 46;
+/// The index of the first hotbar slot in the player inventory menu.
+const HOTBAR_START: usize = 36;
+/// The index of the offhand slot in the player inventory menu.
+const OFFHAND_SLOT: usize = 45;
+
+/// The items in our inventory. The slots are in the same order as the player
+/// inventory menu, so 5-8 are armor, 9-35 are the main inventory, 36-44 are
+/// the hotbar, and 45 is the offhand.
+#[derive(Clone, Debug)]
+pub struct Inventory {
+    pub slots: Vec<Slot>,
+    /// The hotbar slot we're holding, from 0 to 8.
+    pub selected_hotbar_slot: u8,
+    /// The last state id the server sent for our inventory, which we have to
+    /// send back when we click on a slot.
+    pub state_id: u32,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: vec![Slot::Empty; INVENTORY_SIZE],
+            selected_hotbar_slot: 0,
+            state_id: 0,
+        }
+    }
+}
+
+impl Inventory {
+    pub fn apply_container_set_content(&mut self, packet: &ClientboundContainerSetContentPacket) {
+        if packet.container_id != PLAYER_INVENTORY_CONTAINER_ID {
+            return;
+        }
+        self.slots = packet.items.clone();
+        self.slots.resize(INVENTORY_SIZE, Slot::Empty);
+        self.state_id = packet.state_id;
+    }
+
+    pub fn apply_container_set_slot(&mut self, packet: &ClientboundContainerSetSlotPacket) {
+        let index = match packet.container_id {
+            PLAYER_INVENTORY_CONTAINER_ID => {
+                self.state_id = packet.state_id;
+                packet.slot as usize
+            }
+            // this uses the indexes of the inventory itself instead of the
+            // menu, so the hotbar comes first
+            SET_INVENTORY_SLOT_CONTAINER_ID => match packet.slot as usize {
+                slot @ 0..=8 => HOTBAR_START + slot,
+                slot @ 9..=35 => slot,
+                slot @ 36..=39 => 8 - (slot - 36),
+                40 => OFFHAND_SLOT,
+                _ => return,
+            },
+            _ => return,
+        };
+        if let Some(slot) = self.slots.get_mut(index) {
+            *slot = packet.item_stack.clone();
+        }
+    }
+
+    /// Get the item in a hotbar slot, from 0 to 8.
+    pub fn hotbar_slot(&self, hotbar_slot: u8) -> &Slot {
+        &self.slots[HOTBAR_START + hotbar_slot as usize]
+    }
+
+    /// Take one item (or the whole stack) out of the slot, like when it's
+    /// dropped.
+    fn remove_from_slot(&mut self, index: usize, whole_stack: bool) {
+        let slot = match self.slots.get_mut(index) {
+            Some(slot) => slot,
+            None => return,
+        };
+        if let Slot::Present(data) = slot {
+            if whole_stack || data.count <= 1 {
+                *slot = Slot::Empty;
+            } else {
+                data.count -= 1;
+            }
+        }
+    }
+
+    /// Get the item we're holding in our main hand.
+    pub fn selected_item(&self) -> &Slot {
+        self.hotbar_slot(self.selected_hotbar_slot)
+    }
+
+    /// Get the item we're holding in the given hand.
+    pub fn held_item(&self, hand: InteractionHand) -> &Slot {
+        match hand {
+            InteractionHand::MainHand => self.selected_item(),
+            InteractionHand::OffHand => &self.slots[OFFHAND_SLOT],
+        }
+    }
+
+    /// Get the hotbar slot with the tool that mines the block the fastest, or
+    /// `None` if none of them are better than an empty hand.
+    ///
+    /// Tools that make the block drop its items are always picked over ones
+    /// that don't, even if they're slower. Efficiency is taken into account,
+    /// and tools that would break if they were used again are skipped.
+    pub fn best_tool_for(&self, block: BlockState) -> Option<u8> {
+        let efficiency_id = ResourceLocation::new("minecraft:efficiency").unwrap();
+        let mut best = None;
+        let mut best_score = (mining::can_harvest(None, block), 1.);
+        for hotbar_slot in 0..9 {
+            let data = match self.hotbar_slot(hotbar_slot) {
+                Slot::Present(data) => data,
+                Slot::Empty => continue,
+            };
+            let tool = match data.item().and_then(Tool::from_item) {
+                Some(tool) => tool,
+                None => continue,
+            };
+            if matches!(data.durability(), Some(durability) if durability <= 1) {
+                continue;
+            }
+            let mut speed = mining::destroy_speed(Some(tool), block);
+            if speed > 1. {
+                let efficiency = data.enchantment_level(&efficiency_id) as f32;
+                if efficiency > 0. {
+                    speed += efficiency * efficiency + 1.;
+                }
+            }
+            let score = (mining::can_harvest(Some(tool), block), speed);
+            if score > best_score {
+                best = Some(hotbar_slot);
+                best_score = score;
+            }
+        }
+        best
+    }
+
+    /// Get the slot with the food that fills our hunger bar the most, skipping
+    /// food that's bad for us or too valuable to eat without thinking about
+    /// it. See [`auto_eat::nutrition`].
+    ///
+    /// The slot is an index in [`Inventory::slots`] in the hotbar or main
+    /// inventory. If the hotbar and main inventory have food that's just as
+    /// good, the hotbar is picked so we don't have to move anything.
+    pub fn best_food(&self) -> Option<usize> {
+        let mut best = None;
+        let mut best_nutrition = 0;
+        for index in (HOTBAR_START..HOTBAR_START + 9).chain(9..HOTBAR_START) {
+            let item = match &self.slots[index] {
+                Slot::Present(data) => data.item(),
+                Slot::Empty => continue,
+            };
+            let item = match item {
+                Some(item) if auto_eat::is_safe_to_eat(item) => item,
+                _ => continue,
+            };
+            let nutrition = auto_eat::nutrition(item).unwrap_or(0);
+            if nutrition > best_nutrition {
+                best = Some(index);
+                best_nutrition = nutrition;
+            }
+        }
+        best
+    }
+
+    /// Get the hotbar slot (from 0 to 8) that a slot index is, or `None` if
+    /// it isn't in the hotbar.
+    pub fn hotbar_slot_of(index: usize) -> Option<u8> {
+        (HOTBAR_START..HOTBAR_START + 9)
+            .contains(&index)
+            .then(|| (index - HOTBAR_START) as u8)
+    }
+}
+
+impl Client {
+    /// Get a copy of our inventory.
+    pub fn inventory(&self) -> Inventory {
+        self.inventory.lock().clone()
+    }
+
+    /// Hold the item in the given hotbar slot, from 0 to 8.
+    pub async fn set_selected_hotbar_slot(&self, hotbar_slot: u8) -> Result<(), std::io::Error> {
+        assert!(hotbar_slot < 9, "Hotbar slot must be from 0 to 8");
+        self.inventory.lock().selected_hotbar_slot = hotbar_slot;
+        self.write_packet(
+            ServerboundSetCarriedItemPacket {
+                slot: hotbar_slot as u16,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Drop one of the item we're holding, like pressing Q in vanilla.
+    pub async fn drop_item(&self) -> Result<(), std::io::Error> {
+        self.drop_held(false).await
+    }
+
+    /// Drop the whole stack we're holding, like pressing Ctrl+Q in vanilla.
+    pub async fn drop_stack(&self) -> Result<(), std::io::Error> {
+        self.drop_held(true).await
+    }
+
+    async fn drop_held(&self, whole_stack: bool) -> Result<(), std::io::Error> {
+        {
+            let mut inventory = self.inventory.lock();
+            let index = HOTBAR_START + inventory.selected_hotbar_slot as usize;
+            // the server will correct this if it's wrong
+            inventory.remove_from_slot(index, whole_stack);
+        }
+        self.write_packet(
+            ServerboundPlayerActionPacket {
+                action: if whole_stack {
+                    serverbound_player_action_packet::Action::DropAllItems
+                } else {
+                    serverbound_player_action_packet::Action::DropItem
+                },
+                pos: BlockPos::default(),
+                direction: Direction::Down,
+                sequence: 0,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Drop one item (or the whole stack) in a slot of our inventory, like
+    /// pressing Q (or Ctrl+Q) while hovering over it with the inventory open.
+    /// The slot is an index in [`Inventory::slots`], from 0 to 45.
+    pub async fn drop_slot(&self, slot: usize, whole_stack: bool) -> Result<(), std::io::Error> {
+        if slot >= INVENTORY_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Slot must be from 0 to 45, got {}", slot),
+            ));
+        }
+        let (state_id, changed_slot) = {
+            let mut inventory = self.inventory.lock();
+            inventory.remove_from_slot(slot, whole_stack);
+            (inventory.state_id, inventory.slots[slot].clone())
+        };
+        self.write_packet(
+            ServerboundContainerClickPacket {
+                container_id: PLAYER_INVENTORY_CONTAINER_ID,
+                state_id,
+                slot_num: slot as u16,
+                // 1 drops the whole stack, and 0 drops one item
+                button_num: u8::from(whole_stack),
+                click_type: ClickType::Throw,
+                changed_slots: [(slot as u16, changed_slot)].into_iter().collect(),
+                // throwing never puts anything on the cursor
+                carried_item: Slot::Empty,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Swap the items in a slot of our inventory and a hotbar slot, like
+    /// pressing a number key while hovering over the slot with the inventory
+    /// open. The slot is an index in [`Inventory::slots`], from 0 to 45, and
+    /// the hotbar slot is from 0 to 8.
+    pub async fn swap_with_hotbar(
+        &self,
+        slot: usize,
+        hotbar_slot: u8,
+    ) -> Result<(), std::io::Error> {
+        if slot >= INVENTORY_SIZE || hotbar_slot > 8 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Slot must be from 0 to 45 and hotbar slot from 0 to 8, got {} and {}",
+                    slot, hotbar_slot
+                ),
+            ));
+        }
+        let hotbar_index = HOTBAR_START + hotbar_slot as usize;
+        let (state_id, changed_slots) = {
+            let mut inventory = self.inventory.lock();
+            inventory.slots.swap(slot, hotbar_index);
+            (
+                inventory.state_id,
+                [slot, hotbar_index]
+                    .into_iter()
+                    .map(|index| (index as u16, inventory.slots[index].clone()))
+                    .collect(),
+            )
+        };
+        self.write_packet(
+            ServerboundContainerClickPacket {
+                container_id: PLAYER_INVENTORY_CONTAINER_ID,
+                state_id,
+                slot_num: slot as u16,
+                button_num: hotbar_slot,
+                click_type: ClickType::Swap,
+                changed_slots,
+                carried_item: Slot::Empty,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Get the hotbar slot with the best tool in it for mining the block.
+    /// See [`Inventory::best_tool_for`].
+    ///
+    /// Only the hotbar is checked, since we can't hold items in the rest of
+    /// the inventory without moving them first.
+    pub fn best_tool_for(&self, block: BlockState) -> Option<u8> {
+        self.inventory.lock().best_tool_for(block)
+    }
+
+    /// Hold the best tool in our hotbar for mining the block, if we have one
+    /// that's better than what we're holding. Returns the hotbar slot we're
+    /// holding now.
+    pub async fn select_best_tool_for(&self, block: BlockState) -> Result<u8, std::io::Error> {
+        let (best, selected) = {
+            let inventory = self.inventory.lock();
+            (
+                inventory.best_tool_for(block),
+                inventory.selected_hotbar_slot,
+            )
+        };
+        match best {
+            Some(best) if best != selected => {
+                self.set_selected_hotbar_slot(best).await?;
+                Ok(best)
+            }
+            _ => Ok(selected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_core::SlotData;
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+    use azalea_registry::Item;
+
+    fn item(item: Item) -> Slot {
+        Slot::Present(SlotData {
+            id: item as i32,
+            count: 1,
+            nbt: Default::default(),
+        })
+    }
+
+    fn inventory_with_hotbar(items: &[Item]) -> Inventory {
+        let mut inventory = Inventory::default();
+        for (i, &hotbar_item) in items.iter().enumerate() {
+            inventory.slots[HOTBAR_START + i] = item(hotbar_item);
+        }
+        inventory
+    }
+
+    #[test]
+    fn test_best_tool_for() {
+        let inventory = inventory_with_hotbar(&[
+            Item::Apple,
+            Item::WoodenPickaxe,
+            Item::IronShovel,
+            Item::DiamondPickaxe,
+        ]);
+        assert_eq!(inventory.best_tool_for(BlockState::Stone), Some(3));
+        assert_eq!(inventory.best_tool_for(BlockState::Sand), Some(2));
+        assert_eq!(inventory.best_tool_for(BlockState::OakPlanks), None);
+    }
+
+    #[test]
+    fn test_best_food() {
+        let inventory = inventory_with_hotbar(&[
+            Item::RottenFlesh,
+            Item::Bread,
+            Item::DiamondPickaxe,
+            Item::CookedBeef,
+            Item::EnchantedGoldenApple,
+        ]);
+        assert_eq!(inventory.best_food(), Some(HOTBAR_START + 3));
+        let mut inventory = inventory_with_hotbar(&[Item::RottenFlesh, Item::SpiderEye]);
+        assert_eq!(inventory.best_food(), None);
+
+        // food in the main inventory counts too, but the hotbar wins ties
+        inventory.slots[20] = item(Item::CookedBeef);
+        assert_eq!(inventory.best_food(), Some(20));
+        inventory.slots[HOTBAR_START + 8] = item(Item::CookedPorkchop);
+        assert_eq!(inventory.best_food(), Some(HOTBAR_START + 8));
+        assert_eq!(Inventory::hotbar_slot_of(HOTBAR_START + 8), Some(8));
+        assert_eq!(Inventory::hotbar_slot_of(20), None);
+    }
+
+    #[test]
+    fn test_harvesting_is_preferred() {
+        // the golden pickaxe is faster, but diamond ore doesn't drop anything
+        let inventory = inventory_with_hotbar(&[Item::GoldenPickaxe, Item::IronPickaxe]);
+        assert_eq!(inventory.best_tool_for(BlockState::DiamondOre), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_drop_items() {
+        let (client, _rx, mut server) = test_client();
+        {
+            let mut inventory = client.inventory.lock();
+            inventory.slots[HOTBAR_START] = Slot::Present(SlotData {
+                id: Item::Stone as i32,
+                count: 10,
+                nbt: Default::default(),
+            });
+            inventory.slots[9] = item(Item::IronAxe);
+            inventory.state_id = 7;
+        }
+
+        client.drop_item().await.unwrap();
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::PlayerAction(ServerboundPlayerActionPacket {
+                action: serverbound_player_action_packet::Action::DropItem,
+                ..
+            })
+        ));
+        assert!(matches!(
+            client.inventory.lock().selected_item(),
+            Slot::Present(data) if data.count == 9
+        ));
+
+        client.drop_stack().await.unwrap();
+        server.read().await.unwrap();
+        assert!(matches!(
+            client.inventory.lock().selected_item(),
+            Slot::Empty
+        ));
+
+        client.drop_slot(9, true).await.unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::ContainerClick(p) => {
+                assert_eq!(p.slot_num, 9);
+                assert_eq!(p.state_id, 7);
+                assert_eq!(p.button_num, 1);
+                assert!(matches!(p.changed_slots.get(&9), Some(Slot::Empty)));
+                assert!(matches!(p.carried_item, Slot::Empty));
+            }
+            p => panic!("Expected a container click packet, got {:?}", p),
+        }
+        assert!(matches!(client.inventory.lock().slots[9], Slot::Empty));
+    }
+
+    #[tokio::test]
+    async fn test_drop_one_from_slot() {
+        let (client, _rx, mut server) = test_client();
+        client.inventory.lock().slots[9] = Slot::Present(SlotData {
+            id: Item::Stone as i32,
+            count: 10,
+            nbt: Default::default(),
+        });
+
+        client.drop_slot(9, false).await.unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::ContainerClick(p) => {
+                assert_eq!(p.button_num, 0);
+                assert!(matches!(
+                    p.changed_slots.get(&9),
+                    Some(Slot::Present(data)) if data.count == 9
+                ));
+            }
+            p => panic!("Expected a container click packet, got {:?}", p),
+        }
+
+        let err = client.drop_slot(INVENTORY_SIZE, true).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_set_inventory_slot() {
+        let mut inventory = Inventory::default();
+        inventory.apply_container_set_slot(&ClientboundContainerSetSlotPacket {
+            container_id: SET_INVENTORY_SLOT_CONTAINER_ID,
+            state_id: 0,
+            slot: 2,
+            item_stack: item(Item::IronAxe),
+        });
+        assert!(matches!(inventory.hotbar_slot(2), Slot::Present(_)));
+    }
+}
@@ -1,15 +1,52 @@
 //! Significantly abstract azalea-protocol so it's actually useable for bots.
 
 mod account;
+mod auto_eat;
+mod boss_bar;
+mod chat;
 mod client;
+mod completions;
+mod interact;
+mod inventory;
+mod local_player;
+mod login_plugin;
 mod movement;
+mod pathfinder;
+mod pending;
 pub mod ping;
 mod player;
+mod resource_pack;
+mod scheduler;
+mod scoreboard;
+mod settings;
+mod sound;
+mod subscribe;
+mod tab_list;
+mod world_info;
 
-pub use account::Account;
-pub use client::{Client, Event};
-pub use movement::MoveDirection;
+pub use account::{Account, AuthError, LookupUuidError};
+pub use auto_eat::{nutrition, AutoEat};
+pub use boss_bar::BossBar;
+pub use chat::LastSeenMessagesTracker;
+pub use client::{Client, Event, DEFAULT_PACKET_QUEUE_CAPACITY};
+pub use completions::{CompletionsError, DEFAULT_COMPLETIONS_TIMEOUT};
+pub use interact::{has_use_duration, BlockPrediction, ItemUseState};
+pub use inventory::Inventory;
+pub use local_player::{Abilities, Experience, Health, LocalPlayerState, MobEffectInstance};
+pub use login_plugin::LoginPluginHandlers;
+pub use movement::{MoveDirection, MovementInput};
+pub use pathfinder::{
+    euclidean_heuristic, fall_damage, FallContext, Goal, PathfindError, Pathfinder,
+};
 pub use player::Player;
+pub use resource_pack::ResourcePackPolicy;
+pub use scheduler::{ScheduledAction, Scheduler};
+pub use scoreboard::{Objective, Scoreboard};
+pub use settings::ClientSettings;
+pub use sound::SoundKind;
+pub use subscribe::EventKind;
+pub use tab_list::TabList;
+pub use world_info::{Weather, WorldInfo};
 
 #[cfg(test)]
 mod tests {
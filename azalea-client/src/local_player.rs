@@ -0,0 +1,318 @@
+use azalea_core::Slot;
+use azalea_protocol::packets::game::{
+    clientbound_player_abilities_packet::ClientboundPlayerAbilitiesPacket,
+    clientbound_remove_mob_effect_packet::ClientboundRemoveMobEffectPacket,
+    clientbound_set_equipment_packet::{EquipmentSlot, EquipmentSlots},
+    clientbound_set_experience_packet::ClientboundSetExperiencePacket,
+    clientbound_set_health_packet::ClientboundSetHealthPacket,
+    clientbound_update_mob_effect_packet::ClientboundUpdateMobEffectPacket,
+};
+use azalea_registry::MobEffect;
+use azalea_world::entity::{EntityDataValue, EntityMetadata};
+use std::collections::HashMap;
+
+/// A status effect that's active on our player, like speed or poison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MobEffectInstance {
+    /// The level of the effect minus one, so speed II has an amplifier of 1.
+    pub amplifier: u8,
+    /// How many ticks are left until the effect runs out. This counts down
+    /// every tick, but the effect is only removed when the server says so.
+    pub duration_ticks: u32,
+    /// Whether the effect is from a beacon or conduit.
+    pub ambient: bool,
+    /// Whether the effect shows particles.
+    pub visible: bool,
+    /// Whether the effect's icon is shown.
+    pub show_icon: bool,
+}
+
+impl MobEffectInstance {
+    /// How many levels the effect has, starting from 1.
+    fn level(&self) -> f32 {
+        self.amplifier as f32 + 1.
+    }
+}
+
+/// Our experience, as shown above the hotbar.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Experience {
+    /// How full the experience bar is, from 0 to 1.
+    pub progress: f32,
+    pub level: u32,
+    /// All the experience points we've collected since we last died.
+    pub total: u32,
+}
+
+/// Our health and hunger, as shown above the hotbar.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Health {
+    /// Our health from 0 to 20, where each heart is 2.
+    pub health: f32,
+    /// How full our hunger bar is, from 0 to 20.
+    pub food: u32,
+    /// The hidden extra food that gets used up before the hunger bar goes
+    /// down.
+    pub saturation: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        // what a new player spawns with
+        Self {
+            health: 20.,
+            food: 20,
+            saturation: 5.,
+        }
+    }
+}
+
+/// What we're allowed to do because of our game mode, like flying in
+/// creative mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Abilities {
+    /// Whether we can't take damage.
+    pub invulnerable: bool,
+    pub flying: bool,
+    pub may_fly: bool,
+    /// Whether we break blocks instantly, like in creative mode.
+    pub instant_break: bool,
+    /// How fast we accelerate while flying.
+    pub flying_speed: f32,
+    /// Our walking speed. This only changes the field of view in vanilla.
+    pub walking_speed: f32,
+}
+
+impl Default for Abilities {
+    fn default() -> Self {
+        // a survival mode player
+        Self {
+            invulnerable: false,
+            flying: false,
+            may_fly: false,
+            instant_break: false,
+            flying_speed: 0.05,
+            walking_speed: 0.1,
+        }
+    }
+}
+
+impl From<&ClientboundPlayerAbilitiesPacket> for Abilities {
+    fn from(packet: &ClientboundPlayerAbilitiesPacket) -> Self {
+        Self {
+            invulnerable: packet.flags.invulnerable,
+            flying: packet.flags.flying,
+            may_fly: packet.flags.can_fly,
+            instant_break: packet.flags.instant_break,
+            flying_speed: packet.flying_speed,
+            walking_speed: packet.walking_speed,
+        }
+    }
+}
+
+/// What the server told us about our own entity. These come in the same
+/// packets as for every other entity, so the entity id has to be checked
+/// against [`Client::entity_id`](crate::Client::entity_id) first.
+#[derive(Clone, Debug, Default)]
+pub struct LocalPlayerState {
+    /// The latest value of every entity data field we've received, by index.
+    pub metadata: HashMap<u8, EntityDataValue>,
+    pub equipment: HashMap<EquipmentSlot, Slot>,
+    pub effects: HashMap<MobEffect, MobEffectInstance>,
+    pub experience: Experience,
+    /// Whether the server has told us our experience yet. The first time it
+    /// does isn't a level up, even though it's higher than the default.
+    experience_received: bool,
+    pub health: Health,
+    pub abilities: Abilities,
+}
+
+impl LocalPlayerState {
+    pub fn apply_entity_data(&mut self, metadata: &EntityMetadata) {
+        // only the fields that changed are sent
+        for item in metadata.iter() {
+            self.metadata.insert(item.index, item.value.clone());
+        }
+    }
+
+    pub fn apply_equipment(&mut self, equipment: &EquipmentSlots) {
+        for (slot, item) in &equipment.slots {
+            self.equipment.insert(*slot, item.clone());
+        }
+    }
+
+    pub fn apply_update_mob_effect(&mut self, packet: &ClientboundUpdateMobEffectPacket) {
+        self.effects.insert(
+            packet.effect,
+            MobEffectInstance {
+                amplifier: packet.effect_amplifier,
+                duration_ticks: packet.effect_duration_ticks,
+                ambient: packet.flags & 0x01 != 0,
+                visible: packet.flags & 0x02 != 0,
+                show_icon: packet.flags & 0x04 != 0,
+            },
+        );
+    }
+
+    pub fn apply_remove_mob_effect(&mut self, packet: &ClientboundRemoveMobEffectPacket) {
+        self.effects.remove(&packet.effect);
+    }
+
+    /// Update our experience. Returns true if our level went up, which is
+    /// never the case for the first packet after logging in.
+    pub fn apply_set_experience(&mut self, packet: &ClientboundSetExperiencePacket) -> bool {
+        let old_level = self.experience.level;
+        self.experience = Experience {
+            // NaN would get past clamp
+            progress: if packet.experience_progress.is_nan() {
+                0.
+            } else {
+                packet.experience_progress.clamp(0., 1.)
+            },
+            level: packet.experience_level,
+            total: packet.total_experience,
+        };
+        let first = !self.experience_received;
+        self.experience_received = true;
+        !first && self.experience.level > old_level
+    }
+
+    pub fn apply_set_health(&mut self, packet: &ClientboundSetHealthPacket) {
+        self.health = Health {
+            health: packet.health,
+            food: packet.food,
+            saturation: packet.saturation,
+        };
+    }
+
+    /// Count down the duration of our effects. This should be called every
+    /// tick.
+    pub fn tick_effects(&mut self) {
+        for effect in self.effects.values_mut() {
+            effect.duration_ticks = effect.duration_ticks.saturating_sub(1);
+        }
+    }
+
+    /// How much our movement speed is multiplied by because of the speed and
+    /// slowness effects.
+    pub fn speed_multiplier(&self) -> f32 {
+        let mut multiplier = 1.;
+        if let Some(speed) = self.effects.get(&MobEffect::Speed) {
+            multiplier *= 1. + 0.2 * speed.level();
+        }
+        if let Some(slowness) = self.effects.get(&MobEffect::Slowness) {
+            multiplier *= 1. - 0.15 * slowness.level();
+        }
+        f32::max(multiplier, 0.)
+    }
+
+    /// How much extra vertical velocity we get when jumping because of the
+    /// jump boost effect.
+    pub fn jump_boost(&self) -> f64 {
+        match self.effects.get(&MobEffect::JumpBoost) {
+            Some(jump_boost) => (0.1 * jump_boost.level()) as f64,
+            None => 0.,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_world::entity::EntityDataItem;
+
+    #[test]
+    fn test_apply_entity_data() {
+        let mut state = LocalPlayerState::default();
+        state.apply_entity_data(&EntityMetadata::from(vec![
+            EntityDataItem {
+                index: 0,
+                value: EntityDataValue::Byte(2),
+            },
+            EntityDataItem {
+                index: 9,
+                value: EntityDataValue::Float(20.),
+            },
+        ]));
+        state.apply_entity_data(&EntityMetadata::from(vec![EntityDataItem {
+            index: 9,
+            value: EntityDataValue::Float(5.),
+        }]));
+        assert!(matches!(state.metadata[&0], EntityDataValue::Byte(2)));
+        assert!(matches!(state.metadata[&9], EntityDataValue::Float(h) if h == 5.));
+    }
+
+    #[test]
+    fn test_mob_effects() {
+        let mut state = LocalPlayerState::default();
+        state.apply_update_mob_effect(&ClientboundUpdateMobEffectPacket {
+            entity_id: 1,
+            effect: MobEffect::Speed,
+            effect_amplifier: 1,
+            effect_duration_ticks: 600,
+            flags: 0x01,
+            factor_data: None,
+        });
+        assert_eq!(state.effects[&MobEffect::Speed].amplifier, 1);
+        assert!(state.effects[&MobEffect::Speed].ambient);
+        assert!((state.speed_multiplier() - 1.4).abs() < 1e-6);
+
+        state.tick_effects();
+        assert_eq!(state.effects[&MobEffect::Speed].duration_ticks, 599);
+
+        state.apply_remove_mob_effect(&ClientboundRemoveMobEffectPacket {
+            entity_id: 1,
+            effect: MobEffect::Speed,
+        });
+        assert!(state.effects.is_empty());
+        assert_eq!(state.speed_multiplier(), 1.);
+    }
+
+    #[test]
+    fn test_set_experience() {
+        let mut state = LocalPlayerState::default();
+        // we already had this level when we logged in
+        let leveled_up = state.apply_set_experience(&ClientboundSetExperiencePacket {
+            experience_progress: 0.5,
+            experience_level: 1,
+            total_experience: 10,
+        });
+        assert!(!leveled_up);
+        assert_eq!(state.experience.level, 1);
+
+        let leveled_up = state.apply_set_experience(&ClientboundSetExperiencePacket {
+            experience_progress: 1.5,
+            experience_level: 2,
+            total_experience: 20,
+        });
+        assert!(leveled_up);
+        assert_eq!(state.experience.progress, 1.);
+
+        let leveled_up = state.apply_set_experience(&ClientboundSetExperiencePacket {
+            experience_progress: f32::NAN,
+            experience_level: 2,
+            total_experience: 21,
+        });
+        assert!(!leveled_up);
+        assert_eq!(state.experience.progress, 0.);
+        assert_eq!(state.experience.total, 21);
+    }
+
+    #[test]
+    fn test_effect_multipliers() {
+        let mut state = LocalPlayerState::default();
+        for (effect, amplifier) in [(MobEffect::Slowness, 9), (MobEffect::JumpBoost, 1)] {
+            state.apply_update_mob_effect(&ClientboundUpdateMobEffectPacket {
+                entity_id: 1,
+                effect,
+                effect_amplifier: amplifier,
+                effect_duration_ticks: 20,
+                flags: 0,
+                factor_data: None,
+            });
+        }
+        // slowness x can't make us go backwards
+        assert_eq!(state.speed_multiplier(), 0.);
+        assert!((state.jump_boost() - 0.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,85 @@
+use azalea_buf::UnsizedByteArray;
+use azalea_core::ResourceLocation;
+use azalea_protocol::packets::login::{
+    clientbound_custom_query_packet::ClientboundCustomQueryPacket,
+    serverbound_custom_query_packet::ServerboundCustomQueryPacket,
+};
+use std::collections::HashMap;
+
+type LoginPluginHandler = Box<dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Answers the plugin requests that servers (and proxies like Velocity) can
+/// send while we're logging in. The server waits for a response to every
+/// request, so requests on channels without a handler are answered with an
+/// empty response, which means we don't understand the channel.
+#[derive(Default)]
+pub struct LoginPluginHandlers {
+    handlers: HashMap<ResourceLocation, LoginPluginHandler>,
+}
+
+impl LoginPluginHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests on the given channel with the handler. The handler
+    /// gets the data from the request and returns the data to respond with,
+    /// or `None` if it didn't understand the request.
+    pub fn register<F>(&mut self, channel: ResourceLocation, handler: F)
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.handlers.insert(channel, Box::new(handler));
+    }
+
+    /// Create the response for a plugin request.
+    pub fn respond(&self, packet: &ClientboundCustomQueryPacket) -> ServerboundCustomQueryPacket {
+        let data = self
+            .handlers
+            .get(&packet.identifier)
+            .and_then(|handler| handler(&packet.data))
+            .map(UnsizedByteArray::from);
+        ServerboundCustomQueryPacket {
+            transaction_id: packet.transaction_id,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(transaction_id: u32, channel: &str) -> ClientboundCustomQueryPacket {
+        ClientboundCustomQueryPacket {
+            transaction_id,
+            identifier: ResourceLocation::new(channel).unwrap(),
+            data: UnsizedByteArray::from(vec![1, 2, 3]),
+        }
+    }
+
+    #[test]
+    fn test_unhandled_channel() {
+        let handlers = LoginPluginHandlers::new();
+        let response = handlers.respond(&request(5, "velocity:player_info"));
+        assert_eq!(response.transaction_id, 5);
+        assert!(response.data.is_none());
+    }
+
+    #[test]
+    fn test_registered_channel() {
+        let mut handlers = LoginPluginHandlers::new();
+        handlers.register(
+            ResourceLocation::new("velocity:player_info").unwrap(),
+            |data| Some(data.iter().rev().copied().collect()),
+        );
+        let response = handlers.respond(&request(7, "velocity:player_info"));
+        assert_eq!(response.transaction_id, 7);
+        assert_eq!(response.data.unwrap().0, vec![3, 2, 1]);
+
+        assert!(handlers
+            .respond(&request(8, "minecraft:other"))
+            .data
+            .is_none());
+    }
+}
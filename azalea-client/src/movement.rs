@@ -1,5 +1,5 @@
 use crate::Client;
-use azalea_core::Vec3;
+use azalea_core::{BlockHitResult, GameType, Vec3};
 use azalea_physics::collision::{MovableEntity, MoverType};
 use azalea_physics::HasPhysics;
 use azalea_protocol::packets::game::{
@@ -7,10 +7,32 @@ use azalea_protocol::packets::game::{
     serverbound_move_player_pos_rot_packet::ServerboundMovePlayerPosRotPacket,
     serverbound_move_player_rot_packet::ServerboundMovePlayerRotPacket,
     serverbound_move_player_status_only_packet::ServerboundMovePlayerStatusOnlyPacket,
+    serverbound_player_abilities_packet::ServerboundPlayerAbilitiesPacket,
+    serverbound_player_command_packet::{self, ServerboundPlayerCommandPacket},
 };
-use azalea_world::MoveEntityError;
+use azalea_world::{entity::input_vector, MoveEntityError};
+use std::ops::Add;
 use thiserror::Error;
 
+/// How much slower we move while sneaking.
+const SNEAKING_SPEED_MULTIPLIER: f32 = 0.3;
+/// How tall the player's hitbox is normally.
+const STANDING_HEIGHT: f32 = 1.8;
+/// How tall the player's hitbox is while sneaking.
+const SNEAKING_HEIGHT: f32 = 1.5;
+
+/// How close we have to get to the target of [`Client::move_toward`]
+/// horizontally before we stop.
+pub(crate) const MOVE_TOWARD_TOLERANCE: f64 = 0.35;
+/// How far above our feet the target of [`Client::move_toward`] has to be for
+/// us to jump.
+const MOVE_TOWARD_JUMP_HEIGHT: f64 = 0.5;
+/// The furthest we tell the server we moved in one tick. Vanilla servers kick
+/// players for moving too quickly if a movement packet is more than 10 blocks
+/// further than they expected, so bigger moves (like from
+/// [`Client::set_pos`]) are split up over multiple ticks.
+const MAX_MOVEMENT_PER_TICK: f64 = 8.;
+
 #[derive(Error, Debug)]
 pub enum MovePlayerError {
     #[error("Player is not in world")]
@@ -28,20 +50,42 @@ impl From<MoveEntityError> for MovePlayerError {
 }
 
 impl Client {
-    /// This gets called every tick.
+    /// Tell the server where we are, if we moved or rotated. This gets called
+    /// every tick, and only the first call each tick sends anything.
+    ///
+    /// If we haven't moved in 20 ticks, our position is sent anyway so the
+    /// server knows we're still here.
     pub async fn send_position(&mut self) -> Result<(), MovePlayerError> {
         let packet = {
             let player_lock = self.player.lock();
             let mut physics_state = self.physics_state.lock();
             let mut dimension_lock = self.dimension.lock();
 
+            if physics_state.sent_position_this_tick {
+                return Ok(());
+            }
+            physics_state.sent_position_this_tick = true;
+
             let mut player_entity = player_lock
                 .entity_mut(&mut dimension_lock)
                 .expect("Player must exist");
-            let player_pos = player_entity.pos();
             let player_old_pos = player_entity.last_pos;
+            let player_pos = {
+                let pos = *player_entity.pos();
+                let distance = pos.distance_to(&player_old_pos);
+                if distance > MAX_MOVEMENT_PER_TICK {
+                    let delta = Vec3 {
+                        x: pos.x - player_old_pos.x,
+                        y: pos.y - player_old_pos.y,
+                        z: pos.z - player_old_pos.z,
+                    };
+                    player_old_pos + delta.scale(MAX_MOVEMENT_PER_TICK / distance)
+                } else {
+                    pos
+                }
+            };
 
-            // TODO: send sprinting and sneaking packets here if they changed
+            // TODO: send sprinting packets here if it changed
 
             // TODO: the camera being able to be controlled by other entities isn't implemented yet
             // if !self.is_controlled_camera() { return };
@@ -106,7 +150,7 @@ impl Client {
             };
 
             if sending_position {
-                player_entity.last_pos = *player_entity.pos();
+                player_entity.last_pos = player_pos;
                 physics_state.position_remainder = 0;
             }
             if sending_rotation {
@@ -154,54 +198,75 @@ impl Client {
         Ok(())
     }
 
-    pub fn ai_step(&mut self) {
-        self.tick_controls(None);
+    /// Move the player for this tick. If we land while flying, we stop
+    /// flying and tell the server.
+    pub async fn ai_step(&mut self) -> Result<(), std::io::Error> {
+        let sneaking = self.sneaking();
+        let flying = self.local_player.lock().abilities.flying;
+        // sneaking while flying moves us down instead of slowing us down
+        self.tick_controls((sneaking && !flying).then_some(SNEAKING_SPEED_MULTIPLIER));
+        let spectator = self.game_mode() == Some(GameType::SPECTATOR);
+
+        // copy what we need out of the other locks, so they aren't held while
+        // the physics runs
+        let (left_impulse, forward_impulse, mut physics_profile) = {
+            let physics_state = self.physics_state.lock();
+            (
+                physics_state.left_impulse,
+                physics_state.forward_impulse,
+                physics_state.physics_profile.clone(),
+            )
+        };
+        let flying_speed = {
+            let local_player = self.local_player.lock();
+            physics_profile.speed *= local_player.speed_multiplier();
+            physics_profile.jump_boost += local_player.jump_boost();
+            local_player.abilities.flying_speed
+        };
 
-        let player_lock = self.player.lock();
-        let mut dimension_lock = self.dimension.lock();
-        let mut player_entity = player_lock
-            .entity_mut(&mut dimension_lock)
-            .expect("Player must exist");
+        let landed = {
+            let player_lock = self.player.lock();
+            let mut dimension_lock = self.dimension.lock();
+            let mut player_entity = player_lock
+                .entity_mut(&mut dimension_lock)
+                .expect("Player must exist");
 
-        // server ai step
-        {
-            let physics_state = self.physics_state.lock();
-            player_entity.xxa = physics_state.left_impulse;
-            player_entity.zza = physics_state.forward_impulse;
-        }
+            // server ai step
+            player_entity.xxa = left_impulse;
+            player_entity.zza = forward_impulse;
+            player_entity.flying = flying;
+            if flying {
+                physics_profile.flying_speed = flying_speed;
+                if player_entity.is_sprinting() {
+                    physics_profile.flying_speed *= 2.;
+                }
+            }
+
+            player_entity.ai_step(&physics_profile);
 
-        player_entity.ai_step();
+            // landing stops flying, except in spectator mode where we can't
+            // land
+            let landed = flying && player_entity.on_ground && !spectator;
+            if landed {
+                player_entity.flying = false;
+            }
+            landed
+        };
+        if landed {
+            self.local_player.lock().abilities.flying = false;
+            self.write_packet(ServerboundPlayerAbilitiesPacket { is_flying: false }.get())
+                .await?;
+        }
+        Ok(())
     }
 
-    /// Update the impulse from self.move_direction. The multipler is used for sneaking.
+    /// Update the impulse from self.movement_input. The multipler is used for sneaking.
     pub(crate) fn tick_controls(&mut self, multiplier: Option<f32>) {
         let mut physics_state = self.physics_state.lock();
 
-        let mut forward_impulse: f32 = 0.;
-        let mut left_impulse: f32 = 0.;
-        let move_direction = physics_state.move_direction;
-        match move_direction {
-            MoveDirection::Forward | MoveDirection::ForwardRight | MoveDirection::ForwardLeft => {
-                forward_impulse += 1.;
-            }
-            MoveDirection::Backward
-            | MoveDirection::BackwardRight
-            | MoveDirection::BackwardLeft => {
-                forward_impulse -= 1.;
-            }
-            _ => {}
-        };
-        match move_direction {
-            MoveDirection::Right | MoveDirection::ForwardRight | MoveDirection::BackwardRight => {
-                left_impulse += 1.;
-            }
-            MoveDirection::Left | MoveDirection::ForwardLeft | MoveDirection::BackwardLeft => {
-                left_impulse -= 1.;
-            }
-            _ => {}
-        };
-        physics_state.forward_impulse = forward_impulse;
-        physics_state.left_impulse = left_impulse;
+        let movement_input = physics_state.movement_input;
+        physics_state.forward_impulse = movement_input.forward;
+        physics_state.left_impulse = movement_input.left;
 
         if let Some(multiplier) = multiplier {
             physics_state.forward_impulse *= multiplier;
@@ -211,8 +276,107 @@ impl Client {
 
     /// Start walking in the given direction.
     pub fn walk(&mut self, direction: MoveDirection) {
+        self.set_movement_input(direction.into());
+    }
+
+    /// Face the target and walk toward it. This should be called every tick
+    /// until it returns true, which means our feet reached the target and we
+    /// stopped walking.
+    ///
+    /// We jump if the target is higher than we can walk up, and if it's
+    /// directly above us we jump in place instead of walking past it. This
+    /// doesn't go around obstacles, so use [`Client::goto`] for that.
+    pub fn move_toward(&mut self, target: Vec3) -> bool {
+        let pos = {
+            let dimension = self.dimension.lock();
+            *self.entity(&dimension).pos()
+        };
+        let dx = target.x - pos.x;
+        let dy = target.y - pos.y;
+        let dz = target.z - pos.z;
+        let horizontal_distance = (dx * dx + dz * dz).sqrt();
+
+        let close = horizontal_distance < MOVE_TOWARD_TOLERANCE;
+        let above = dy > MOVE_TOWARD_JUMP_HEIGHT;
+        if close && !above && dy > -MOVE_TOWARD_JUMP_HEIGHT {
+            self.walk(MoveDirection::None);
+            self.set_jumping(false);
+            return true;
+        }
+
+        {
+            let mut dimension = self.dimension.lock();
+            let mut player_entity = self.entity_mut(&mut dimension);
+            let y_rot = if close {
+                player_entity.y_rot
+            } else {
+                (-dx).atan2(dz).to_degrees() as f32
+            };
+            let x_rot = -(dy - player_entity.eye_height())
+                .atan2(horizontal_distance)
+                .to_degrees() as f32;
+            player_entity.set_rotation(y_rot, x_rot);
+        }
+        // if the target is right above or below us, walking would take us
+        // past it
+        self.walk(if close {
+            MoveDirection::None
+        } else {
+            MoveDirection::Forward
+        });
+        self.set_jumping(above);
+        false
+    }
+
+    /// Turn our head so our eyes look at the given position.
+    pub fn look_at(&mut self, target: &Vec3) {
+        let mut dimension = self.dimension.lock();
+        let mut player_entity = self.entity_mut(&mut dimension);
+        let (y_rot, x_rot) = direction_looking_at(&player_entity.eye_pos(), target);
+        player_entity.set_rotation(y_rot, x_rot);
+    }
+
+    /// Turn our head so our eyes look at the eyes of the entity with this
+    /// id, which is what you want before attacking it. Returns false if we
+    /// don't know about the entity.
+    pub fn look_at_entity(&mut self, entity_id: u32) -> bool {
+        let target = match self.dimension.lock().entity(entity_id) {
+            Some(entity) => entity.eye_pos(),
+            None => return false,
+        };
+        self.look_at(&target);
+        true
+    }
+
+    /// Find the block we're looking at, if it's within `max_distance` blocks
+    /// of our eyes. Vanilla players can reach blocks up to 4.5 blocks away
+    /// in survival mode, and 5 in creative mode.
+    pub fn raycast(&self, max_distance: f64) -> Option<BlockHitResult> {
+        let dimension = self.dimension.lock();
+        let player_entity = self.entity(&dimension);
+        let from = player_entity.eye_pos();
+        let to = from + player_entity.view_vector().scale(max_distance);
+        azalea_physics::clip::clip(&dimension, &from, &to)
+    }
+
+    /// Whether there are no blocks between our eyes and the eyes of the
+    /// entity with the given id. Returns false if we don't know about the
+    /// entity.
+    pub fn can_see_entity(&self, entity_id: u32) -> bool {
+        let dimension = self.dimension.lock();
+        let to = match dimension.entity(entity_id) {
+            Some(entity) => entity.eye_pos(),
+            None => return false,
+        };
+        let from = self.entity(&dimension).eye_pos();
+        azalea_physics::clip::has_line_of_sight(&dimension, &from, &to)
+    }
+
+    /// Start moving with the given input. This is like [`Client::walk`], but
+    /// lets you move at any angle and speed.
+    pub fn set_movement_input(&mut self, movement_input: MovementInput) {
         let mut physics_state = self.physics_state.lock();
-        physics_state.move_direction = direction;
+        physics_state.movement_input = movement_input;
     }
 
     /// Toggle whether we're jumping. This acts as if you held space in
@@ -234,6 +398,84 @@ impl Client {
 
         player_entity.jumping
     }
+
+    /// Start or stop sneaking. This acts as if you held shift in vanilla, so
+    /// we move slower, our hitbox is shorter, and we won't walk off the edges
+    /// of blocks while we're on the ground.
+    pub async fn set_sneaking(&mut self, sneaking: bool) -> Result<(), std::io::Error> {
+        let entity_id = {
+            let mut dimension = self.dimension.lock();
+            let mut player_entity = self.entity_mut(&mut dimension);
+            if player_entity.sneaking == sneaking {
+                return Ok(());
+            }
+
+            player_entity.sneaking = sneaking;
+            player_entity.dimensions.height = if sneaking {
+                SNEAKING_HEIGHT
+            } else {
+                STANDING_HEIGHT
+            };
+            player_entity.bounding_box = player_entity
+                .dimensions
+                .make_bounding_box(player_entity.pos());
+            player_entity.id
+        };
+
+        self.write_packet(
+            ServerboundPlayerCommandPacket {
+                id: entity_id,
+                action: if sneaking {
+                    serverbound_player_command_packet::Action::PressShiftKey
+                } else {
+                    serverbound_player_command_packet::Action::ReleaseShiftKey
+                },
+                data: 0,
+            }
+            .get(),
+        )
+        .await
+    }
+
+    /// Start or stop flying, like double tapping space in creative mode.
+    /// Returns false without doing anything if we aren't allowed to fly.
+    pub async fn set_flying(&self, flying: bool) -> Result<bool, std::io::Error> {
+        {
+            let mut local_player = self.local_player.lock();
+            if !local_player.abilities.may_fly {
+                return Ok(false);
+            }
+            local_player.abilities.flying = flying;
+        }
+        self.write_packet(ServerboundPlayerAbilitiesPacket { is_flying: flying }.get())
+            .await?;
+        Ok(true)
+    }
+
+    /// Returns whether the player is sneaking.
+    pub fn sneaking(&self) -> bool {
+        let dimension = self.dimension.lock();
+        let player_entity = self.entity(&dimension);
+
+        player_entity.sneaking
+    }
+
+    /// Returns whether any part of the player is in water. Holding jump with
+    /// [`Client::set_jumping`] while in water makes the player swim up.
+    pub fn is_in_water(&self) -> bool {
+        let dimension = self.dimension.lock();
+        let player_entity = self.entity(&dimension);
+
+        player_entity.is_in_water()
+    }
+
+    /// Returns whether any part of the player is in lava.
+    pub fn is_in_lava(&self) -> bool {
+        let dimension = self.dimension.lock();
+        let player_entity = self.entity(&dimension);
+
+        player_entity.is_in_lava()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -249,3 +491,426 @@ pub enum MoveDirection {
     BackwardRight,
     BackwardLeft,
 }
+
+impl MoveDirection {
+    /// Whether this direction includes moving forward (1), backward (-1), or
+    /// neither (0).
+    fn forward(self) -> f32 {
+        match self {
+            MoveDirection::Forward | MoveDirection::ForwardRight | MoveDirection::ForwardLeft => 1.,
+            MoveDirection::Backward
+            | MoveDirection::BackwardRight
+            | MoveDirection::BackwardLeft => -1.,
+            _ => 0.,
+        }
+    }
+
+    /// Whether this direction includes moving left (1), right (-1), or
+    /// neither (0).
+    fn left(self) -> f32 {
+        match self {
+            MoveDirection::Left | MoveDirection::ForwardLeft | MoveDirection::BackwardLeft => 1.,
+            MoveDirection::Right | MoveDirection::ForwardRight | MoveDirection::BackwardRight => {
+                -1.
+            }
+            _ => 0.,
+        }
+    }
+}
+
+/// How much we're trying to move forward and to the left, which is what
+/// holding the movement keys does in vanilla. Both are from -1 to 1, and
+/// negative numbers mean backward and right.
+///
+/// Inputs can be added together to combine them, so for example forward plus
+/// left is the same as [`MoveDirection::ForwardLeft`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementInput {
+    pub forward: f32,
+    pub left: f32,
+}
+
+impl MovementInput {
+    pub fn new(forward: f32, left: f32) -> Self {
+        Self {
+            forward: forward.clamp(-1., 1.),
+            left: left.clamp(-1., 1.),
+        }
+    }
+
+    /// Get which way this input moves us in the world when we're facing the
+    /// given yaw, as a vector that's at most 1 long. Vanilla multiplies this
+    /// by our speed every tick.
+    pub fn to_velocity(&self, y_rot: f32) -> Vec3 {
+        input_vector(
+            y_rot,
+            1.,
+            &Vec3 {
+                x: self.left as f64,
+                y: 0.,
+                z: self.forward as f64,
+            },
+        )
+    }
+}
+
+impl From<MoveDirection> for MovementInput {
+    fn from(direction: MoveDirection) -> Self {
+        Self::new(direction.forward(), direction.left())
+    }
+}
+
+impl Add for MovementInput {
+    type Output = MovementInput;
+
+    fn add(self, other: MovementInput) -> MovementInput {
+        MovementInput::new(self.forward + other.forward, self.left + other.left)
+    }
+}
+
+/// Get the y_rot and x_rot (yaw and pitch) that something at `from` has to
+/// have to look at `to`.
+fn direction_looking_at(from: &Vec3, to: &Vec3) -> (f32, f32) {
+    let dx = to.x - from.x;
+    let dy = to.y - from.y;
+    let dz = to.z - from.z;
+    let horizontal_distance = (dx * dx + dz * dz).sqrt();
+    let y_rot = (-dx).atan2(dz).to_degrees() as f32;
+    let x_rot = -dy.atan2(horizontal_distance).to_degrees() as f32;
+    (y_rot, x_rot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_block::BlockState;
+    use azalea_core::{BlockPos, ChunkPos, Direction};
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+    use azalea_registry::EntityType;
+    use azalea_world::entity::EntityData;
+    use azalea_world::Chunk;
+    use uuid::Uuid;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!(
+            (a.x - b.x).abs() < 1e-6 && (a.y - b.y).abs() < 1e-6 && (a.z - b.z).abs() < 1e-6,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[tokio::test]
+    async fn test_look_at_entity() {
+        let (mut client, _rx, _server) = test_client();
+        client.player.lock().set_entity_id(1);
+        {
+            let mut dimension = client.dimension.lock();
+            dimension.add_entity(1, EntityData::new(Uuid::nil(), Vec3::default()));
+            // another player to the east of us
+            let pos = Vec3 {
+                x: 5.,
+                y: 0.,
+                z: 0.,
+            };
+            dimension.add_entity(2, EntityData::new(Uuid::from_u128(2), pos));
+            // something short to the south of us
+            let pos = Vec3 {
+                x: 0.,
+                y: 0.,
+                z: 2.,
+            };
+            let mut entity = EntityData::new(Uuid::from_u128(3), pos);
+            entity.set_kind(EntityType::Chicken);
+            dimension.add_entity(3, entity);
+        }
+        let rotation = |client: &Client| {
+            let dimension = client.dimension.lock();
+            let entity = client.entity(&dimension);
+            (entity.y_rot, entity.x_rot)
+        };
+
+        assert!(client.look_at_entity(2));
+        // straight ahead, since our eyes are at the same height
+        assert_eq!(rotation(&client), (-90., 0.));
+
+        assert!(client.look_at_entity(3));
+        let (y_rot, x_rot) = rotation(&client);
+        assert_eq!(y_rot, 0.);
+        let expected_x_rot = (1.62 - 0.7 * 0.92f64).atan2(2.).to_degrees() as f32;
+        assert!((x_rot - expected_x_rot).abs() < 1e-4, "{x_rot}");
+
+        assert!(!client.look_at_entity(4));
+    }
+
+    #[tokio::test]
+    async fn test_raycast() {
+        let (mut client, _rx, _server) = test_client();
+        client.player.lock().set_entity_id(1);
+        {
+            let mut dimension = client.dimension.lock();
+            dimension
+                .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+                .unwrap();
+            dimension.set_block_state(&BlockPos::new(0, 64, 3), BlockState::Stone);
+            let pos = Vec3 {
+                x: 0.5,
+                y: 64.,
+                z: 0.5,
+            };
+            dimension.add_entity(1, EntityData::new(Uuid::nil(), pos));
+        }
+
+        client.look_at(&Vec3 {
+            x: 0.5,
+            y: 64.5,
+            z: 3.5,
+        });
+        let hit = client.raycast(4.5).unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(0, 64, 3));
+        assert_eq!(hit.direction, Direction::North);
+        // it's too far away
+        assert!(client.raycast(2.).is_none());
+
+        // looking straight up at nothing
+        client.look_at(&Vec3 {
+            x: 0.5,
+            y: 100.,
+            z: 0.5,
+        });
+        assert!(client.raycast(4.5).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_can_see_entity() {
+        let (client, _rx, _server) = test_client();
+        client.player.lock().set_entity_id(1);
+        {
+            let mut dimension = client.dimension.lock();
+            dimension
+                .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+                .unwrap();
+            // a wall between us and the second player, but not the third
+            for y in 64..=65 {
+                dimension.set_block_state(&BlockPos::new(3, y, 0), BlockState::Stone);
+            }
+            let pos = Vec3 {
+                x: 0.5,
+                y: 64.,
+                z: 0.5,
+            };
+            dimension.add_entity(1, EntityData::new(Uuid::nil(), pos));
+            let pos = Vec3 {
+                x: 6.5,
+                y: 64.,
+                z: 0.5,
+            };
+            dimension.add_entity(2, EntityData::new(Uuid::from_u128(2), pos));
+            let pos = Vec3 {
+                x: 0.5,
+                y: 64.,
+                z: 6.5,
+            };
+            dimension.add_entity(3, EntityData::new(Uuid::from_u128(3), pos));
+        }
+
+        assert!(!client.can_see_entity(2));
+        assert!(client.can_see_entity(3));
+        assert!(!client.can_see_entity(4));
+    }
+
+    #[tokio::test]
+    async fn test_set_flying() {
+        let (mut client, _rx, mut server) = test_client();
+        client.player.lock().set_entity_id(1);
+        {
+            let mut dimension = client.dimension.lock();
+            dimension
+                .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+                .unwrap();
+            dimension.set_block_state(&BlockPos::new(0, 64, 0), BlockState::Stone);
+            let pos = Vec3 {
+                x: 0.5,
+                y: 70.,
+                z: 0.5,
+            };
+            dimension.add_entity(1, EntityData::new(Uuid::nil(), pos));
+        }
+
+        // we can't fly in survival mode
+        assert!(!client.set_flying(true).await.unwrap());
+        assert!(!client.abilities().flying);
+
+        client.local_player.lock().abilities.may_fly = true;
+        assert!(client.set_flying(true).await.unwrap());
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::PlayerAbilities(p) if p.is_flying
+        ));
+        for _ in 0..10 {
+            client.ai_step().await.unwrap();
+        }
+        assert_eq!(client.entity(&client.dimension.lock()).pos().y, 70.);
+
+        // sneaking while flying takes us down until we land
+        client.set_sneaking(true).await.unwrap();
+        server.read().await.unwrap();
+        // and doesn't slow us down like it does on the ground
+        client.walk(MoveDirection::Forward);
+        client.ai_step().await.unwrap();
+        assert_eq!(client.physics_state.lock().forward_impulse, 1.);
+        client.walk(MoveDirection::None);
+        for _ in 0..100 {
+            client.ai_step().await.unwrap();
+        }
+        assert!(!client.abilities().flying);
+        assert_eq!(client.entity(&client.dimension.lock()).pos().y, 65.);
+        // the server is told we stopped flying when we land
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::PlayerAbilities(p) if !p.is_flying
+        ));
+    }
+
+    #[test]
+    fn test_combine_directions() {
+        assert_eq!(
+            MovementInput::from(MoveDirection::Forward) + MoveDirection::Left.into(),
+            MovementInput::from(MoveDirection::ForwardLeft)
+        );
+        assert_eq!(
+            MovementInput::from(MoveDirection::Forward) + MoveDirection::Backward.into(),
+            MovementInput::default()
+        );
+        // inputs can't go past 1
+        assert_eq!(
+            MovementInput::from(MoveDirection::Forward) + MoveDirection::Forward.into(),
+            MovementInput::from(MoveDirection::Forward)
+        );
+    }
+
+    #[test]
+    fn test_to_velocity() {
+        // a yaw of 0 is facing south (+z), so left is east (+x)
+        let forward = MovementInput::from(MoveDirection::Forward);
+        assert_close(
+            forward.to_velocity(0.),
+            Vec3 {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+        );
+        let left = MovementInput::from(MoveDirection::Left);
+        assert_close(
+            left.to_velocity(0.),
+            Vec3 {
+                x: 1.,
+                y: 0.,
+                z: 0.,
+            },
+        );
+        // a yaw of 90 is facing west (-x)
+        assert_close(
+            forward.to_velocity(90.),
+            Vec3 {
+                x: -1.,
+                y: 0.,
+                z: 0.,
+            },
+        );
+
+        // diagonal movement isn't faster
+        let velocity = MovementInput::from(MoveDirection::ForwardRight).to_velocity(0.);
+        assert!((velocity.length_squared() - 1.).abs() < 1e-6);
+        assert!(velocity.x < 0. && velocity.z > 0.);
+    }
+
+    #[tokio::test]
+    async fn test_move_toward() {
+        let (mut client, _rx, _server) = test_client();
+        client.player.lock().set_entity_id(0);
+        client.dimension.lock().add_entity(
+            0,
+            EntityData::new(
+                Uuid::nil(),
+                Vec3 {
+                    x: 0.5,
+                    y: 70.,
+                    z: 0.5,
+                },
+            ),
+        );
+
+        // walk east
+        assert!(!client.move_toward(Vec3 {
+            x: 5.5,
+            y: 70.,
+            z: 0.5,
+        }));
+        assert_eq!(client.entity(&client.dimension.lock()).y_rot, -90.);
+        assert_eq!(
+            client.physics_state.lock().movement_input,
+            MoveDirection::Forward.into()
+        );
+        assert!(!client.jumping());
+
+        // jump in place if it's right above us
+        assert!(!client.move_toward(Vec3 {
+            x: 0.5,
+            y: 71.,
+            z: 0.5,
+        }));
+        assert_eq!(
+            client.physics_state.lock().movement_input,
+            MovementInput::default()
+        );
+        assert!(client.jumping());
+
+        // stop once we're there
+        assert!(client.move_toward(Vec3 {
+            x: 0.6,
+            y: 70.,
+            z: 0.5,
+        }));
+        assert_eq!(
+            client.physics_state.lock().movement_input,
+            MovementInput::default()
+        );
+        assert!(!client.jumping());
+    }
+
+    #[tokio::test]
+    async fn test_big_moves_are_split_up() {
+        let (mut client, _rx, mut server) = test_client();
+        client.player.lock().set_entity_id(1);
+        client
+            .dimension
+            .lock()
+            .add_entity(1, EntityData::new(Uuid::nil(), Vec3::default()));
+        client
+            .set_pos(Vec3 {
+                x: 20.,
+                y: 0.,
+                z: 0.,
+            })
+            .await
+            .unwrap();
+
+        for expected_x in [8., 16., 20.] {
+            client.send_position().await.unwrap();
+            // only one movement packet is sent per tick
+            client.send_position().await.unwrap();
+            assert_eq!(
+                client.entity(&client.dimension.lock()).last_pos.x,
+                expected_x
+            );
+            client.physics_state.lock().sent_position_this_tick = false;
+            match server.read().await.unwrap() {
+                ServerboundGamePacket::MovePlayerPos(p) => assert_eq!(p.x, expected_x),
+                p => panic!("Expected a movement packet, got {:?}", p),
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+//! Walk to a position by finding a path with D* Lite.
+
+mod moves;
+
+pub use moves::{euclidean_heuristic, fall_damage, nearest_standable, FallContext};
+
+use crate::{
+    movement::MOVE_TOWARD_TOLERANCE, subscribe::EventSender, Client, Event, MoveDirection,
+};
+use azalea_block::BlockState;
+use azalea_core::{BlockPos, Direction, Vec3};
+use azalea_pathfinder::DStarLite;
+use azalea_world::Dimension;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+/// How close we have to be to the center of a block (horizontally) before we
+/// start walking to the next one. This is the same as the tolerance for
+/// [`Client::move_toward`], which we use to walk to each block.
+const NODE_REACHED_DISTANCE: f64 = MOVE_TOWARD_TOLERANCE;
+/// How far away from the goal we look for somewhere to stand if we can't
+/// stand at the goal itself.
+const GOAL_SEARCH_RADIUS: u32 = 4;
+
+/// Where we're trying to go with [`Client::goto`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Goal {
+    /// The position our feet should end up at.
+    pub pos: BlockPos,
+    /// How far away from `pos` (in blocks) we're allowed to stop. If this is
+    /// 0, we have to be exactly at `pos`.
+    pub radius: f64,
+}
+
+impl Goal {
+    /// Whether standing with our feet at the given position counts as
+    /// reaching the goal.
+    pub fn is_reached(&self, pos: &BlockPos) -> bool {
+        let dx = (pos.x - self.pos.x) as f64;
+        let dy = (pos.y - self.pos.y) as f64;
+        let dz = (pos.z - self.pos.z) as f64;
+        dx * dx + dy * dy + dz * dz <= self.radius * self.radius
+    }
+}
+
+impl From<BlockPos> for Goal {
+    fn from(pos: BlockPos) -> Self {
+        Goal { pos, radius: 0. }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PathfindError {
+    #[error("There's no path to the goal")]
+    NoPath,
+    #[error("The pathfinder was given a different goal")]
+    Interrupted,
+    #[error("The pathfinder was stopped with Client::stop")]
+    Cancelled,
+}
+
+/// The state of [`Client::goto`].
+#[derive(Default)]
+pub struct Pathfinder {
+    goal: Option<Goal>,
+    /// This is only created once we're on the ground, since that's where the
+    /// path starts.
+    dstar: Option<DStarLite<'static, BlockPos, f32>>,
+    /// The node we're walking away from.
+    previous: Option<BlockPos>,
+    /// The node we're walking towards.
+    next: Option<BlockPos>,
+    finished: Option<oneshot::Sender<Result<(), PathfindError>>>,
+    /// The door we've already tried to open on the way to the next node, so
+    /// we don't close it again by clicking it every tick.
+    opened_door: Option<BlockPos>,
+    /// Decides which falls are safe to take. This isn't reset when we
+    /// finish.
+    fall_context: FallContext,
+}
+
+impl Pathfinder {
+    /// The goal we're currently walking to, if any.
+    pub fn goal(&self) -> Option<&Goal> {
+        self.goal.as_ref()
+    }
+
+    /// Stop pathfinding and resolve the [`Client::goto`] future.
+    pub(crate) fn finish(&mut self, result: Result<(), PathfindError>) {
+        self.goal = None;
+        self.dstar = None;
+        self.previous = None;
+        self.next = None;
+        self.opened_door = None;
+        if let Some(finished) = self.finished.take() {
+            // the future might've been dropped, which is fine
+            let _ = finished.send(result);
+        }
+    }
+}
+
+/// Create a pathfinder that moves around in the given dimension.
+fn new_dstar(
+    dimension: Arc<Mutex<Dimension>>,
+    start: BlockPos,
+    goal: BlockPos,
+    fall_context: FallContext,
+) -> DStarLite<'static, BlockPos, f32> {
+    let successors_dimension = dimension.clone();
+    DStarLite::new(
+        start,
+        goal,
+        Box::new(moves::heuristic),
+        Box::new(move |pos| moves::successors(&successors_dimension.lock(), pos, &fall_context)),
+        Box::new(move |pos| moves::predecessors(&dimension.lock(), pos, &fall_context)),
+    )
+}
+
+impl Client {
+    /// Walk to the given goal, finding a path around obstacles. This
+    /// resolves when we reach the goal or there's no way to get there.
+    ///
+    /// Only one goal can be active at a time, so calling this again makes the
+    /// previous call return [`PathfindError::Interrupted`].
+    ///
+    /// If we can't stand at the goal, like if it's inside a wall or in the
+    /// air, we go to the closest place we can stand instead.
+    pub async fn goto(&self, goal: impl Into<Goal>) -> Result<(), PathfindError> {
+        let mut goal = goal.into();
+        if let Some(pos) = nearest_standable(&self.dimension.lock(), &goal.pos, GOAL_SEARCH_RADIUS)
+        {
+            goal.pos = pos;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pathfinder = self.pathfinder.lock();
+            pathfinder.finish(Err(PathfindError::Interrupted));
+            pathfinder.goal = Some(goal);
+            pathfinder.finished = Some(sender);
+        }
+        receiver.await.unwrap_or(Err(PathfindError::Interrupted))
+    }
+
+    /// Tell the pathfinder how much health we have and how much feather
+    /// falling is on our boots, so it knows which falls are safe. Falls that
+    /// would hurt us cost more, and ones that would kill us are never taken.
+    ///
+    /// This is used the next time a path is found, so it doesn't change the
+    /// path we're currently walking along.
+    pub fn set_fall_context(&self, fall_context: FallContext) {
+        self.pathfinder.lock().fall_context = fall_context;
+    }
+
+    /// Set blocks in our dimension, and tell the pathfinder about any moves
+    /// that became possible or impossible.
+    pub(crate) fn set_block_states(
+        &self,
+        blocks: impl IntoIterator<Item = (BlockPos, BlockState)>,
+    ) {
+        // the pathfinder locks the dimension, so we can't lock the pathfinder
+        // while we have the dimension locked
+        let (pathfinding, fall_context) = {
+            let pathfinder = self.pathfinder.lock();
+            (pathfinder.dstar.is_some(), pathfinder.fall_context)
+        };
+
+        let mut changed_edges = Vec::new();
+        {
+            let mut dimension = self.dimension.lock();
+            for (pos, state) in blocks {
+                if !pathfinding {
+                    dimension.set_block_state(&pos, state);
+                    continue;
+                }
+                let before = moves::edges_around(&dimension, &pos, &fall_context);
+                dimension.set_block_state(&pos, state);
+                let after = moves::edges_around(&dimension, &pos, &fall_context);
+                changed_edges.extend(moves::changed_edges(&before, &after));
+            }
+        }
+
+        if !changed_edges.is_empty() {
+            if let Some(dstar) = self.pathfinder.lock().dstar.as_mut() {
+                dstar.updated_edge_costs.extend(changed_edges);
+            }
+        }
+    }
+
+    /// Walk along the path to the goal, if we have one. This should be run
+    /// every tick before the physics.
+    ///
+    /// If there's a closed door in the way, this returns it and the side we
+    /// should click on to open it.
+    pub(crate) fn tick_pathfinder(&mut self, tx: &EventSender) -> Option<(BlockPos, Direction)> {
+        let (feet, on_ground, pos) = {
+            let dimension = self.dimension.lock();
+            let player_entity = self.entity(&dimension);
+            let pos = *player_entity.pos();
+            (BlockPos::from(&pos), player_entity.on_ground, pos)
+        };
+
+        let mut pathfinder = self.pathfinder.lock();
+        let goal = match &pathfinder.goal {
+            Some(goal) => goal.clone(),
+            None => return None,
+        };
+
+        if on_ground && goal.is_reached(&feet) {
+            pathfinder.finish(Ok(()));
+            drop(pathfinder);
+            self.walk(MoveDirection::None);
+            self.set_jumping(false);
+            tx.send(Event::GoalReached(goal.pos));
+            return None;
+        }
+
+        if on_ground {
+            // if we fell or got pushed off the path, start over from here
+            let on_path = pathfinder.next.is_none()
+                || pathfinder.next == Some(feet)
+                || pathfinder.previous == Some(feet);
+            if pathfinder.dstar.is_none() || !on_path {
+                pathfinder.dstar = Some(new_dstar(
+                    self.dimension.clone(),
+                    feet,
+                    goal.pos,
+                    pathfinder.fall_context,
+                ));
+                pathfinder.previous = Some(feet);
+                pathfinder.next = None;
+            }
+        }
+
+        let centered = |node: &BlockPos| {
+            let dx = node.x as f64 + 0.5 - pos.x;
+            let dz = node.z as f64 + 0.5 - pos.z;
+            dx * dx + dz * dz < NODE_REACHED_DISTANCE * NODE_REACHED_DISTANCE
+        };
+        let reached_next = match pathfinder.next {
+            Some(next) => on_ground && next == feet && centered(&next),
+            None => pathfinder.dstar.is_some(),
+        };
+        if reached_next {
+            if let Some(next) = pathfinder.next {
+                tx.send(Event::PathProgress(next));
+            }
+            let dstar = pathfinder.dstar.as_mut().expect("dstar must exist");
+            dstar.update_from_updated_edges();
+            match dstar.try_next() {
+                Ok(next) => {
+                    let next = next.unwrap_or(dstar.goal);
+                    pathfinder.previous = pathfinder.next.or(Some(feet));
+                    pathfinder.next = Some(next);
+                    pathfinder.opened_door = None;
+                }
+                Err(_) => {
+                    pathfinder.finish(Err(PathfindError::NoPath));
+                    drop(pathfinder);
+                    self.walk(MoveDirection::None);
+                    self.set_jumping(false);
+                    tx.send(Event::PathFailed(goal.pos));
+                    return None;
+                }
+            }
+        }
+
+        let next = pathfinder.next?;
+        drop(pathfinder);
+
+        self.move_toward(Vec3 {
+            x: next.x as f64 + 0.5,
+            y: next.y as f64,
+            z: next.z as f64 + 0.5,
+        });
+
+        let door = moves::closed_door(&self.dimension.lock(), &next)?;
+        let mut pathfinder = self.pathfinder.lock();
+        if pathfinder.opened_door == Some(door) {
+            return None;
+        }
+        pathfinder.opened_door = Some(door);
+        // click on the side of the door that's facing us
+        let direction = match (next.x - feet.x, next.z - feet.z) {
+            (x, _) if x > 0 => Direction::West,
+            (x, _) if x < 0 => Direction::East,
+            (_, z) if z > 0 => Direction::North,
+            (_, z) if z < 0 => Direction::South,
+            _ => Direction::Up,
+        };
+        Some((door, direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_core::ChunkPos;
+    use azalea_world::Chunk;
+
+    #[test]
+    fn test_goal_radius() {
+        let goal = Goal::from(BlockPos::new(0, 0, 0));
+        assert!(goal.is_reached(&BlockPos::new(0, 0, 0)));
+        assert!(!goal.is_reached(&BlockPos::new(1, 0, 0)));
+
+        let goal = Goal {
+            pos: BlockPos::new(0, 0, 0),
+            radius: 2.,
+        };
+        assert!(goal.is_reached(&BlockPos::new(1, 1, 0)));
+        assert!(!goal.is_reached(&BlockPos::new(2, 1, 0)));
+    }
+
+    #[test]
+    fn test_finish_resolves_goto() {
+        let (sender, mut receiver) = oneshot::channel();
+        let mut pathfinder = Pathfinder {
+            goal: Some(BlockPos::new(1, 2, 3).into()),
+            finished: Some(sender),
+            ..Default::default()
+        };
+        pathfinder.finish(Err(PathfindError::Cancelled));
+        assert!(pathfinder.goal().is_none());
+        assert_eq!(receiver.try_recv(), Ok(Err(PathfindError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_reset_state_keeps_fall_context() {
+        let (client, _rx, _server) = crate::client::tests::test_client();
+        let fall_context = FallContext {
+            health: 5.,
+            feather_falling: 4,
+        };
+        client.set_fall_context(fall_context);
+        let (sender, mut receiver) = oneshot::channel();
+        {
+            let mut pathfinder = client.pathfinder.lock();
+            pathfinder.goal = Some(BlockPos::new(1, 2, 3).into());
+            pathfinder.finished = Some(sender);
+        }
+
+        client.reset_state();
+        let pathfinder = client.pathfinder.lock();
+        assert!(pathfinder.goal().is_none());
+        assert_eq!(pathfinder.fall_context, fall_context);
+        assert_eq!(receiver.try_recv(), Ok(Err(PathfindError::Interrupted)));
+    }
+
+    #[test]
+    fn test_path_around_wall() {
+        let mut dimension = Dimension::default();
+        dimension
+            .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+            .unwrap();
+        for x in 0..16 {
+            for z in 0..16 {
+                dimension.set_block_state(&BlockPos::new(x, 0, z), BlockState::Stone);
+            }
+        }
+        // a wall that's too high to jump over, with a gap at z=8
+        for z in 0..8 {
+            dimension.set_block_state(&BlockPos::new(5, 1, z), BlockState::Stone);
+            dimension.set_block_state(&BlockPos::new(5, 2, z), BlockState::Stone);
+        }
+        let dimension = Arc::new(Mutex::new(dimension));
+
+        let mut dstar = new_dstar(
+            dimension.clone(),
+            BlockPos::new(3, 1, 3),
+            BlockPos::new(7, 1, 3),
+            FallContext::default(),
+        );
+        let mut path = Vec::new();
+        while let Some(next) = dstar.try_next().unwrap() {
+            path.push(next);
+        }
+        assert_eq!(path.last(), Some(&BlockPos::new(7, 1, 3)));
+        assert!(path.contains(&BlockPos::new(5, 1, 8)));
+        // 2 to get to the wall, 5 to go around it, 2 to get to the goal, and
+        // 5 to go back
+        assert_eq!(path.len(), 14);
+
+        // block the gap after we've found a path, so we have to go further
+        // around
+        let mut dstar = new_dstar(
+            dimension.clone(),
+            BlockPos::new(3, 1, 3),
+            BlockPos::new(7, 1, 3),
+            FallContext::default(),
+        );
+        dstar.compute_shortest_path().unwrap();
+        let changed_edges = {
+            let mut dimension = dimension.lock();
+            let pos = BlockPos::new(5, 2, 8);
+            let before = moves::edges_around(&dimension, &pos, &FallContext::default());
+            dimension.set_block_state(&pos, BlockState::Stone);
+            let after = moves::edges_around(&dimension, &pos, &FallContext::default());
+            moves::changed_edges(&before, &after)
+        };
+        dstar.updated_edge_costs.extend(changed_edges);
+        dstar.update_from_updated_edges();
+        let mut path = Vec::new();
+        while let Some(next) = dstar.try_next().unwrap() {
+            path.push(next);
+        }
+        assert!(!path.contains(&BlockPos::new(5, 1, 8)));
+        assert!(path.contains(&BlockPos::new(5, 1, 9)));
+        assert_eq!(path.len(), 16);
+    }
+}
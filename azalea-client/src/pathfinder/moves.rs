@@ -0,0 +1,848 @@
+//! The ways we can move between blocks while pathfinding.
+
+use azalea_block::{
+    AcaciaDoorBlock, AcaciaFenceGateBlock, AcaciaTrapdoorBlock, BirchDoorBlock,
+    BirchFenceGateBlock, BirchTrapdoorBlock, Block, BlockState, CrimsonDoorBlock,
+    CrimsonFenceGateBlock, CrimsonTrapdoorBlock, DarkOakDoorBlock, DarkOakFenceGateBlock,
+    DarkOakTrapdoorBlock, IronDoorBlock, IronTrapdoorBlock, JungleDoorBlock, JungleFenceGateBlock,
+    JungleTrapdoorBlock, MangroveDoorBlock, MangroveFenceGateBlock, MangroveTrapdoorBlock,
+    OakDoorBlock, OakFenceGateBlock, OakTrapdoorBlock, SpruceDoorBlock, SpruceFenceGateBlock,
+    SpruceTrapdoorBlock, WarpedDoorBlock, WarpedFenceGateBlock, WarpedTrapdoorBlock,
+};
+use azalea_core::{BlockPos, Vec3};
+use azalea_pathfinder::{ChangedEdge, Edge};
+use azalea_physics::{
+    collision::BlockWithShape,
+    fluids::{fluid_at, FluidKind},
+};
+use azalea_world::Dimension;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+const HORIZONTAL_DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The furthest we'll ever fall in one move, even if we'd survive falling
+/// further.
+pub const MAX_FALL_DISTANCE: u32 = 12;
+/// Opening a door or fence gate costs the same as walking this many blocks.
+const OPEN_DOOR_COST: f32 = 2.;
+/// Taking as much damage as we have health costs the same as walking this
+/// many blocks. Falls that would kill us aren't allowed at all.
+const FULL_HEALTH_DAMAGE_COST: f32 = 40.;
+
+/// How much damage a player takes from falling the given number of blocks,
+/// without any armor or effects.
+pub fn fall_damage(distance: f32) -> f32 {
+    (distance - 3.).ceil().max(0.)
+}
+
+/// What we need to know about the player to decide which falls are safe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FallContext {
+    /// Our current health, which is 20 when it's full.
+    pub health: f32,
+    /// The level of feather falling on our boots, or 0 if they don't have it.
+    pub feather_falling: u8,
+}
+
+impl Default for FallContext {
+    fn default() -> Self {
+        Self {
+            health: 20.,
+            feather_falling: 0,
+        }
+    }
+}
+
+impl FallContext {
+    /// How much damage we'd take from falling the given number of blocks,
+    /// after feather falling reduces it.
+    pub fn damage(&self, distance: u32) -> f32 {
+        // feather falling gives 3 protection per level, and every point of
+        // protection reduces the damage by 4% up to a maximum of 80%
+        let protection = (self.feather_falling as f32 * 3.).min(20.);
+        fall_damage(distance as f32) * (1. - protection / 25.)
+    }
+
+    /// The furthest we can fall without dying, up to [`MAX_FALL_DISTANCE`].
+    pub fn max_fall_distance(&self) -> u32 {
+        (1..=MAX_FALL_DISTANCE)
+            .take_while(|&distance| self.damage(distance) < self.health)
+            .last()
+            .unwrap_or(0)
+    }
+
+    /// How much the damage from a fall adds to the cost of the move.
+    fn damage_cost(&self, distance: u32) -> f32 {
+        self.damage(distance) / self.health * FULL_HEALTH_DAMAGE_COST
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveKind {
+    /// Walk to the block next to us.
+    Forward,
+    /// Jump up onto the block next to us.
+    Ascend,
+    /// Walk off the edge and fall down one block.
+    Descend,
+    /// Walk off the edge and fall down more than one block. This can't be
+    /// done in reverse.
+    Fall { distance: u32 },
+}
+
+impl MoveKind {
+    /// The move for walking off an edge and falling the given number of
+    /// blocks.
+    fn falling(distance: u32) -> MoveKind {
+        if distance == 1 {
+            MoveKind::Descend
+        } else {
+            MoveKind::Fall { distance }
+        }
+    }
+
+    /// How long the move takes, not including any damage it does.
+    pub fn cost(&self) -> f32 {
+        match self {
+            MoveKind::Forward => 1.,
+            MoveKind::Ascend => 2.,
+            MoveKind::Descend => 1.5,
+            MoveKind::Fall { distance } => 0.5 + *distance as f32,
+        }
+    }
+
+    /// The cost of the move, including how much the fall damage it does
+    /// would hurt us.
+    pub fn cost_with_damage(&self, fall_context: &FallContext) -> f32 {
+        match self {
+            MoveKind::Fall { distance } => self.cost() + fall_context.damage_cost(*distance),
+            _ => self.cost(),
+        }
+    }
+
+    /// The move that goes back to where we started, or `None` if we can't
+    /// get back up.
+    pub fn reverse(&self) -> Option<MoveKind> {
+        match self {
+            MoveKind::Forward => Some(MoveKind::Forward),
+            MoveKind::Ascend => Some(MoveKind::Descend),
+            MoveKind::Descend => Some(MoveKind::Ascend),
+            MoveKind::Fall { .. } => None,
+        }
+    }
+}
+
+/// A door, trapdoor, or fence gate, which we can walk through once it's open.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Openable {
+    pub open: bool,
+    /// Whether we can open it by clicking on it, which we can't do for iron
+    /// doors and trapdoors.
+    pub by_hand: bool,
+}
+
+lazy_static! {
+    /// The doors, trapdoors, and fence gates for every block state, so the
+    /// pathfinder doesn't have to build the block to check.
+    static ref OPENABLES: Vec<Option<Openable>> = (0..=BlockState::max_state())
+        .map(|id| openable_from_block(BlockState::try_from(id).unwrap()))
+        .collect();
+}
+
+/// Get the door, trapdoor, or fence gate at this block state, if it's one of
+/// those.
+pub fn openable_at(state: BlockState) -> Option<Openable> {
+    OPENABLES[state as usize]
+}
+
+fn openable_from_block(state: BlockState) -> Option<Openable> {
+    let block = Box::<dyn Block>::from(state);
+    let block = block.as_any();
+    macro_rules! openable {
+        ($by_hand:expr, $($block_struct:ident),*) => {
+            $(
+                if let Some(block) = block.downcast_ref::<$block_struct>() {
+                    return Some(Openable {
+                        open: block.open,
+                        by_hand: $by_hand,
+                    });
+                }
+            )*
+        };
+    }
+    openable!(
+        true,
+        OakDoorBlock,
+        SpruceDoorBlock,
+        BirchDoorBlock,
+        JungleDoorBlock,
+        AcaciaDoorBlock,
+        DarkOakDoorBlock,
+        MangroveDoorBlock,
+        CrimsonDoorBlock,
+        WarpedDoorBlock,
+        OakTrapdoorBlock,
+        SpruceTrapdoorBlock,
+        BirchTrapdoorBlock,
+        JungleTrapdoorBlock,
+        AcaciaTrapdoorBlock,
+        DarkOakTrapdoorBlock,
+        MangroveTrapdoorBlock,
+        CrimsonTrapdoorBlock,
+        WarpedTrapdoorBlock,
+        OakFenceGateBlock,
+        SpruceFenceGateBlock,
+        BirchFenceGateBlock,
+        JungleFenceGateBlock,
+        AcaciaFenceGateBlock,
+        DarkOakFenceGateBlock,
+        MangroveFenceGateBlock,
+        CrimsonFenceGateBlock,
+        WarpedFenceGateBlock
+    );
+    openable!(false, IronDoorBlock, IronTrapdoorBlock);
+    None
+}
+
+/// Whether a player can be inside the block at this position. Unloaded blocks
+/// and lava aren't passable. Doors and fence gates are passable if they're
+/// open or we can open them.
+pub fn is_passable(dimension: &Dimension, pos: &BlockPos) -> bool {
+    passable(dimension, pos, true)
+}
+
+/// Like [`is_passable`], but closed doors and fence gates don't count, since
+/// we can't open them while we're falling past.
+fn is_open(dimension: &Dimension, pos: &BlockPos) -> bool {
+    passable(dimension, pos, false)
+}
+
+fn passable(dimension: &Dimension, pos: &BlockPos, can_open: bool) -> bool {
+    match dimension.get_block_state(pos) {
+        Some(state) => {
+            if state.shape().is_empty() {
+                return !matches!(fluid_at(state), Some((FluidKind::Lava, _)));
+            }
+            match openable_at(state) {
+                Some(openable) => openable.open || (can_open && openable.by_hand),
+                None => false,
+            }
+        }
+        None => false,
+    }
+}
+
+/// Get the closed door or fence gate we'd have to open to stand with our feet
+/// at this position, if there is one.
+pub fn closed_door(dimension: &Dimension, pos: &BlockPos) -> Option<BlockPos> {
+    [*pos, BlockPos::new(pos.x, pos.y + 1, pos.z)]
+        .into_iter()
+        .find(|pos| {
+            matches!(
+                dimension.get_block_state(pos).and_then(openable_at),
+                Some(Openable {
+                    open: false,
+                    by_hand: true
+                })
+            )
+        })
+}
+
+/// The extra cost of moving to this position because of the doors we'd have
+/// to open.
+fn door_cost(dimension: &Dimension, pos: &BlockPos) -> f32 {
+    if closed_door(dimension, pos).is_some() {
+        OPEN_DOOR_COST
+    } else {
+        0.
+    }
+}
+
+/// Whether the top of the block at this position is full, so a player can
+/// stand on it.
+pub fn is_solid(dimension: &Dimension, pos: &BlockPos) -> bool {
+    let state = match dimension.get_block_state(pos) {
+        Some(state) => state,
+        None => return false,
+    };
+    let mut has_full_top = false;
+    state
+        .shape()
+        .for_all_boxes(|min_x, _, min_z, max_x, max_y, max_z| {
+            if min_x <= 0. && min_z <= 0. && max_x >= 1. && max_z >= 1. && max_y >= 1. {
+                has_full_top = true;
+            }
+        });
+    has_full_top
+}
+
+/// Whether a player can stand with their feet at this position.
+pub fn is_standable(dimension: &Dimension, pos: &BlockPos) -> bool {
+    is_solid(dimension, &pos.below())
+        && is_passable(dimension, pos)
+        && is_passable(dimension, &BlockPos::new(pos.x, pos.y + 1, pos.z))
+}
+
+/// Find the closest position to `pos` that a player can stand at, looking at
+/// most `max_radius` blocks away on each axis. This is `pos` itself if we can
+/// already stand there.
+pub fn nearest_standable(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    max_radius: u32,
+) -> Option<BlockPos> {
+    let max_radius = max_radius as i32;
+    let mut best: Option<(i32, BlockPos)> = None;
+    for radius in 0..=max_radius {
+        // everything further out is at least this far away
+        if let Some((best_distance, best_pos)) = best {
+            if best_distance <= radius * radius {
+                return Some(best_pos);
+            }
+        }
+        // only check the outside of the cube, since the inside was checked
+        // already
+        for x in -radius..=radius {
+            for y in -radius..=radius {
+                for z in -radius..=radius {
+                    if x.abs() != radius && y.abs() != radius && z.abs() != radius {
+                        continue;
+                    }
+                    let distance = x * x + y * y + z * z;
+                    if matches!(best, Some((best_distance, _)) if best_distance <= distance) {
+                        continue;
+                    }
+                    let candidate = BlockPos::new(pos.x + x, pos.y + y, pos.z + z);
+                    if is_standable(dimension, &candidate) {
+                        best = Some((distance, candidate));
+                    }
+                }
+            }
+        }
+    }
+    best.map(|(_, pos)| pos)
+}
+
+/// Get the positions we can move to from the given position. Falls that
+/// would kill us aren't included.
+pub fn moves(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    fall_context: &FallContext,
+) -> Vec<(BlockPos, MoveKind)> {
+    let mut moves = Vec::new();
+    if !is_standable(dimension, pos) {
+        return moves;
+    }
+    let head_room = is_passable(dimension, &BlockPos::new(pos.x, pos.y + 2, pos.z));
+    let max_fall_distance = fall_context.max_fall_distance() as i32;
+    for (x, z) in HORIZONTAL_DIRECTIONS {
+        let forward = BlockPos::new(pos.x + x, pos.y, pos.z + z);
+        let above = BlockPos::new(forward.x, forward.y + 1, forward.z);
+        if is_standable(dimension, &forward) {
+            moves.push((forward, MoveKind::Forward));
+        } else if head_room && is_standable(dimension, &above) {
+            moves.push((above, MoveKind::Ascend));
+        } else if is_passable(dimension, &forward) && is_passable(dimension, &above) {
+            // fall until we land on something
+            for distance in 1..=max_fall_distance {
+                let landing = BlockPos::new(forward.x, forward.y - distance, forward.z);
+                if !is_open(dimension, &landing) {
+                    break;
+                }
+                if is_solid(dimension, &landing.below()) {
+                    moves.push((landing, MoveKind::falling(distance as u32)));
+                    break;
+                }
+            }
+        }
+    }
+    moves
+}
+
+/// Get the positions we could've fallen more than one block from to land at
+/// the given position, since those moves can't be found by reversing the
+/// moves from it.
+fn falls_onto(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    fall_context: &FallContext,
+) -> Vec<(BlockPos, MoveKind)> {
+    let mut falls = Vec::new();
+    // we land here, so we can't open a door first
+    if !is_standable(dimension, pos)
+        || !is_open(dimension, pos)
+        || !is_open(dimension, &BlockPos::new(pos.x, pos.y + 1, pos.z))
+    {
+        return falls;
+    }
+    if !is_passable(dimension, &BlockPos::new(pos.x, pos.y + 2, pos.z)) {
+        return falls;
+    }
+    let max_fall_distance = fall_context.max_fall_distance() as i32;
+    for distance in 2..=max_fall_distance {
+        // we have to be able to fit in the column above where we land. The
+        // top two blocks are the ones we step into before falling, so they
+        // can be doors we open.
+        if !is_passable(
+            dimension,
+            &BlockPos::new(pos.x, pos.y + distance + 1, pos.z),
+        ) {
+            break;
+        }
+        for (x, z) in HORIZONTAL_DIRECTIONS {
+            let from = BlockPos::new(pos.x - x, pos.y + distance, pos.z - z);
+            if is_standable(dimension, &from) {
+                falls.push((from, MoveKind::falling(distance as u32)));
+            }
+        }
+        // falling from any higher means falling past this block
+        if !is_open(dimension, &BlockPos::new(pos.x, pos.y + distance, pos.z)) {
+            break;
+        }
+    }
+    falls
+}
+
+/// Get the edges that go out of a node, for the pathfinder.
+pub fn successors(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    fall_context: &FallContext,
+) -> Vec<Edge<BlockPos, f32>> {
+    moves(dimension, pos, fall_context)
+        .into_iter()
+        .map(|(target, kind)| Edge {
+            target,
+            cost: kind.cost_with_damage(fall_context) + door_cost(dimension, &target),
+        })
+        .collect()
+}
+
+/// Get the edges that go into a node, for the pathfinder.
+pub fn predecessors(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    fall_context: &FallContext,
+) -> Vec<Edge<BlockPos, f32>> {
+    moves(dimension, pos, fall_context)
+        .into_iter()
+        .filter_map(|(target, kind)| Some((target, kind.reverse()?)))
+        .chain(falls_onto(dimension, pos, fall_context))
+        .map(|(target, kind)| Edge {
+            target,
+            cost: kind.cost_with_damage(fall_context) + door_cost(dimension, pos),
+        })
+        .collect()
+}
+
+/// An estimate of the cost between two positions that's never too high.
+pub fn heuristic(a: &BlockPos, b: &BlockPos) -> f32 {
+    // every move goes one block horizontally, and going up or down costs at
+    // least one per block
+    let horizontal = a.x.abs_diff(b.x) + a.z.abs_diff(b.z);
+    horizontal.max(a.y.abs_diff(b.y)) as f32
+}
+
+/// The straight-line distance between two positions. This is never too high
+/// either, since every move costs at least as much as the distance it
+/// covers, but it's usually lower than [`heuristic`].
+pub fn euclidean_heuristic(a: &BlockPos, b: &BlockPos) -> f32 {
+    Vec3::from(*a).distance_to(&Vec3::from(*b)) as f32
+}
+
+/// Get the outgoing edges of every node that could be affected by the block
+/// at the given position changing.
+pub fn edges_around(
+    dimension: &Dimension,
+    pos: &BlockPos,
+    fall_context: &FallContext,
+) -> HashMap<BlockPos, Vec<Edge<BlockPos, f32>>> {
+    let mut edges = HashMap::new();
+    // moves look at most one block horizontally, two blocks up, and as far
+    // down as we can fall
+    let max_fall_distance = fall_context.max_fall_distance() as i32;
+    for (x, z) in HORIZONTAL_DIRECTIONS.into_iter().chain([(0, 0)]) {
+        for y in -2..=(max_fall_distance + 1).max(2) {
+            let node = BlockPos::new(pos.x + x, pos.y + y, pos.z + z);
+            edges.insert(node, successors(dimension, &node, fall_context));
+        }
+    }
+    edges
+}
+
+/// Compare the edges from [`edges_around`] from before and after a block
+/// changed.
+pub fn changed_edges(
+    before: &HashMap<BlockPos, Vec<Edge<BlockPos, f32>>>,
+    after: &HashMap<BlockPos, Vec<Edge<BlockPos, f32>>>,
+) -> Vec<ChangedEdge<BlockPos, f32>> {
+    fn cost_to(edges: Option<&Vec<Edge<BlockPos, f32>>>, target: &BlockPos) -> f32 {
+        edges
+            .and_then(|edges| edges.iter().find(|edge| &edge.target == target))
+            .map_or(f32::INFINITY, |edge| edge.cost)
+    }
+
+    let mut changed = Vec::new();
+    for (node, edges) in before.iter().chain(after.iter()) {
+        for edge in edges {
+            let c_old = cost_to(before.get(node), &edge.target);
+            let c_new = cost_to(after.get(node), &edge.target);
+            let change = ChangedEdge {
+                u: *node,
+                v: edge.target,
+                c_old,
+                c_new,
+            };
+            if c_old != c_new && !changed.contains(&change) {
+                changed.push(change);
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_core::ChunkPos;
+    use azalea_world::Chunk;
+
+    /// A dimension with a stone floor at y=0.
+    pub fn flat_dimension() -> Dimension {
+        let mut dimension = Dimension::default();
+        dimension
+            .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+            .unwrap();
+        for x in 0..16 {
+            for z in 0..16 {
+                dimension.set_block_state(&BlockPos::new(x, 0, z), BlockState::Stone);
+            }
+        }
+        dimension
+    }
+
+    #[test]
+    fn test_walk_on_flat_ground() {
+        let dimension = flat_dimension();
+        let moves = moves(&dimension, &BlockPos::new(5, 1, 5), &FallContext::default());
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|(_, kind)| *kind == MoveKind::Forward));
+    }
+
+    #[test]
+    fn test_cant_stand_in_air() {
+        let dimension = flat_dimension();
+        assert!(moves(&dimension, &BlockPos::new(5, 3, 5), &FallContext::default()).is_empty());
+    }
+
+    #[test]
+    fn test_ascend_and_descend() {
+        let mut dimension = flat_dimension();
+        dimension.set_block_state(&BlockPos::new(6, 1, 5), BlockState::Stone);
+
+        let up = moves(&dimension, &BlockPos::new(5, 1, 5), &FallContext::default());
+        assert!(up.contains(&(BlockPos::new(6, 2, 5), MoveKind::Ascend)));
+
+        let down = moves(&dimension, &BlockPos::new(6, 2, 5), &FallContext::default());
+        assert!(down.contains(&(BlockPos::new(5, 1, 5), MoveKind::Descend)));
+        assert!(down.contains(&(BlockPos::new(7, 1, 5), MoveKind::Descend)));
+
+        // a ceiling stops us from jumping
+        dimension.set_block_state(&BlockPos::new(5, 3, 5), BlockState::Stone);
+        let up = moves(&dimension, &BlockPos::new(5, 1, 5), &FallContext::default());
+        assert!(!up.iter().any(|(_, kind)| *kind == MoveKind::Ascend));
+    }
+
+    #[test]
+    fn test_walls_block_moves() {
+        let mut dimension = flat_dimension();
+        // a wall that's two blocks high can't be jumped over
+        dimension.set_block_state(&BlockPos::new(6, 1, 5), BlockState::Stone);
+        dimension.set_block_state(&BlockPos::new(6, 2, 5), BlockState::Stone);
+        let moves = moves(&dimension, &BlockPos::new(5, 1, 5), &FallContext::default());
+        assert_eq!(moves.len(), 3);
+    }
+
+    #[test]
+    fn test_changed_edges() {
+        let mut dimension = flat_dimension();
+        let pos = BlockPos::new(6, 1, 5);
+        let before = edges_around(&dimension, &pos, &FallContext::default());
+        dimension.set_block_state(&pos, BlockState::Stone);
+        let after = edges_around(&dimension, &pos, &FallContext::default());
+
+        let changed = changed_edges(&before, &after);
+        // we can't walk into the block anymore
+        assert!(changed.contains(&ChangedEdge {
+            u: BlockPos::new(5, 1, 5),
+            v: pos,
+            c_old: MoveKind::Forward.cost(),
+            c_new: f32::INFINITY,
+        }));
+        // but we can jump on top of it
+        assert!(changed.contains(&ChangedEdge {
+            u: BlockPos::new(5, 1, 5),
+            v: BlockPos::new(6, 2, 5),
+            c_old: f32::INFINITY,
+            c_new: MoveKind::Ascend.cost(),
+        }));
+        assert!(changed_edges(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_fall_damage() {
+        assert_eq!(fall_damage(3.), 0.);
+        assert_eq!(fall_damage(4.), 1.);
+        assert_eq!(fall_damage(10.), 7.);
+
+        let fall_context = FallContext::default();
+        assert_eq!(fall_context.max_fall_distance(), MAX_FALL_DISTANCE);
+        let fall_context = FallContext {
+            health: 5.,
+            feather_falling: 0,
+        };
+        assert_eq!(fall_context.max_fall_distance(), 7);
+        // feather falling iv takes away 48% of the damage
+        let fall_context = FallContext {
+            health: 5.,
+            feather_falling: 4,
+        };
+        assert!((fall_context.damage(10) - 7. * 0.52).abs() < 0.001);
+        assert_eq!(fall_context.max_fall_distance(), 12);
+    }
+
+    #[test]
+    fn test_fall_off_pillar() {
+        let mut dimension = flat_dimension();
+        for y in 1..6 {
+            dimension.set_block_state(&BlockPos::new(5, y, 5), BlockState::Stone);
+        }
+        let top = BlockPos::new(5, 6, 5);
+        let bottom = BlockPos::new(6, 1, 5);
+
+        let fall_context = FallContext::default();
+        let edges = successors(&dimension, &top, &fall_context);
+        let fall = edges.iter().find(|edge| edge.target == bottom).unwrap();
+        // falling 5 blocks does 2 damage, so it costs more than the time it
+        // takes
+        assert!(fall.cost > MoveKind::Fall { distance: 5 }.cost());
+        // we can't get back up, but the pathfinder still has to know we can
+        // get here from the top
+        assert!(!moves(&dimension, &bottom, &fall_context)
+            .iter()
+            .any(|(target, _)| target == &top));
+        assert!(predecessors(&dimension, &bottom, &fall_context)
+            .iter()
+            .any(|edge| edge.target == top && edge.cost == fall.cost));
+
+        // the fall would kill us if we only have 2 health
+        let fall_context = FallContext {
+            health: 2.,
+            feather_falling: 0,
+        };
+        assert!(successors(&dimension, &top, &fall_context).is_empty());
+        assert!(!predecessors(&dimension, &bottom, &fall_context)
+            .iter()
+            .any(|edge| edge.target == top));
+    }
+
+    #[test]
+    fn test_walk_through_doors() {
+        let mut dimension = flat_dimension();
+        // a wall that's too high to jump over with a door in it
+        for z in 0..16 {
+            dimension.set_block_state(&BlockPos::new(6, 1, z), BlockState::Stone);
+            dimension.set_block_state(&BlockPos::new(6, 2, z), BlockState::Stone);
+        }
+        let door = BlockPos::new(6, 1, 5);
+        dimension.set_block_state(&door, BlockState::from(OakDoorBlock::default()));
+        dimension.set_block_state(
+            &BlockPos::new(6, 2, 5),
+            BlockState::from(OakDoorBlock {
+                half: azalea_block::Half::Upper,
+                ..Default::default()
+            }),
+        );
+
+        let fall_context = FallContext::default();
+        let edges = successors(&dimension, &BlockPos::new(5, 1, 5), &fall_context);
+        let through_door = edges.iter().find(|edge| edge.target == door).unwrap();
+        assert_eq!(through_door.cost, MoveKind::Forward.cost() + OPEN_DOOR_COST);
+        assert_eq!(closed_door(&dimension, &door), Some(door));
+
+        // it doesn't cost anything extra once it's open
+        for (pos, half) in [
+            (door, azalea_block::Half::Lower),
+            (BlockPos::new(6, 2, 5), azalea_block::Half::Upper),
+        ] {
+            dimension.set_block_state(
+                &pos,
+                BlockState::from(OakDoorBlock {
+                    half,
+                    open: true,
+                    ..Default::default()
+                }),
+            );
+        }
+        let edges = successors(&dimension, &BlockPos::new(5, 1, 5), &fall_context);
+        let through_door = edges.iter().find(|edge| edge.target == door).unwrap();
+        assert_eq!(through_door.cost, MoveKind::Forward.cost());
+    }
+
+    #[test]
+    fn test_heuristics_are_admissible() {
+        use std::collections::BinaryHeap;
+
+        // stairs up to a pillar that's high enough to take damage from
+        let mut dimension = flat_dimension();
+        for x in 1..8 {
+            for y in 1..=x {
+                dimension.set_block_state(&BlockPos::new(x, y, 5), BlockState::Stone);
+            }
+        }
+        let fall_context = FallContext::default();
+
+        // find the real cost of getting everywhere from the start
+        #[derive(PartialEq)]
+        struct Node(f32, BlockPos);
+        impl Eq for Node {}
+        impl PartialOrd for Node {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Node {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                other.0.total_cmp(&self.0)
+            }
+        }
+        let start = BlockPos::new(0, 1, 5);
+        let mut costs = HashMap::from([(start, 0.)]);
+        let mut queue = BinaryHeap::from([Node(0., start)]);
+        while let Some(Node(cost, pos)) = queue.pop() {
+            if cost > costs[&pos] {
+                continue;
+            }
+            for edge in successors(&dimension, &pos, &fall_context) {
+                let new_cost = cost + edge.cost;
+                if costs.get(&edge.target).map_or(true, |&c| new_cost < c) {
+                    costs.insert(edge.target, new_cost);
+                    queue.push(Node(new_cost, edge.target));
+                }
+            }
+        }
+
+        // make sure we went up the stairs and fell off
+        assert!(costs.contains_key(&BlockPos::new(7, 8, 5)));
+        assert!(costs.contains_key(&BlockPos::new(8, 1, 5)));
+        for (pos, cost) in &costs {
+            assert!(euclidean_heuristic(&start, pos) <= *cost, "{:?}", pos);
+            assert!(heuristic(&start, pos) <= *cost, "{:?}", pos);
+        }
+    }
+
+    #[test]
+    fn test_cant_open_doors_while_falling() {
+        let mut dimension = flat_dimension();
+        for y in 1..6 {
+            dimension.set_block_state(&BlockPos::new(5, y, 5), BlockState::Stone);
+        }
+        let top = BlockPos::new(5, 6, 5);
+        let bottom = BlockPos::new(6, 1, 5);
+        let door = BlockPos::new(6, 3, 5);
+        dimension.set_block_state(&door, BlockState::from(OakDoorBlock::default()));
+
+        let fall_context = FallContext::default();
+        assert!(!successors(&dimension, &top, &fall_context)
+            .iter()
+            .any(|edge| edge.target == bottom));
+        assert!(!predecessors(&dimension, &bottom, &fall_context)
+            .iter()
+            .any(|edge| edge.target == top));
+
+        // an open door is fine to fall through
+        dimension.set_block_state(
+            &door,
+            BlockState::from(OakDoorBlock {
+                open: true,
+                ..Default::default()
+            }),
+        );
+        assert!(successors(&dimension, &top, &fall_context)
+            .iter()
+            .any(|edge| edge.target == bottom));
+        assert!(predecessors(&dimension, &bottom, &fall_context)
+            .iter()
+            .any(|edge| edge.target == top));
+    }
+
+    #[test]
+    fn test_openable_at() {
+        assert_eq!(openable_at(BlockState::Stone), None);
+        assert_eq!(
+            openable_at(BlockState::from(OakDoorBlock {
+                open: true,
+                ..Default::default()
+            })),
+            Some(Openable {
+                open: true,
+                by_hand: true
+            })
+        );
+        assert_eq!(
+            openable_at(BlockState::from(IronTrapdoorBlock::default())),
+            Some(Openable {
+                open: false,
+                by_hand: false
+            })
+        );
+    }
+
+    #[test]
+    fn test_iron_doors_are_walls() {
+        let mut dimension = flat_dimension();
+        let door = BlockPos::new(6, 1, 5);
+        dimension.set_block_state(&door, BlockState::from(IronDoorBlock::default()));
+        assert!(!is_passable(&dimension, &door));
+        assert_eq!(closed_door(&dimension, &door), None);
+
+        dimension.set_block_state(
+            &door,
+            BlockState::from(IronDoorBlock {
+                open: true,
+                ..Default::default()
+            }),
+        );
+        assert!(is_passable(&dimension, &door));
+    }
+
+    #[test]
+    fn test_nearest_standable() {
+        let mut dimension = flat_dimension();
+        let pos = BlockPos::new(5, 1, 5);
+        assert_eq!(nearest_standable(&dimension, &pos, 3), Some(pos));
+        // floating in the air
+        assert_eq!(
+            nearest_standable(&dimension, &BlockPos::new(5, 3, 5), 3),
+            Some(pos)
+        );
+        // inside the floor
+        assert_eq!(
+            nearest_standable(&dimension, &BlockPos::new(5, 0, 5), 3),
+            Some(pos)
+        );
+        // inside a pillar, so the closest place is next to it
+        dimension.set_block_state(&pos, BlockState::Stone);
+        dimension.set_block_state(&BlockPos::new(5, 2, 5), BlockState::Stone);
+        let nearest = nearest_standable(&dimension, &pos, 3).unwrap();
+        assert_eq!(nearest.y, 1);
+        assert_eq!(nearest.x.abs_diff(5) + nearest.z.abs_diff(5), 1);
+        // nothing nearby
+        assert_eq!(
+            nearest_standable(&dimension, &BlockPos::new(5, 10, 5), 3),
+            None
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Keeping track of requests we sent to the server that we're waiting for an
+//! answer to, like tab completions.
+
+use parking_lot::Mutex;
+use std::{collections::HashMap, hash::Hash, time::Duration};
+use tokio::sync::oneshot;
+
+/// The requests we're waiting for answers to, by their id. Action methods
+/// register an id before sending their packet, and the packet handler
+/// resolves it when the answer arrives.
+pub(crate) struct Pending<Id, Resp> {
+    senders: HashMap<Id, oneshot::Sender<Resp>>,
+    /// The id [`Pending::register_next`] gives out next, for requests where
+    /// we pick the id.
+    next_id: u32,
+}
+
+impl<Id, Resp> Default for Pending<Id, Resp> {
+    fn default() -> Self {
+        Self {
+            senders: HashMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+impl<Id: Eq + Hash, Resp> Pending<Id, Resp> {
+    /// Start waiting for the answer to the request with this id. If we were
+    /// already waiting for one with the same id, that request is forgotten.
+    pub fn register(&mut self, id: Id) -> oneshot::Receiver<Resp> {
+        let (sender, receiver) = oneshot::channel();
+        self.senders.insert(id, sender);
+        receiver
+    }
+
+    /// Send the answer to whoever is waiting for it. Returns false if we
+    /// weren't waiting for an answer with this id.
+    pub fn resolve(&mut self, id: &Id, response: Resp) -> bool {
+        match self.senders.remove(id) {
+            // they might've stopped waiting already
+            Some(sender) => sender.send(response).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Stop waiting for the answer to the request with this id. This has to
+    /// be called if sending the request failed, since no answer will come.
+    pub fn forget(&mut self, id: &Id) {
+        self.senders.remove(id);
+    }
+
+    /// Whether we aren't waiting for any answers.
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+impl<Resp> Pending<u32, Resp> {
+    /// Pick an id for a new request and start waiting for its answer. If
+    /// sending the request fails, [`Pending::forget`] the id.
+    pub fn register_next(&mut self) -> (u32, oneshot::Receiver<Resp>) {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        (id, self.register(id))
+    }
+}
+
+/// Wait for the answer to a request registered in `pending`. If it doesn't
+/// come within the timeout, the request is forgotten and `None` is returned.
+pub(crate) async fn wait<Id: Eq + Hash, Resp>(
+    pending: &Mutex<Pending<Id, Resp>>,
+    id: Id,
+    receiver: oneshot::Receiver<Resp>,
+    timeout: Duration,
+) -> Option<Resp> {
+    match tokio::time::timeout(timeout, receiver).await {
+        Ok(Ok(response)) => Some(response),
+        // the sender is only dropped without sending if the request was
+        // forgotten, which counts as not getting an answer
+        Ok(Err(_)) | Err(_) => {
+            pending.lock().forget(&id);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let pending = Mutex::new(Pending::<u32, &str>::default());
+        let (first, first_receiver) = pending.lock().register_next();
+        let (second, second_receiver) = pending.lock().register_next();
+        assert_ne!(first, second);
+
+        assert!(pending.lock().resolve(&second, "b"));
+        assert!(!pending.lock().resolve(&second, "again"));
+        assert_eq!(
+            wait(&pending, second, second_receiver, Duration::from_secs(1)).await,
+            Some("b")
+        );
+        assert_eq!(
+            wait(&pending, first, first_receiver, Duration::from_millis(10)).await,
+            None
+        );
+        // it was forgotten when it timed out
+        assert!(!pending.lock().resolve(&first, "a"));
+    }
+}
@@ -1,5 +1,9 @@
+use azalea_auth::game_profile::{GameProfile, ProfilePropertyValue};
+use azalea_chat::component::Component;
+use azalea_core::GameType;
 use azalea_world::entity::{EntityMut, EntityRef};
 use azalea_world::Dimension;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Something that has a dimension associated to it. Usually, this is a `Client`.
@@ -8,15 +12,41 @@ pub trait DimensionHaver {
 }
 
 /// A player in the dimension or tab list.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Player {
     /// The player's uuid.
     pub uuid: Uuid,
-    /// The player's entity id.
+    /// The player's entity id. This is only known for our own player.
     pub entity_id: u32,
+    /// The player's username.
+    pub name: String,
+    /// The player's latency to the server in milliseconds, as shown in the tab
+    /// list.
+    pub ping: i32,
+    pub gamemode: GameType,
+    /// The name shown in the tab list, if it's different from the username.
+    pub display_name: Option<Component>,
+    /// The player's profile properties, like their skin. See
+    /// [`GameProfile::skin_url`].
+    pub properties: HashMap<String, ProfilePropertyValue>,
 }
 
 impl Player {
+    /// Get the player's profile, which has their uuid, username, and
+    /// properties.
+    pub fn profile(&self) -> GameProfile {
+        GameProfile {
+            uuid: self.uuid,
+            name: self.name.clone(),
+            properties: self.properties.clone(),
+        }
+    }
+
+    /// Get the URL of the player's skin, if they have a custom one.
+    pub fn skin_url(&self) -> Option<String> {
+        self.properties.get("textures")?.skin_url()
+    }
+
     /// Get a reference to the entity of the player in the world.
     pub fn entity<'d>(&'d self, dimension: &'d Dimension) -> Option<EntityRef> {
         dimension.entity(self.entity_id)
@@ -0,0 +1,80 @@
+use crate::Client;
+use azalea_protocol::packets::game::serverbound_resource_pack_packet::{
+    self, ServerboundResourcePackPacket,
+};
+
+/// What we do when a server asks us to use a resource pack. Resource packs are
+/// never actually downloaded, but some servers kick players that don't accept
+/// them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResourcePackPolicy {
+    /// Tell the server we accepted the resource pack and that it loaded
+    /// successfully.
+    #[default]
+    Accept,
+    /// Tell the server we declined the resource pack.
+    Decline,
+    /// Don't respond automatically. You should respond yourself with
+    /// [`Client::accept_resource_pack`] or [`Client::decline_resource_pack`]
+    /// when you get an [`Event::ResourcePackPrompt`].
+    ///
+    /// [`Event::ResourcePackPrompt`]: crate::Event::ResourcePackPrompt
+    Manual,
+}
+
+impl ResourcePackPolicy {
+    /// The statuses that we automatically send to the server when it asks us
+    /// to use a resource pack, in order.
+    pub fn responses(&self) -> Vec<serverbound_resource_pack_packet::Action> {
+        match self {
+            ResourcePackPolicy::Accept => vec![
+                serverbound_resource_pack_packet::Action::Accepted,
+                serverbound_resource_pack_packet::Action::SuccessfullyLoaded,
+            ],
+            ResourcePackPolicy::Decline => vec![serverbound_resource_pack_packet::Action::Declined],
+            ResourcePackPolicy::Manual => vec![],
+        }
+    }
+}
+
+impl Client {
+    /// Tell the server that we accepted and loaded its resource pack. This
+    /// doesn't download anything.
+    pub async fn accept_resource_pack(&self) -> Result<(), std::io::Error> {
+        for action in ResourcePackPolicy::Accept.responses() {
+            self.write_packet(ServerboundResourcePackPacket { action }.get())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Tell the server that we declined its resource pack.
+    pub async fn decline_resource_pack(&self) -> Result<(), std::io::Error> {
+        self.write_packet(
+            ServerboundResourcePackPacket {
+                action: serverbound_resource_pack_packet::Action::Declined,
+            }
+            .get(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_protocol::packets::game::serverbound_resource_pack_packet::Action;
+
+    #[test]
+    fn test_policy_responses() {
+        assert_eq!(
+            ResourcePackPolicy::default().responses(),
+            vec![Action::Accepted, Action::SuccessfullyLoaded]
+        );
+        assert_eq!(
+            ResourcePackPolicy::Decline.responses(),
+            vec![Action::Declined]
+        );
+        assert!(ResourcePackPolicy::Manual.responses().is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Client;
+use futures::future::BoxFuture;
+use std::collections::BTreeMap;
+
+/// Something to run on a future tick with [`Client::schedule_in`]. It's
+/// async so it can send packets.
+pub type ScheduledAction = Box<dyn FnOnce(Client) -> BoxFuture<'static, ()> + Send>;
+
+/// Counts our ticks and keeps track of the actions that should run on later
+/// ticks.
+#[derive(Default)]
+pub struct Scheduler {
+    /// How many ticks we've run since we joined.
+    pub tick_count: u64,
+    /// The actions to run, indexed by the tick they should run on. Actions
+    /// for the same tick run in the order they were scheduled.
+    actions: BTreeMap<u64, Vec<ScheduledAction>>,
+}
+
+impl Scheduler {
+    /// Run `action` after this many ticks. 0 and 1 both mean the next tick.
+    pub fn schedule_in(&mut self, ticks: u32, action: ScheduledAction) {
+        let tick = self.tick_count + ticks.max(1) as u64;
+        self.actions.entry(tick).or_default().push(action);
+    }
+
+    /// Count a tick and return the actions that should run on it. The
+    /// actions are returned instead of being run here so they can schedule
+    /// more actions.
+    pub fn tick(&mut self) -> Vec<ScheduledAction> {
+        self.tick_count += 1;
+        let later = self.actions.split_off(&(self.tick_count + 1));
+        std::mem::replace(&mut self.actions, later)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+    use futures::FutureExt;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_actions_run_on_their_tick() {
+        let (client, _rx, _server) = test_client();
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::default();
+        for (ticks, name) in [(3, "a"), (1, "b"), (3, "c"), (0, "d")] {
+            let ran = ran.clone();
+            scheduler.schedule_in(
+                ticks,
+                Box::new(move |_| async move { ran.lock().push(name) }.boxed()),
+            );
+        }
+
+        let mut ran_per_tick = Vec::new();
+        for _ in 0..4 {
+            for action in scheduler.tick() {
+                action(client.clone()).await;
+            }
+            ran_per_tick.push(std::mem::take(&mut *ran.lock()));
+        }
+        assert_eq!(scheduler.tick_count, 4);
+        assert_eq!(
+            ran_per_tick,
+            vec![vec!["b", "d"], vec![], vec!["a", "c"], vec![]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_actions_can_send_packets() {
+        let (client, _rx, mut server) = test_client();
+        client.schedule_in(1, |client| async move {
+            client.chat("hello").await.unwrap();
+        });
+
+        let actions = client.scheduler.lock().tick();
+        for action in actions {
+            action(client.clone()).await;
+        }
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundGamePacket::Chat(p) if p.message == "hello"
+        ));
+    }
+}
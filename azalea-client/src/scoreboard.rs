@@ -0,0 +1,281 @@
+use crate::{Client, Event, EventKind};
+use azalea_chat::component::Component;
+use azalea_protocol::packets::game::{
+    clientbound_set_display_objective_packet::ClientboundSetDisplayObjectivePacket,
+    clientbound_set_objective_packet::{self, ClientboundSetObjectivePacket, RenderType},
+    clientbound_set_score_packet::{self, ClientboundSetScorePacket},
+};
+use std::{collections::HashMap, time::Duration};
+
+/// The display slot that shows an objective on the right side of the screen.
+pub const SIDEBAR_SLOT: u8 = 1;
+/// The maximum number of scores vanilla shows in the sidebar.
+const MAX_SIDEBAR_SCORES: usize = 15;
+
+#[derive(Clone, Debug)]
+pub struct Objective {
+    pub display_name: Component,
+    pub render_type: RenderType,
+}
+
+/// The objectives and scores that the server told us about.
+#[derive(Clone, Debug, Default)]
+pub struct Scoreboard {
+    pub objectives: HashMap<String, Objective>,
+    /// The scores for each objective, indexed by the objective name and then
+    /// the owner.
+    pub scores: HashMap<String, HashMap<String, i32>>,
+    /// The objective names shown in each display slot.
+    pub display_slots: HashMap<u8, String>,
+}
+
+impl Scoreboard {
+    pub fn apply_objective(&mut self, packet: &ClientboundSetObjectivePacket) {
+        match &packet.method {
+            clientbound_set_objective_packet::Method::Add(info)
+            | clientbound_set_objective_packet::Method::Change(info) => {
+                self.objectives.insert(
+                    packet.objective_name.clone(),
+                    Objective {
+                        display_name: info.display_name.clone(),
+                        render_type: info.render_type,
+                    },
+                );
+            }
+            clientbound_set_objective_packet::Method::Remove => {
+                self.objectives.remove(&packet.objective_name);
+                self.scores.remove(&packet.objective_name);
+                self.display_slots
+                    .retain(|_, name| name != &packet.objective_name);
+            }
+        }
+    }
+
+    pub fn apply_score(&mut self, packet: &ClientboundSetScorePacket) {
+        match (packet.method, &packet.objective_name) {
+            (clientbound_set_score_packet::Method::Change { score }, Some(objective_name)) => {
+                self.scores
+                    .entry(objective_name.clone())
+                    .or_default()
+                    .insert(packet.owner.clone(), score as i32);
+            }
+            // changing a score without an objective doesn't mean anything
+            (clientbound_set_score_packet::Method::Change { .. }, None) => {}
+            (clientbound_set_score_packet::Method::Remove, Some(objective_name)) => {
+                if let Some(scores) = self.scores.get_mut(objective_name) {
+                    scores.remove(&packet.owner);
+                }
+            }
+            // no objective means the owner is removed from every objective
+            (clientbound_set_score_packet::Method::Remove, None) => {
+                for scores in self.scores.values_mut() {
+                    scores.remove(&packet.owner);
+                }
+            }
+        }
+    }
+
+    pub fn apply_display_objective(&mut self, packet: &ClientboundSetDisplayObjectivePacket) {
+        if packet.objective_name.is_empty() {
+            self.display_slots.remove(&packet.slot);
+        } else {
+            self.display_slots
+                .insert(packet.slot, packet.objective_name.clone());
+        }
+    }
+
+    /// The objective that's currently shown in the sidebar, if any.
+    pub fn sidebar_objective(&self) -> Option<&Objective> {
+        self.objectives.get(self.display_slots.get(&SIDEBAR_SLOT)?)
+    }
+
+    /// The lines in the sidebar, in the same order as vanilla shows them
+    /// (highest score first, ties sorted by name).
+    pub fn sidebar(&self) -> Vec<(Component, i32)> {
+        let objective_name = match self.display_slots.get(&SIDEBAR_SLOT) {
+            Some(objective_name) => objective_name,
+            None => return Vec::new(),
+        };
+        let scores = match self.scores.get(objective_name) {
+            Some(scores) => scores,
+            None => return Vec::new(),
+        };
+
+        let mut lines = scores
+            .iter()
+            // owners that start with # are hidden
+            .filter(|(owner, _)| !owner.starts_with('#'))
+            .collect::<Vec<_>>();
+        lines.sort_by(|(a_owner, a_score), (b_owner, b_score)| {
+            b_score.cmp(a_score).then_with(|| a_owner.cmp(b_owner))
+        });
+        lines
+            .into_iter()
+            .take(MAX_SIDEBAR_SCORES)
+            .map(|(owner, score)| (Component::from(owner.clone()), *score))
+            .collect()
+    }
+}
+
+impl Client {
+    /// Wait until the lines in the sidebar match the predicate, like for
+    /// waiting until a minigame starts. The sidebar is checked right away
+    /// and then every time the scoreboard changes.
+    ///
+    /// Returns the matching sidebar, or `None` if we disconnected first.
+    pub async fn wait_for_scoreboard(
+        &self,
+        predicate: impl Fn(&[(Component, i32)]) -> bool,
+    ) -> Option<Vec<(Component, i32)>> {
+        // subscribe before checking so we can't miss an update in between
+        let mut events = self.subscribe(EventKind::SCOREBOARD | EventKind::DISCONNECT);
+        loop {
+            let sidebar = self.sidebar();
+            if predicate(&sidebar) {
+                return Some(sidebar);
+            }
+            loop {
+                match events.recv().await? {
+                    Event::ScoreboardUpdate => break,
+                    Event::Disconnect(_) => return None,
+                    _ => continue,
+                }
+            }
+        }
+    }
+
+    /// Like [`Client::wait_for_scoreboard`], but giving up and returning
+    /// `None` if the sidebar doesn't match in time.
+    pub async fn wait_for_scoreboard_with_timeout(
+        &self,
+        predicate: impl Fn(&[(Component, i32)]) -> bool,
+        timeout: Duration,
+    ) -> Option<Vec<(Component, i32)>> {
+        tokio::time::timeout(timeout, self.wait_for_scoreboard(predicate))
+            .await
+            .ok()
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_protocol::packets::game::clientbound_set_objective_packet::DisplayInfo;
+
+    fn set_score(
+        owner: &str,
+        objective_name: Option<&str>,
+        score: Option<u32>,
+    ) -> ClientboundSetScorePacket {
+        ClientboundSetScorePacket {
+            owner: owner.to_string(),
+            method: match score {
+                Some(score) => clientbound_set_score_packet::Method::Change { score },
+                None => clientbound_set_score_packet::Method::Remove,
+            },
+            objective_name: objective_name.map(|s| s.to_string()),
+        }
+    }
+
+    fn scoreboard_with_sidebar() -> Scoreboard {
+        let mut scoreboard = Scoreboard::default();
+        scoreboard.apply_objective(&ClientboundSetObjectivePacket {
+            objective_name: "game".to_string(),
+            method: clientbound_set_objective_packet::Method::Add(DisplayInfo {
+                display_name: Component::from("Game".to_string()),
+                render_type: RenderType::Integer,
+            }),
+        });
+        scoreboard.apply_display_objective(&ClientboundSetDisplayObjectivePacket {
+            slot: SIDEBAR_SLOT,
+            objective_name: "game".to_string(),
+        });
+        scoreboard
+    }
+
+    fn sidebar_text_is(lines: &[(Component, i32)], text: &str) -> bool {
+        lines.iter().any(|(line, _)| line.to_string() == text)
+    }
+
+    fn sidebar_text(scoreboard: &Scoreboard) -> Vec<(String, i32)> {
+        scoreboard
+            .sidebar()
+            .into_iter()
+            .map(|(name, score)| (name.to_string(), score))
+            .collect()
+    }
+
+    #[test]
+    fn test_sidebar_order() {
+        let mut scoreboard = scoreboard_with_sidebar();
+        scoreboard.apply_score(&set_score("b", Some("game"), Some(3)));
+        scoreboard.apply_score(&set_score("a", Some("game"), Some(3)));
+        scoreboard.apply_score(&set_score("c", Some("game"), Some(10)));
+        scoreboard.apply_score(&set_score("#hidden", Some("game"), Some(100)));
+
+        assert_eq!(
+            sidebar_text(&scoreboard),
+            vec![
+                ("c".to_string(), 10),
+                ("a".to_string(), 3),
+                ("b".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_score() {
+        let mut scoreboard = scoreboard_with_sidebar();
+        scoreboard.apply_score(&set_score("a", Some("game"), Some(1)));
+        scoreboard.apply_score(&set_score("b", Some("game"), Some(2)));
+        scoreboard.apply_score(&set_score("b", None, None));
+
+        assert_eq!(sidebar_text(&scoreboard), vec![("a".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_remove_objective() {
+        let mut scoreboard = scoreboard_with_sidebar();
+        scoreboard.apply_score(&set_score("a", Some("game"), Some(1)));
+        scoreboard.apply_objective(&ClientboundSetObjectivePacket {
+            objective_name: "game".to_string(),
+            method: clientbound_set_objective_packet::Method::Remove,
+        });
+
+        assert!(scoreboard.sidebar_objective().is_none());
+        assert!(scoreboard.sidebar().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_scoreboard() {
+        let (client, _rx, mut server) = test_client();
+        *client.scoreboard.lock() = scoreboard_with_sidebar();
+
+        let waiting = tokio::spawn({
+            let client = client.clone();
+            async move {
+                client
+                    .wait_for_scoreboard(|lines| sidebar_text_is(lines, "Starting in 0s"))
+                    .await
+            }
+        });
+        for text in ["Starting in 1s", "Starting in 0s"] {
+            server
+                .write(set_score(text, Some("game"), Some(1)).get())
+                .await
+                .unwrap();
+        }
+        let sidebar = waiting.await.unwrap().unwrap();
+        assert!(sidebar_text_is(&sidebar, "Starting in 0s"));
+
+        assert!(client
+            .wait_for_scoreboard_with_timeout(
+                |lines| sidebar_text_is(lines, "Game over"),
+                Duration::from_millis(10)
+            )
+            .await
+            .is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Client;
+use azalea_buf::{McBufWritable, UnsizedByteArray};
+use azalea_protocol::packets::game::serverbound_client_information_packet::{
+    ChatVisibility, HumanoidArm, ServerboundClientInformationPacket,
+};
+
+/// The options the vanilla client tells the server about when it joins. These
+/// are sent automatically after we log in, and you can change them later with
+/// [`Client::set_settings`].
+#[derive(Clone, Debug)]
+pub struct ClientSettings {
+    /// The client brand, which is sent in a `minecraft:brand` plugin message
+    /// right after we log in.
+    pub brand: String,
+    /// The language code, like `en_us`. Some servers use this to translate
+    /// their messages.
+    pub language: String,
+    /// How many chunks around us we want the server to send.
+    pub view_distance: u8,
+    pub chat_visibility: ChatVisibility,
+    pub chat_colors: bool,
+    /// Which parts of the skin are shown, as a bitmask. The bits are cape,
+    /// jacket, left sleeve, right sleeve, left pants leg, right pants leg, and
+    /// hat.
+    pub model_customisation: u8,
+    pub main_hand: HumanoidArm,
+    pub text_filtering_enabled: bool,
+    /// Whether we show up in the server list's player sample.
+    pub allows_listing: bool,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            brand: "vanilla".to_string(),
+            language: "en_us".to_string(),
+            view_distance: 8,
+            chat_visibility: ChatVisibility::Full,
+            chat_colors: true,
+            model_customisation: 0b0111_1111,
+            main_hand: HumanoidArm::Right,
+            text_filtering_enabled: false,
+            allows_listing: true,
+        }
+    }
+}
+
+impl ClientSettings {
+    /// The packet that tells the server about these settings.
+    pub fn information_packet(&self) -> ServerboundClientInformationPacket {
+        ServerboundClientInformationPacket {
+            language: self.language.clone(),
+            view_distance: self.view_distance,
+            chat_visibility: self.chat_visibility,
+            chat_colors: self.chat_colors,
+            model_customisation: self.model_customisation,
+            main_hand: self.main_hand,
+            text_filtering_enabled: self.text_filtering_enabled,
+            allows_listing: self.allows_listing,
+        }
+    }
+
+    /// The data for the `minecraft:brand` plugin message, which is the brand
+    /// as a length-prefixed string.
+    pub fn brand_data(&self) -> UnsizedByteArray {
+        let mut data = Vec::new();
+        self.brand
+            .write_into(&mut data)
+            .expect("writing to a Vec can't fail");
+        data.into()
+    }
+}
+
+impl Client {
+    /// Change our [`ClientSettings`] and tell the server about them. The
+    /// brand is only sent when we log in, so changing it here won't do
+    /// anything until the next time we join.
+    pub async fn set_settings(&self, settings: ClientSettings) -> Result<(), std::io::Error> {
+        let packet = settings.information_packet();
+        *self.settings.lock() = settings;
+        self.write_packet(packet.get()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use azalea_protocol::packets::game::ServerboundGamePacket;
+
+    #[test]
+    fn test_brand_data() {
+        let settings = ClientSettings::default();
+        assert_eq!(settings.brand_data().to_vec(), b"\x07vanilla".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_set_settings() {
+        let (client, _rx, mut server) = test_client();
+        client
+            .set_settings(ClientSettings {
+                language: "de_de".to_string(),
+                view_distance: 4,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        match server.read().await.unwrap() {
+            ServerboundGamePacket::ClientInformation(p) => {
+                assert_eq!(p.language, "de_de");
+                assert_eq!(p.view_distance, 4);
+            }
+            p => panic!("Expected a client information packet, got {:?}", p),
+        }
+        assert_eq!(client.settings.lock().language, "de_de");
+    }
+}
@@ -0,0 +1,38 @@
+use azalea_core::ResourceLocation;
+use azalea_registry::SoundEvent;
+
+/// A sound that the server played. Vanilla sounds are sent as an id in the
+/// sound event registry, but servers can also play sounds from resource packs
+/// by name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SoundKind {
+    Registry(SoundEvent),
+    Custom(ResourceLocation),
+}
+
+impl SoundKind {
+    /// The name of the sound, like `minecraft:entity.zombie.ambient`.
+    pub fn name(&self) -> String {
+        match self {
+            SoundKind::Registry(sound) => sound.to_string(),
+            SoundKind::Custom(name) => name.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sound_name() {
+        assert_eq!(
+            SoundKind::Registry(SoundEvent::EntityZombieAmbient).name(),
+            "minecraft:entity.zombie.ambient"
+        );
+        assert_eq!(
+            SoundKind::Custom(ResourceLocation::new("mypack:beep").unwrap()).name(),
+            "mypack:beep"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{Client, Event};
+use bitflags::bitflags;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+bitflags! {
+    /// The categories of [`Event`]s, used to pick which events
+    /// [`Client::subscribe`] receives.
+    pub struct EventKind: u32 {
+        const LOGIN = 1 << 0;
+        const CHAT = 1 << 1;
+        const TICK = 1 << 2;
+        /// Every packet we receive. This is by far the most common event.
+        const PACKET = 1 << 3;
+        const ITEM_USE = 1 << 4;
+        const BOSS_BAR = 1 << 5;
+        const SCOREBOARD = 1 << 6;
+        const RESOURCE_PACK = 1 << 7;
+        const PATHFINDER = 1 << 8;
+        const PARTICLE = 1 << 9;
+        const SOUND = 1 << 10;
+        const HANDLER_ERROR = 1 << 11;
+        const EFFECT = 1 << 12;
+        const EXPERIENCE = 1 << 13;
+        const GAME_MODE = 1 << 14;
+        const WEATHER = 1 << 15;
+        const KNOCKBACK = 1 << 16;
+        const DISCONNECT = 1 << 17;
+        const PLUGIN_MESSAGE = 1 << 18;
+        const ENTITY_ANIMATION = 1 << 19;
+        const ENTITY_EQUIPMENT = 1 << 20;
+        const ENTITY_REMOVED = 1 << 21;
+        const DIMENSION_CHANGED = 1 << 22;
+    }
+}
+
+impl Event {
+    /// The category this event is in.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::Login => EventKind::LOGIN,
+            Event::Chat(_) => EventKind::CHAT,
+            Event::Tick => EventKind::TICK,
+            Event::Packet(_) => EventKind::PACKET,
+            Event::ItemUseFinished => EventKind::ITEM_USE,
+            Event::BossBarUpdate(_, _) => EventKind::BOSS_BAR,
+            Event::ScoreboardUpdate => EventKind::SCOREBOARD,
+            Event::ResourcePackPrompt(_, _) => EventKind::RESOURCE_PACK,
+            Event::PathProgress(_) | Event::GoalReached(_) | Event::PathFailed(_) => {
+                EventKind::PATHFINDER
+            }
+            Event::Particle(_) => EventKind::PARTICLE,
+            Event::Sound { .. } => EventKind::SOUND,
+            Event::HandlerError(_) => EventKind::HANDLER_ERROR,
+            Event::EffectAdded(_, _) | Event::EffectRemoved(_) => EventKind::EFFECT,
+            Event::LevelUp(_) => EventKind::EXPERIENCE,
+            Event::GameModeChanged(_) => EventKind::GAME_MODE,
+            Event::Weather(_) => EventKind::WEATHER,
+            Event::Knockback(_) => EventKind::KNOCKBACK,
+            Event::Disconnect(_) => EventKind::DISCONNECT,
+            Event::PluginMessage { .. } => EventKind::PLUGIN_MESSAGE,
+            Event::EntityAnimation { .. } => EventKind::ENTITY_ANIMATION,
+            Event::EntityEquipmentChanged { .. } => EventKind::ENTITY_EQUIPMENT,
+            Event::EntityRemoved(_) => EventKind::ENTITY_REMOVED,
+            Event::DimensionChanged(_) => EventKind::DIMENSION_CHANGED,
+        }
+    }
+}
+
+pub(crate) struct Subscriber {
+    filter: EventKind,
+    tx: UnboundedSender<Event>,
+}
+
+/// Sends events to the channel returned by [`Client::join`] and to every
+/// channel from [`Client::subscribe`] that wants them.
+#[derive(Clone)]
+pub(crate) struct EventSender {
+    tx: UnboundedSender<Event>,
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventSender {
+    pub fn new(tx: UnboundedSender<Event>, subscribers: Arc<Mutex<Vec<Subscriber>>>) -> Self {
+        Self { tx, subscribers }
+    }
+
+    /// Whether anyone is listening for events of this kind. If not, you can
+    /// skip creating the event.
+    pub fn wants(&self, kind: EventKind) -> bool {
+        !self.tx.is_closed()
+            || self
+                .subscribers
+                .lock()
+                .iter()
+                .any(|subscriber| subscriber.filter.contains(kind) && !subscriber.tx.is_closed())
+    }
+
+    /// Send an event to everyone that wants it. Receivers that were dropped
+    /// are ignored.
+    pub fn send(&self, event: Event) {
+        let kind = event.kind();
+        {
+            let mut subscribers = self.subscribers.lock();
+            subscribers.retain(|subscriber| !subscriber.tx.is_closed());
+            for subscriber in subscribers.iter() {
+                if subscriber.filter.contains(kind) {
+                    let _ = subscriber.tx.send(event.clone());
+                }
+            }
+        }
+        let _ = self.tx.send(event);
+    }
+}
+
+impl Client {
+    /// Get a new channel that only receives the events in the given
+    /// categories. The channel returned by [`Client::join`] still gets every
+    /// event, so if you only use filtered channels you should drop it to
+    /// avoid building events that nobody reads.
+    ///
+    /// ```no_run
+    /// # async fn example(client: azalea_client::Client) {
+    /// use azalea_client::EventKind;
+    ///
+    /// let mut chat = client.subscribe(EventKind::CHAT | EventKind::LOGIN);
+    /// while let Some(event) = chat.recv().await {
+    ///     println!("{:?}", event);
+    /// }
+    /// # }
+    /// ```
+    pub fn subscribe(&self, filter: EventKind) -> UnboundedReceiver<Event> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.event_subscribers
+            .lock()
+            .push(Subscriber { filter, tx });
+        rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filtered_subscribers() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let sender = EventSender::new(tx, subscribers.clone());
+
+        let (login_tx, mut login_rx) = mpsc::unbounded_channel();
+        subscribers.lock().push(Subscriber {
+            filter: EventKind::LOGIN,
+            tx: login_tx,
+        });
+
+        // the main channel gets everything
+        assert!(sender.wants(EventKind::PACKET));
+        sender.send(Event::Tick);
+        sender.send(Event::Login);
+        assert!(matches!(login_rx.try_recv(), Ok(Event::Login)));
+        assert!(login_rx.try_recv().is_err());
+
+        // once it's dropped, only the events that subscribers want are needed
+        drop(rx);
+        assert!(!sender.wants(EventKind::PACKET));
+        assert!(sender.wants(EventKind::LOGIN));
+        sender.send(Event::Tick);
+
+        drop(login_rx);
+        assert!(!sender.wants(EventKind::LOGIN));
+        sender.send(Event::Login);
+        assert!(subscribers.lock().is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::Player;
+use azalea_auth::game_profile::ProfilePropertyValue;
+use azalea_chat::component::Component;
+use azalea_core::GameType;
+use azalea_protocol::packets::game::clientbound_player_info_packet::{
+    Action, ClientboundPlayerInfoPacket,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The players and text that are shown when you hold tab.
+#[derive(Clone, Debug, Default)]
+pub struct TabList {
+    pub header: Option<Component>,
+    pub footer: Option<Component>,
+    pub players: HashMap<Uuid, Player>,
+}
+
+impl TabList {
+    pub fn apply_player_info(&mut self, packet: &ClientboundPlayerInfoPacket) {
+        match &packet.action {
+            Action::AddPlayer(players) => {
+                for p in players {
+                    self.players.insert(
+                        p.uuid,
+                        Player {
+                            uuid: p.uuid,
+                            name: p.name.clone(),
+                            ping: p.ping,
+                            gamemode: GameType::from_id(p.gamemode as u8).unwrap_or_default(),
+                            display_name: p.display_name.clone(),
+                            properties: p
+                                .properties
+                                .iter()
+                                .map(|property| {
+                                    (
+                                        property.name.clone(),
+                                        ProfilePropertyValue {
+                                            value: property.value.clone(),
+                                            signature: property.signature.clone(),
+                                        },
+                                    )
+                                })
+                                .collect(),
+                            ..Default::default()
+                        },
+                    );
+                }
+            }
+            Action::UpdateGameMode(players) => {
+                for p in players {
+                    if let Some(player) = self.players.get_mut(&p.uuid) {
+                        player.gamemode = GameType::from_id(p.gamemode as u8).unwrap_or_default();
+                    }
+                }
+            }
+            Action::UpdateLatency(players) => {
+                for p in players {
+                    if let Some(player) = self.players.get_mut(&p.uuid) {
+                        player.ping = p.ping;
+                    }
+                }
+            }
+            Action::UpdateDisplayName(players) => {
+                for p in players {
+                    if let Some(player) = self.players.get_mut(&p.uuid) {
+                        player.display_name = p.display_name.clone();
+                    }
+                }
+            }
+            Action::RemovePlayer(players) => {
+                for p in players {
+                    self.players.remove(&p.uuid);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_protocol::packets::game::clientbound_player_info_packet::{
+        AddPlayer, PlayerProperty, RemovePlayer, UpdateGameMode, UpdateLatency,
+    };
+
+    #[test]
+    fn test_player_info_updates() {
+        let uuid = Uuid::from_u128(1);
+        let mut tab_list = TabList::default();
+
+        tab_list.apply_player_info(&ClientboundPlayerInfoPacket {
+            action: Action::AddPlayer(vec![AddPlayer {
+                uuid,
+                name: "bot".to_string(),
+                properties: vec![PlayerProperty {
+                    name: "textures".to_string(),
+                    value: "abc".to_string(),
+                    signature: Some("signature".to_string()),
+                }],
+                gamemode: 1,
+                ping: 20,
+                display_name: None,
+                profile_public_key: None,
+            }]),
+        });
+        assert_eq!(tab_list.players[&uuid].name, "bot");
+        assert_eq!(tab_list.players[&uuid].gamemode, GameType::CREATIVE);
+        assert_eq!(tab_list.players[&uuid].properties["textures"].value, "abc");
+
+        tab_list.apply_player_info(&ClientboundPlayerInfoPacket {
+            action: Action::UpdateLatency(vec![UpdateLatency { uuid, ping: 150 }]),
+        });
+        tab_list.apply_player_info(&ClientboundPlayerInfoPacket {
+            action: Action::UpdateGameMode(vec![UpdateGameMode { uuid, gamemode: 3 }]),
+        });
+        assert_eq!(tab_list.players[&uuid].ping, 150);
+        assert_eq!(tab_list.players[&uuid].gamemode, GameType::SPECTATOR);
+
+        tab_list.apply_player_info(&ClientboundPlayerInfoPacket {
+            action: Action::RemovePlayer(vec![RemovePlayer { uuid }]),
+        });
+        assert!(tab_list.players.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use azalea_core::{Difficulty, GameType, ResourceLocation};
+use azalea_protocol::packets::game::{
+    clientbound_game_event_packet::GameEvent, clientbound_login_packet::ClientboundLoginPacket,
+    clientbound_respawn_packet::ClientboundRespawnPacket,
+    clientbound_set_time_packet::ClientboundSetTimePacket,
+};
+
+/// Whether it's raining or thundering, and how strongly.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Weather {
+    pub raining: bool,
+    /// How strong the rain is, from 0 to 1. This fades in and out when it
+    /// starts or stops raining.
+    pub rain_level: f32,
+    /// How strong the thunder is, from 0 to 1.
+    pub thunder_level: f32,
+}
+
+/// Information about the world we're in, mostly from the login packet.
+#[derive(Clone, Debug)]
+pub struct WorldInfo {
+    /// The dimension we're in, like `minecraft:overworld`.
+    pub dimension: ResourceLocation,
+    /// The type of the dimension we're in, which decides things like its
+    /// height.
+    pub dimension_type: ResourceLocation,
+    /// This is `None` until the server tells us, which it does right after
+    /// we log in.
+    pub difficulty: Option<Difficulty>,
+    /// Whether the difficulty can't be changed.
+    pub difficulty_locked: bool,
+    pub game_mode: GameType,
+    pub is_hardcore: bool,
+    pub max_players: i32,
+    /// The server's render distance in chunks.
+    pub view_distance: u32,
+    pub simulation_distance: u32,
+    /// The first 8 bytes of the SHA-256 hash of the world's seed.
+    pub hashed_seed: i64,
+    pub weather: Weather,
+    /// How many ticks the world has existed for.
+    pub game_time: u64,
+    /// How many ticks it's been since the first morning, not counting when
+    /// the daylight cycle was disabled.
+    pub day_time: u64,
+    /// Whether the time of day moves forward.
+    pub daylight_cycle: bool,
+}
+
+impl WorldInfo {
+    pub fn from_login(packet: &ClientboundLoginPacket) -> Self {
+        Self {
+            dimension: packet.dimension.clone(),
+            dimension_type: packet.dimension_type.clone(),
+            difficulty: None,
+            difficulty_locked: false,
+            game_mode: packet.game_type,
+            is_hardcore: packet.hardcore,
+            max_players: packet.max_players,
+            view_distance: packet.chunk_radius,
+            simulation_distance: packet.simulation_distance,
+            hashed_seed: packet.seed,
+            weather: Weather::default(),
+            game_time: 0,
+            day_time: 0,
+            daylight_cycle: true,
+        }
+    }
+
+    /// Update the dimension we're in. The game mode in the packet isn't
+    /// applied here, so the caller can tell whether it changed.
+    pub fn apply_respawn(&mut self, packet: &ClientboundRespawnPacket) {
+        self.dimension = packet.dimension.clone();
+        self.dimension_type = packet.dimension_type.clone();
+        self.hashed_seed = packet.seed as i64;
+    }
+
+    pub fn apply_set_time(&mut self, packet: &ClientboundSetTimePacket) {
+        self.game_time = packet.game_time;
+        // a negative time means the daylight cycle is disabled
+        self.day_time = packet.day_time.unsigned_abs();
+        self.daylight_cycle = packet.day_time >= 0;
+    }
+
+    /// Move the time forward by a tick, like the server does between set time
+    /// packets.
+    pub fn tick_time(&mut self) {
+        self.game_time += 1;
+        if self.daylight_cycle {
+            self.day_time += 1;
+        }
+    }
+
+    /// The time of the current day, from 0 to 24000. 0 is sunrise, 6000 is
+    /// noon, and 18000 is midnight.
+    pub fn time_of_day(&self) -> u32 {
+        (self.day_time % 24000) as u32
+    }
+
+    /// Whether it's between 13000 and 23000 in the day, which is when it's
+    /// dark enough for monsters to spawn.
+    pub fn is_night(&self) -> bool {
+        (13000..23000).contains(&self.time_of_day())
+    }
+
+    pub fn is_day(&self) -> bool {
+        !self.is_night()
+    }
+
+    /// Update the weather if the event is about it. Returns true if the
+    /// weather changed.
+    pub fn apply_game_event(&mut self, event: &GameEvent) -> bool {
+        let old_weather = self.weather;
+        match *event {
+            // the rain level is faded in and out later by the server
+            GameEvent::StartRaining => {
+                self.weather.raining = true;
+                self.weather.rain_level = 0.;
+            }
+            GameEvent::StopRaining => {
+                self.weather.raining = false;
+                self.weather.rain_level = 1.;
+            }
+            GameEvent::RainLevelChange(level) => self.weather.rain_level = level,
+            GameEvent::ThunderLevelChange(level) => self.weather.thunder_level = level,
+            _ => {}
+        }
+        self.weather != old_weather
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world_info() -> WorldInfo {
+        WorldInfo {
+            dimension: ResourceLocation::new("minecraft:overworld").unwrap(),
+            dimension_type: ResourceLocation::new("minecraft:overworld").unwrap(),
+            difficulty: None,
+            difficulty_locked: false,
+            game_mode: GameType::SURVIVAL,
+            is_hardcore: false,
+            max_players: 20,
+            view_distance: 10,
+            simulation_distance: 10,
+            hashed_seed: 0,
+            weather: Weather::default(),
+            game_time: 0,
+            day_time: 0,
+            daylight_cycle: true,
+        }
+    }
+
+    #[test]
+    fn test_set_time() {
+        let mut world_info = world_info();
+        world_info.apply_set_time(&ClientboundSetTimePacket {
+            game_time: 100,
+            day_time: 24000 * 3 + 12999,
+        });
+        assert_eq!(world_info.time_of_day(), 12999);
+        assert!(world_info.is_day());
+        world_info.tick_time();
+        assert_eq!(world_info.game_time, 101);
+        assert!(world_info.is_night());
+    }
+
+    #[test]
+    fn test_frozen_time() {
+        let mut world_info = world_info();
+        world_info.apply_set_time(&ClientboundSetTimePacket {
+            game_time: 100,
+            day_time: -18000,
+        });
+        assert_eq!(world_info.time_of_day(), 18000);
+        assert!(!world_info.daylight_cycle);
+        world_info.tick_time();
+        assert_eq!(world_info.time_of_day(), 18000);
+        assert_eq!(world_info.game_time, 101);
+    }
+
+    #[test]
+    fn test_weather() {
+        let mut world_info = world_info();
+        assert!(world_info.apply_game_event(&GameEvent::StartRaining));
+        assert!(world_info.weather.raining);
+        assert!(world_info.apply_game_event(&GameEvent::RainLevelChange(0.5)));
+        assert_eq!(world_info.weather.rain_level, 0.5);
+        assert!(!world_info.apply_game_event(&GameEvent::ArrowHitPlayer));
+    }
+}
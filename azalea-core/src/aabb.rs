@@ -4,6 +4,7 @@ pub const EPSILON: f64 = 1.0E-7;
 
 /// A rectangular prism with a starting and ending point.
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AABB {
     pub min_x: f64,
     pub min_y: f64,
@@ -248,7 +249,8 @@ impl AABB {
         let z = to.z - from.z;
 
         for aabb in boxes {
-            dir = self.get_direction(aabb, from, &mut t, dir, &Vec3 { x, y, z });
+            let aabb = aabb.move_relative(pos.x as f64, pos.y as f64, pos.z as f64);
+            dir = self.get_direction(&aabb, from, &mut t, dir, &Vec3 { x, y, z });
         }
         let dir = dir?;
         let t = t[0];
@@ -269,8 +271,9 @@ impl AABB {
         dir: Option<Direction>,
         delta: &Vec3,
     ) -> Option<Direction> {
+        let mut dir = dir;
         if delta.x > EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta,
@@ -283,7 +286,7 @@ impl AABB {
                 start: from,
             });
         } else if delta.x < -EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta,
@@ -298,7 +301,7 @@ impl AABB {
         }
 
         if delta.y > EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta: &Vec3 {
@@ -319,7 +322,7 @@ impl AABB {
                 },
             });
         } else if delta.y < -EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta: &Vec3 {
@@ -342,7 +345,7 @@ impl AABB {
         }
 
         if delta.z > EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta: &Vec3 {
@@ -363,7 +366,7 @@ impl AABB {
                 },
             });
         } else if delta.z < -EPSILON {
-            return self.clip_point(ClipPointOpts {
+            dir = self.clip_point(ClipPointOpts {
                 t,
                 approach_dir: dir,
                 delta: &Vec3 {
@@ -390,8 +393,8 @@ impl AABB {
 
     fn clip_point(&self, opts: ClipPointOpts) -> Option<Direction> {
         let t_x = (opts.begin - opts.start.x) / opts.delta.x;
-        let t_y = (opts.start.y + t_x) / opts.delta.y;
-        let t_z = (opts.start.z + t_x) / opts.delta.z;
+        let t_y = opts.start.y + t_x * opts.delta.y;
+        let t_z = opts.start.z + t_x * opts.delta.z;
         if 0.0 < t_x
             && t_x < opts.t[0]
             && opts.min_x - EPSILON < t_y
@@ -434,10 +437,134 @@ impl AABB {
         }
     }
 
+    /// The point in or on the box that's closest to `point`. Points inside
+    /// the box are returned unchanged.
+    pub fn closest_point(&self, point: &Vec3) -> Vec3 {
+        Vec3 {
+            x: point.x.clamp(self.min_x, self.max_x),
+            y: point.y.clamp(self.min_y, self.max_y),
+            z: point.z.clamp(self.min_z, self.max_z),
+        }
+    }
+
+    /// The squared distance from `point` to the closest point of the box, or
+    /// 0 if it's inside the box.
+    pub fn distance_squared_to_point(&self, point: &Vec3) -> f64 {
+        self.closest_point(point).distance_squared_to(point)
+    }
+
+    /// The distance from `point` to the closest point of the box, or 0 if
+    /// it's inside the box. This is how vanilla checks whether an entity is
+    /// in reach, with the point being the player's eyes.
+    pub fn distance_to_point(&self, point: &Vec3) -> f64 {
+        self.distance_squared_to_point(point).sqrt()
+    }
+
     pub fn max(&self, axis: &Axis) -> f64 {
         axis.choose(self.max_x, self.max_y, self.max_z)
     }
     pub fn min(&self, axis: &Axis) -> f64 {
         axis.choose(self.min_x, self.min_y, self.min_z)
     }
+
+    /// Rotate the box clockwise (when looking down) around the vertical line
+    /// through the center of the block, so one quarter turn moves the north
+    /// side of the block to the east side. This is for boxes in block shapes,
+    /// which go from 0 to 1.
+    pub fn rotate_y(&self, quarter_turns: u8) -> AABB {
+        let mut aabb = *self;
+        for _ in 0..quarter_turns % 4 {
+            aabb = AABB {
+                min_x: 1. - aabb.max_z,
+                min_y: aabb.min_y,
+                min_z: aabb.min_x,
+                max_x: 1. - aabb.min_z,
+                max_y: aabb.max_y,
+                max_z: aabb.max_x,
+            };
+        }
+        aabb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_y() {
+        // the north half of a block
+        let north = AABB {
+            min_x: 0.,
+            min_y: 0.,
+            min_z: 0.,
+            max_x: 1.,
+            max_y: 1.,
+            max_z: 0.5,
+        };
+        let east = north.rotate_y(1);
+        assert_eq!(
+            east,
+            AABB {
+                min_x: 0.5,
+                min_y: 0.,
+                min_z: 0.,
+                max_x: 1.,
+                max_y: 1.,
+                max_z: 1.,
+            }
+        );
+        assert_eq!(north.rotate_y(2).min_z, 0.5);
+        assert_eq!(north.rotate_y(3).max_x, 0.5);
+        assert_eq!(north.rotate_y(4), north);
+    }
+
+    #[test]
+    fn test_distance_to_point() {
+        let aabb = AABB {
+            min_x: 0.,
+            min_y: 64.,
+            min_z: 0.,
+            max_x: 1.,
+            max_y: 65.8,
+            max_z: 1.,
+        };
+        let inside = Vec3 {
+            x: 0.5,
+            y: 65.,
+            z: 0.25,
+        };
+        assert_eq!(aabb.closest_point(&inside), inside);
+        assert_eq!(aabb.distance_to_point(&inside), 0.);
+
+        let outside = Vec3 {
+            x: 4.,
+            y: 70.,
+            z: 0.5,
+        };
+        assert_eq!(
+            aabb.closest_point(&outside),
+            Vec3 {
+                x: 1.,
+                y: 65.8,
+                z: 0.5,
+            }
+        );
+        assert!((aabb.distance_to_point(&outside) - (3f64 * 3. + 4.2 * 4.2).sqrt()).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let aabb = AABB {
+            min_x: -0.3,
+            min_y: 64.,
+            min_z: 0.7,
+            max_x: 0.3,
+            max_y: 65.8,
+            max_z: 1.3,
+        };
+        let json = serde_json::to_string(&aabb).unwrap();
+        assert_eq!(serde_json::from_str::<AABB>(&json).unwrap(), aabb);
+    }
 }
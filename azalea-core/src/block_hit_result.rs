@@ -7,3 +7,51 @@ pub struct BlockHitResult {
     pub miss: bool,
     pub inside: bool,
 }
+
+impl BlockHitResult {
+    /// The unit vector pointing out of the face of the block that was hit.
+    pub fn face_normal(&self) -> Vec3 {
+        self.direction.normal().into()
+    }
+
+    /// The position of the block on the other side of the face that was hit.
+    /// This is where a block would go if you placed one on the face.
+    pub fn adjacent_block_pos(&self) -> BlockPos {
+        self.block_pos.offset(self.direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_block_pos() {
+        let hit = BlockHitResult {
+            location: Vec3 {
+                x: 0.5,
+                y: 65.,
+                z: 0.5,
+            },
+            direction: Direction::Up,
+            block_pos: BlockPos::new(0, 64, 0),
+            miss: false,
+            inside: false,
+        };
+        assert_eq!(
+            hit.face_normal(),
+            Vec3 {
+                x: 0.,
+                y: 1.,
+                z: 0.
+            }
+        );
+        assert_eq!(hit.adjacent_block_pos(), BlockPos::new(0, 65, 0));
+
+        let hit = BlockHitResult {
+            direction: Direction::West,
+            ..hit
+        };
+        assert_eq!(hit.adjacent_block_pos(), BlockPos::new(-1, 64, 0));
+    }
+}
@@ -75,6 +75,48 @@ impl Vec3 {
     pub fn scale(&self, amount: f64) -> Vec3 {
         self.multiply(amount, amount, amount)
     }
+
+    /// Get the position `t` of the way from this one to `to`, so 0 gives this
+    /// position and 1 gives `to`. Values outside of 0 to 1 keep going past
+    /// the ends.
+    pub fn lerp(&self, to: &Vec3, t: f64) -> Vec3 {
+        // written this way instead of self + (to - self) * t so the ends are
+        // exact
+        Vec3 {
+            x: self.x * (1. - t) + to.x * t,
+            y: self.y * (1. - t) + to.y * t,
+            z: self.z * (1. - t) + to.z * t,
+        }
+    }
+
+    /// Get the squared distance from this position to another position. This
+    /// is cheaper than [`Vec3::distance_to`] since it doesn't need a square
+    /// root, so prefer it when you're only comparing distances.
+    pub fn distance_squared_to(&self, other: &Vec3) -> f64 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        let dz = self.z - other.z;
+        dx * dx + dy * dy + dz * dz
+    }
+
+    /// Get the distance from this position to another position.
+    pub fn distance_to(&self, other: &Vec3) -> f64 {
+        self.distance_squared_to(other).sqrt()
+    }
+
+    /// Get the squared distance from this position to another position,
+    /// ignoring the y axis.
+    pub fn horizontal_distance_squared_to(&self, other: &Vec3) -> f64 {
+        let dx = self.x - other.x;
+        let dz = self.z - other.z;
+        dx * dx + dz * dz
+    }
+
+    /// Get the distance from this position to another position, ignoring the
+    /// y axis.
+    pub fn horizontal_distance_to(&self, other: &Vec3) -> f64 {
+        self.horizontal_distance_squared_to(other).sqrt()
+    }
 }
 
 // impl + and +=
@@ -97,3 +139,79 @@ impl AddAssign for Vec3 {
         self.z += other.z;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_to() {
+        let a = Vec3 {
+            x: 1.,
+            y: 2.,
+            z: 3.,
+        };
+        let b = Vec3 {
+            x: 4.,
+            y: 6.,
+            z: 3.,
+        };
+        assert_eq!(a.distance_squared_to(&b), 25.);
+        assert_eq!(a.distance_to(&b), 5.);
+        assert_eq!(b.distance_to(&a), 5.);
+        assert_eq!(a.distance_to(&a), 0.);
+    }
+
+    #[test]
+    fn test_horizontal_distance_to() {
+        let a = Vec3 {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        let b = Vec3 {
+            x: 3.,
+            y: 100.,
+            z: 4.,
+        };
+        assert_eq!(a.horizontal_distance_squared_to(&b), 25.);
+        assert_eq!(a.horizontal_distance_to(&b), 5.);
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Vec3 {
+            x: 0.1,
+            y: -7.3,
+            z: 1e9,
+        };
+        let b = Vec3 {
+            x: 0.3,
+            y: 64.7,
+            z: -0.2,
+        };
+        assert_eq!(a.lerp(&b, 0.), a);
+        assert_eq!(a.lerp(&b, 1.), b);
+        let middle = Vec3 {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        }
+        .lerp(
+            &Vec3 {
+                x: 2.,
+                y: 4.,
+                z: -6.,
+            },
+            0.5,
+        );
+        assert_eq!(
+            middle,
+            Vec3 {
+                x: 1.,
+                y: 2.,
+                z: -3.,
+            }
+        );
+    }
+}
@@ -1,8 +1,8 @@
 use azalea_buf::McBuf;
 
-use crate::floor_mod;
+use crate::{floor_mod, BlockPos, Vec3};
 
-#[derive(Clone, Copy, Debug, McBuf)]
+#[derive(Clone, Copy, Debug, McBuf, PartialEq, Eq)]
 pub enum Direction {
     Down = 0,
     Up = 1,
@@ -12,7 +12,7 @@ pub enum Direction {
     East = 5,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Axis {
     X = 0,
     Y = 1,
@@ -26,6 +26,66 @@ pub enum AxisCycle {
     Backward = 2,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::Down,
+        Direction::Up,
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+    ];
+
+    /// The direction that points the other way, like `Up` for `Down`.
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Down => Direction::Up,
+            Direction::Up => Direction::Down,
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+        }
+    }
+
+    pub fn axis(self) -> Axis {
+        match self {
+            Direction::Down | Direction::Up => Axis::Y,
+            Direction::North | Direction::South => Axis::Z,
+            Direction::West | Direction::East => Axis::X,
+        }
+    }
+
+    /// The offset to the neighboring block in this direction, like `(0, 1, 0)`
+    /// for `Up`.
+    pub fn normal(self) -> BlockPos {
+        match self {
+            Direction::Down => BlockPos::new(0, -1, 0),
+            Direction::Up => BlockPos::new(0, 1, 0),
+            Direction::North => BlockPos::new(0, 0, -1),
+            Direction::South => BlockPos::new(0, 0, 1),
+            Direction::West => BlockPos::new(-1, 0, 0),
+            Direction::East => BlockPos::new(1, 0, 0),
+        }
+    }
+
+    /// Get the direction that's closest to pointing the same way as the
+    /// vector.
+    pub fn from_vec3(vec: &Vec3) -> Direction {
+        let mut nearest = Direction::North;
+        let mut nearest_dot = f64::MIN;
+        for direction in Direction::ALL {
+            let normal = direction.normal();
+            let dot = vec.x * normal.x as f64 + vec.y * normal.y as f64 + vec.z * normal.z as f64;
+            if dot > nearest_dot {
+                nearest = direction;
+                nearest_dot = dot;
+            }
+        }
+        nearest
+    }
+}
+
 impl Axis {
     /// Pick x, y, or z from the arguments depending on the axis.
     #[inline]
@@ -81,3 +141,40 @@ impl AxisCycle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opposite() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_ne!(direction.opposite(), direction);
+            assert_eq!(direction.opposite().axis(), direction.axis());
+        }
+    }
+
+    #[test]
+    fn test_from_vec3() {
+        for direction in Direction::ALL {
+            assert_eq!(Direction::from_vec3(&direction.normal().into()), direction);
+        }
+        assert_eq!(
+            Direction::from_vec3(&Vec3 {
+                x: 0.2,
+                y: -0.9,
+                z: 0.3
+            }),
+            Direction::Down
+        );
+        assert_eq!(
+            Direction::from_vec3(&Vec3 {
+                x: -5.,
+                y: 1.,
+                z: 4.
+            }),
+            Direction::West
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use azalea_buf::McBuf;
+
+/// A slot that an entity can hold or wear an item in.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash, McBuf)]
+pub enum EquipmentSlot {
+    MainHand = 0,
+    OffHand = 1,
+    Feet = 2,
+    Legs = 3,
+    Chest = 4,
+    Head = 5,
+}
+
+impl EquipmentSlot {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(EquipmentSlot::MainHand),
+            1 => Some(EquipmentSlot::OffHand),
+            2 => Some(EquipmentSlot::Feet),
+            3 => Some(EquipmentSlot::Legs),
+            4 => Some(EquipmentSlot::Chest),
+            5 => Some(EquipmentSlot::Head),
+            _ => None,
+        }
+    }
+}
@@ -14,6 +14,9 @@ pub use game_type::*;
 mod slot;
 pub use slot::*;
 
+mod equipment_slot;
+pub use equipment_slot::*;
+
 mod position;
 pub use position::*;
 
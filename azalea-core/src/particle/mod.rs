@@ -1,14 +1,35 @@
-use crate::{BlockPos, Slot};
-use azalea_buf::{BufReadError, McBuf, McBufReadable, McBufVarReadable, McBufWritable};
+use crate::{BlockPos, ResourceLocation, Slot};
+use azalea_buf::{
+    BufReadError, McBuf, McBufReadable, McBufVarReadable, McBufVarWritable, McBufWritable,
+};
 use std::io::{Cursor, Write};
 
-#[derive(Debug, Clone, McBuf)]
+#[derive(Debug, Clone)]
 pub struct Particle {
-    #[var]
     pub id: i32,
     pub data: ParticleData,
 }
 
+// the id is only sent once, so we can't derive this
+impl McBufReadable for Particle {
+    fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
+        let id = i32::var_read_from(buf)?;
+        let data = ParticleData::read_from_particle_id(buf, id as u32)?;
+        Ok(Particle { id, data })
+    }
+}
+
+impl McBufWritable for Particle {
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        self.id.var_write_into(buf)?;
+        self.data.write_data(buf)
+    }
+}
+
+/// The type of a particle, without any of its data. This is the particle type
+/// registry, so `kind as u32` is the particle's protocol id.
+pub type ParticleKind = azalea_registry::ParticleType;
+
 #[derive(Clone, Debug)]
 pub enum ParticleData {
     AmbientEntityEffect,
@@ -35,10 +56,14 @@ pub enum ParticleData {
     EntityEffect,
     ExplosionEmitter,
     Explosion,
+    SonicBoom,
     FallingDust(BlockParticle),
     Firework,
     Fishing,
     Flame,
+    SculkSoul,
+    SculkCharge(SculkChargeParticle),
+    SculkChargePop,
     SoulFireFlame,
     Soul,
     Flash,
@@ -99,6 +124,7 @@ pub enum ParticleData {
     WaxOff,
     ElectricSpark,
     Scrape,
+    Shriek(ShriekParticle),
 }
 
 #[derive(Debug, Clone, McBuf)]
@@ -141,111 +167,300 @@ pub struct ItemParticle {
     pub item: Slot,
 }
 
-#[derive(Debug, Clone, McBuf)]
+#[derive(Debug, Clone)]
 pub struct VibrationParticle {
-    pub origin: BlockPos,
-    pub position_type: String,
-    pub block_position: BlockPos,
-    #[var]
-    pub entity_id: u32,
-    #[var]
+    /// Where the vibration is going.
+    pub destination: PositionSource,
+    /// How many ticks it takes to get there.
     pub ticks: u32,
 }
 
+/// Something that a vibration can go to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionSource {
+    Block(BlockPos),
+    Entity {
+        id: u32,
+        /// How far above the entity's feet the vibration goes.
+        y_offset: f32,
+    },
+}
+
+// the fields after the position source type depend on what the type is, so
+// we can't derive this
+impl McBufReadable for VibrationParticle {
+    fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
+        let source_type = ResourceLocation::read_from(buf)?;
+        let destination = match source_type.to_string().as_str() {
+            "minecraft:block" => PositionSource::Block(BlockPos::read_from(buf)?),
+            "minecraft:entity" => PositionSource::Entity {
+                id: u32::var_read_from(buf)?,
+                y_offset: f32::read_from(buf)?,
+            },
+            _ => {
+                return Err(BufReadError::UnexpectedStringEnumVariant {
+                    id: source_type.to_string(),
+                })
+            }
+        };
+        let ticks = u32::var_read_from(buf)?;
+        Ok(VibrationParticle { destination, ticks })
+    }
+}
+impl McBufWritable for VibrationParticle {
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        match &self.destination {
+            PositionSource::Block(pos) => {
+                ResourceLocation::new("minecraft:block")
+                    .unwrap()
+                    .write_into(buf)?;
+                pos.write_into(buf)?;
+            }
+            PositionSource::Entity { id, y_offset } => {
+                ResourceLocation::new("minecraft:entity")
+                    .unwrap()
+                    .write_into(buf)?;
+                id.var_write_into(buf)?;
+                y_offset.write_into(buf)?;
+            }
+        }
+        self.ticks.var_write_into(buf)
+    }
+}
+
+#[derive(Debug, Clone, McBuf)]
+pub struct SculkChargeParticle {
+    /// The rotation of the particle, in radians.
+    pub roll: f32,
+}
+
+#[derive(Debug, Clone, McBuf)]
+pub struct ShriekParticle {
+    /// How many ticks to wait before the particle is shown.
+    #[var]
+    pub delay: u32,
+}
+
 impl ParticleData {
     pub fn read_from_particle_id(buf: &mut Cursor<&[u8]>, id: u32) -> Result<Self, BufReadError> {
-        Ok(match id {
-            0 => ParticleData::AmbientEntityEffect,
-            1 => ParticleData::AngryVillager,
-            2 => ParticleData::Block(BlockParticle::read_from(buf)?),
-            3 => ParticleData::BlockMarker(BlockParticle::read_from(buf)?),
-            4 => ParticleData::Bubble,
-            5 => ParticleData::Cloud,
-            6 => ParticleData::Crit,
-            7 => ParticleData::DamageIndicator,
-            8 => ParticleData::DragonBreath,
-            9 => ParticleData::DrippingLava,
-            10 => ParticleData::FallingLava,
-            11 => ParticleData::LandingLava,
-            12 => ParticleData::DrippingWater,
-            13 => ParticleData::FallingWater,
-            14 => ParticleData::Dust(DustParticle::read_from(buf)?),
-            15 => ParticleData::DustColorTransition(DustColorTransitionParticle::read_from(buf)?),
-            16 => ParticleData::Effect,
-            17 => ParticleData::ElderGuardian,
-            18 => ParticleData::EnchantedHit,
-            19 => ParticleData::Enchant,
-            20 => ParticleData::EndRod,
-            21 => ParticleData::EntityEffect,
-            22 => ParticleData::ExplosionEmitter,
-            23 => ParticleData::Explosion,
-            24 => ParticleData::FallingDust(BlockParticle::read_from(buf)?),
-            25 => ParticleData::Firework,
-            26 => ParticleData::Fishing,
-            27 => ParticleData::Flame,
-            28 => ParticleData::SoulFireFlame,
-            29 => ParticleData::Soul,
-            30 => ParticleData::Flash,
-            31 => ParticleData::HappyVillager,
-            32 => ParticleData::Composter,
-            33 => ParticleData::Heart,
-            34 => ParticleData::InstantEffect,
-            35 => ParticleData::Item(ItemParticle::read_from(buf)?),
-            36 => ParticleData::Vibration(VibrationParticle::read_from(buf)?),
-            37 => ParticleData::ItemSlime,
-            38 => ParticleData::ItemSnowball,
-            39 => ParticleData::LargeSmoke,
-            40 => ParticleData::Lava,
-            41 => ParticleData::Mycelium,
-            42 => ParticleData::Note,
-            43 => ParticleData::Poof,
-            44 => ParticleData::Portal,
-            45 => ParticleData::Rain,
-            46 => ParticleData::Smoke,
-            47 => ParticleData::Sneeze,
-            48 => ParticleData::Spit,
-            49 => ParticleData::SquidInk,
-            50 => ParticleData::SweepAttack,
-            51 => ParticleData::TotemOfUndying,
-            52 => ParticleData::Underwater,
-            53 => ParticleData::Splash,
-            54 => ParticleData::Witch,
-            55 => ParticleData::BubblePop,
-            56 => ParticleData::CurrentDown,
-            57 => ParticleData::BubbleColumnUp,
-            58 => ParticleData::Nautilus,
-            59 => ParticleData::Dolphin,
-            60 => ParticleData::CampfireCozySmoke,
-            61 => ParticleData::CampfireSignalSmoke,
-            62 => ParticleData::DrippingHoney,
-            63 => ParticleData::FallingHoney,
-            64 => ParticleData::LandingHoney,
-            65 => ParticleData::FallingNectar,
-            66 => ParticleData::FallingSporeBlossom,
-            67 => ParticleData::Ash,
-            68 => ParticleData::CrimsonSpore,
-            69 => ParticleData::WarpedSpore,
-            70 => ParticleData::SporeBlossomAir,
-            71 => ParticleData::DrippingObsidianTear,
-            72 => ParticleData::FallingObsidianTear,
-            73 => ParticleData::LandingObsidianTear,
-            74 => ParticleData::ReversePortal,
-            75 => ParticleData::WhiteAsh,
-            76 => ParticleData::SmallFlame,
-            77 => ParticleData::Snowflake,
-            78 => ParticleData::DrippingDripstoneLava,
-            79 => ParticleData::FallingDripstoneLava,
-            80 => ParticleData::DrippingDripstoneWater,
-            81 => ParticleData::FallingDripstoneWater,
-            82 => ParticleData::GlowSquidInk,
-            83 => ParticleData::Glow,
-            84 => ParticleData::WaxOn,
-            85 => ParticleData::WaxOff,
-            86 => ParticleData::ElectricSpark,
-            87 => ParticleData::Scrape,
-            _ => return Err(BufReadError::UnexpectedEnumVariant { id: id as i32 }),
+        let kind = ParticleKind::try_from(id)
+            .map_err(|_| BufReadError::UnexpectedEnumVariant { id: id as i32 })?;
+        Ok(match kind {
+            ParticleKind::AmbientEntityEffect => ParticleData::AmbientEntityEffect,
+            ParticleKind::AngryVillager => ParticleData::AngryVillager,
+            ParticleKind::Block => ParticleData::Block(BlockParticle::read_from(buf)?),
+            ParticleKind::BlockMarker => ParticleData::BlockMarker(BlockParticle::read_from(buf)?),
+            ParticleKind::Bubble => ParticleData::Bubble,
+            ParticleKind::Cloud => ParticleData::Cloud,
+            ParticleKind::Crit => ParticleData::Crit,
+            ParticleKind::DamageIndicator => ParticleData::DamageIndicator,
+            ParticleKind::DragonBreath => ParticleData::DragonBreath,
+            ParticleKind::DrippingLava => ParticleData::DrippingLava,
+            ParticleKind::FallingLava => ParticleData::FallingLava,
+            ParticleKind::LandingLava => ParticleData::LandingLava,
+            ParticleKind::DrippingWater => ParticleData::DrippingWater,
+            ParticleKind::FallingWater => ParticleData::FallingWater,
+            ParticleKind::Dust => ParticleData::Dust(DustParticle::read_from(buf)?),
+            ParticleKind::DustColorTransition => {
+                ParticleData::DustColorTransition(DustColorTransitionParticle::read_from(buf)?)
+            }
+            ParticleKind::Effect => ParticleData::Effect,
+            ParticleKind::ElderGuardian => ParticleData::ElderGuardian,
+            ParticleKind::EnchantedHit => ParticleData::EnchantedHit,
+            ParticleKind::Enchant => ParticleData::Enchant,
+            ParticleKind::EndRod => ParticleData::EndRod,
+            ParticleKind::EntityEffect => ParticleData::EntityEffect,
+            ParticleKind::ExplosionEmitter => ParticleData::ExplosionEmitter,
+            ParticleKind::Explosion => ParticleData::Explosion,
+            ParticleKind::SonicBoom => ParticleData::SonicBoom,
+            ParticleKind::FallingDust => ParticleData::FallingDust(BlockParticle::read_from(buf)?),
+            ParticleKind::Firework => ParticleData::Firework,
+            ParticleKind::Fishing => ParticleData::Fishing,
+            ParticleKind::Flame => ParticleData::Flame,
+            ParticleKind::SculkSoul => ParticleData::SculkSoul,
+            ParticleKind::SculkCharge => {
+                ParticleData::SculkCharge(SculkChargeParticle::read_from(buf)?)
+            }
+            ParticleKind::SculkChargePop => ParticleData::SculkChargePop,
+            ParticleKind::SoulFireFlame => ParticleData::SoulFireFlame,
+            ParticleKind::Soul => ParticleData::Soul,
+            ParticleKind::Flash => ParticleData::Flash,
+            ParticleKind::HappyVillager => ParticleData::HappyVillager,
+            ParticleKind::Composter => ParticleData::Composter,
+            ParticleKind::Heart => ParticleData::Heart,
+            ParticleKind::InstantEffect => ParticleData::InstantEffect,
+            ParticleKind::Item => ParticleData::Item(ItemParticle::read_from(buf)?),
+            ParticleKind::Vibration => ParticleData::Vibration(VibrationParticle::read_from(buf)?),
+            ParticleKind::ItemSlime => ParticleData::ItemSlime,
+            ParticleKind::ItemSnowball => ParticleData::ItemSnowball,
+            ParticleKind::LargeSmoke => ParticleData::LargeSmoke,
+            ParticleKind::Lava => ParticleData::Lava,
+            ParticleKind::Mycelium => ParticleData::Mycelium,
+            ParticleKind::Note => ParticleData::Note,
+            ParticleKind::Poof => ParticleData::Poof,
+            ParticleKind::Portal => ParticleData::Portal,
+            ParticleKind::Rain => ParticleData::Rain,
+            ParticleKind::Smoke => ParticleData::Smoke,
+            ParticleKind::Sneeze => ParticleData::Sneeze,
+            ParticleKind::Spit => ParticleData::Spit,
+            ParticleKind::SquidInk => ParticleData::SquidInk,
+            ParticleKind::SweepAttack => ParticleData::SweepAttack,
+            ParticleKind::TotemOfUndying => ParticleData::TotemOfUndying,
+            ParticleKind::Underwater => ParticleData::Underwater,
+            ParticleKind::Splash => ParticleData::Splash,
+            ParticleKind::Witch => ParticleData::Witch,
+            ParticleKind::BubblePop => ParticleData::BubblePop,
+            ParticleKind::CurrentDown => ParticleData::CurrentDown,
+            ParticleKind::BubbleColumnUp => ParticleData::BubbleColumnUp,
+            ParticleKind::Nautilus => ParticleData::Nautilus,
+            ParticleKind::Dolphin => ParticleData::Dolphin,
+            ParticleKind::CampfireCosySmoke => ParticleData::CampfireCozySmoke,
+            ParticleKind::CampfireSignalSmoke => ParticleData::CampfireSignalSmoke,
+            ParticleKind::DrippingHoney => ParticleData::DrippingHoney,
+            ParticleKind::FallingHoney => ParticleData::FallingHoney,
+            ParticleKind::LandingHoney => ParticleData::LandingHoney,
+            ParticleKind::FallingNectar => ParticleData::FallingNectar,
+            ParticleKind::FallingSporeBlossom => ParticleData::FallingSporeBlossom,
+            ParticleKind::Ash => ParticleData::Ash,
+            ParticleKind::CrimsonSpore => ParticleData::CrimsonSpore,
+            ParticleKind::WarpedSpore => ParticleData::WarpedSpore,
+            ParticleKind::SporeBlossomAir => ParticleData::SporeBlossomAir,
+            ParticleKind::DrippingObsidianTear => ParticleData::DrippingObsidianTear,
+            ParticleKind::FallingObsidianTear => ParticleData::FallingObsidianTear,
+            ParticleKind::LandingObsidianTear => ParticleData::LandingObsidianTear,
+            ParticleKind::ReversePortal => ParticleData::ReversePortal,
+            ParticleKind::WhiteAsh => ParticleData::WhiteAsh,
+            ParticleKind::SmallFlame => ParticleData::SmallFlame,
+            ParticleKind::Snowflake => ParticleData::Snowflake,
+            ParticleKind::DrippingDripstoneLava => ParticleData::DrippingDripstoneLava,
+            ParticleKind::FallingDripstoneLava => ParticleData::FallingDripstoneLava,
+            ParticleKind::DrippingDripstoneWater => ParticleData::DrippingDripstoneWater,
+            ParticleKind::FallingDripstoneWater => ParticleData::FallingDripstoneWater,
+            ParticleKind::GlowSquidInk => ParticleData::GlowSquidInk,
+            ParticleKind::Glow => ParticleData::Glow,
+            ParticleKind::WaxOn => ParticleData::WaxOn,
+            ParticleKind::WaxOff => ParticleData::WaxOff,
+            ParticleKind::ElectricSpark => ParticleData::ElectricSpark,
+            ParticleKind::Scrape => ParticleData::Scrape,
+            ParticleKind::Shriek => ParticleData::Shriek(ShriekParticle::read_from(buf)?),
         })
     }
+
+    /// The type of this particle.
+    pub fn kind(&self) -> ParticleKind {
+        match self {
+            ParticleData::AmbientEntityEffect => ParticleKind::AmbientEntityEffect,
+            ParticleData::AngryVillager => ParticleKind::AngryVillager,
+            ParticleData::Block(_) => ParticleKind::Block,
+            ParticleData::BlockMarker(_) => ParticleKind::BlockMarker,
+            ParticleData::Bubble => ParticleKind::Bubble,
+            ParticleData::Cloud => ParticleKind::Cloud,
+            ParticleData::Crit => ParticleKind::Crit,
+            ParticleData::DamageIndicator => ParticleKind::DamageIndicator,
+            ParticleData::DragonBreath => ParticleKind::DragonBreath,
+            ParticleData::DrippingLava => ParticleKind::DrippingLava,
+            ParticleData::FallingLava => ParticleKind::FallingLava,
+            ParticleData::LandingLava => ParticleKind::LandingLava,
+            ParticleData::DrippingWater => ParticleKind::DrippingWater,
+            ParticleData::FallingWater => ParticleKind::FallingWater,
+            ParticleData::Dust(_) => ParticleKind::Dust,
+            ParticleData::DustColorTransition(_) => ParticleKind::DustColorTransition,
+            ParticleData::Effect => ParticleKind::Effect,
+            ParticleData::ElderGuardian => ParticleKind::ElderGuardian,
+            ParticleData::EnchantedHit => ParticleKind::EnchantedHit,
+            ParticleData::Enchant => ParticleKind::Enchant,
+            ParticleData::EndRod => ParticleKind::EndRod,
+            ParticleData::EntityEffect => ParticleKind::EntityEffect,
+            ParticleData::ExplosionEmitter => ParticleKind::ExplosionEmitter,
+            ParticleData::Explosion => ParticleKind::Explosion,
+            ParticleData::SonicBoom => ParticleKind::SonicBoom,
+            ParticleData::FallingDust(_) => ParticleKind::FallingDust,
+            ParticleData::Firework => ParticleKind::Firework,
+            ParticleData::Fishing => ParticleKind::Fishing,
+            ParticleData::Flame => ParticleKind::Flame,
+            ParticleData::SculkSoul => ParticleKind::SculkSoul,
+            ParticleData::SculkCharge(_) => ParticleKind::SculkCharge,
+            ParticleData::SculkChargePop => ParticleKind::SculkChargePop,
+            ParticleData::SoulFireFlame => ParticleKind::SoulFireFlame,
+            ParticleData::Soul => ParticleKind::Soul,
+            ParticleData::Flash => ParticleKind::Flash,
+            ParticleData::HappyVillager => ParticleKind::HappyVillager,
+            ParticleData::Composter => ParticleKind::Composter,
+            ParticleData::Heart => ParticleKind::Heart,
+            ParticleData::InstantEffect => ParticleKind::InstantEffect,
+            ParticleData::Item(_) => ParticleKind::Item,
+            ParticleData::Vibration(_) => ParticleKind::Vibration,
+            ParticleData::ItemSlime => ParticleKind::ItemSlime,
+            ParticleData::ItemSnowball => ParticleKind::ItemSnowball,
+            ParticleData::LargeSmoke => ParticleKind::LargeSmoke,
+            ParticleData::Lava => ParticleKind::Lava,
+            ParticleData::Mycelium => ParticleKind::Mycelium,
+            ParticleData::Note => ParticleKind::Note,
+            ParticleData::Poof => ParticleKind::Poof,
+            ParticleData::Portal => ParticleKind::Portal,
+            ParticleData::Rain => ParticleKind::Rain,
+            ParticleData::Smoke => ParticleKind::Smoke,
+            ParticleData::Sneeze => ParticleKind::Sneeze,
+            ParticleData::Spit => ParticleKind::Spit,
+            ParticleData::SquidInk => ParticleKind::SquidInk,
+            ParticleData::SweepAttack => ParticleKind::SweepAttack,
+            ParticleData::TotemOfUndying => ParticleKind::TotemOfUndying,
+            ParticleData::Underwater => ParticleKind::Underwater,
+            ParticleData::Splash => ParticleKind::Splash,
+            ParticleData::Witch => ParticleKind::Witch,
+            ParticleData::BubblePop => ParticleKind::BubblePop,
+            ParticleData::CurrentDown => ParticleKind::CurrentDown,
+            ParticleData::BubbleColumnUp => ParticleKind::BubbleColumnUp,
+            ParticleData::Nautilus => ParticleKind::Nautilus,
+            ParticleData::Dolphin => ParticleKind::Dolphin,
+            ParticleData::CampfireCozySmoke => ParticleKind::CampfireCosySmoke,
+            ParticleData::CampfireSignalSmoke => ParticleKind::CampfireSignalSmoke,
+            ParticleData::DrippingHoney => ParticleKind::DrippingHoney,
+            ParticleData::FallingHoney => ParticleKind::FallingHoney,
+            ParticleData::LandingHoney => ParticleKind::LandingHoney,
+            ParticleData::FallingNectar => ParticleKind::FallingNectar,
+            ParticleData::FallingSporeBlossom => ParticleKind::FallingSporeBlossom,
+            ParticleData::Ash => ParticleKind::Ash,
+            ParticleData::CrimsonSpore => ParticleKind::CrimsonSpore,
+            ParticleData::WarpedSpore => ParticleKind::WarpedSpore,
+            ParticleData::SporeBlossomAir => ParticleKind::SporeBlossomAir,
+            ParticleData::DrippingObsidianTear => ParticleKind::DrippingObsidianTear,
+            ParticleData::FallingObsidianTear => ParticleKind::FallingObsidianTear,
+            ParticleData::LandingObsidianTear => ParticleKind::LandingObsidianTear,
+            ParticleData::ReversePortal => ParticleKind::ReversePortal,
+            ParticleData::WhiteAsh => ParticleKind::WhiteAsh,
+            ParticleData::SmallFlame => ParticleKind::SmallFlame,
+            ParticleData::Snowflake => ParticleKind::Snowflake,
+            ParticleData::DrippingDripstoneLava => ParticleKind::DrippingDripstoneLava,
+            ParticleData::FallingDripstoneLava => ParticleKind::FallingDripstoneLava,
+            ParticleData::DrippingDripstoneWater => ParticleKind::DrippingDripstoneWater,
+            ParticleData::FallingDripstoneWater => ParticleKind::FallingDripstoneWater,
+            ParticleData::GlowSquidInk => ParticleKind::GlowSquidInk,
+            ParticleData::Glow => ParticleKind::Glow,
+            ParticleData::WaxOn => ParticleKind::WaxOn,
+            ParticleData::WaxOff => ParticleKind::WaxOff,
+            ParticleData::ElectricSpark => ParticleKind::ElectricSpark,
+            ParticleData::Scrape => ParticleKind::Scrape,
+            ParticleData::Shriek(_) => ParticleKind::Shriek,
+        }
+    }
+
+    /// Write the data of the particle without its id. This is the opposite
+    /// of [`ParticleData::read_from_particle_id`].
+    pub fn write_data(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        match self {
+            ParticleData::Block(data) => data.write_into(buf),
+            ParticleData::BlockMarker(data) => data.write_into(buf),
+            ParticleData::Dust(data) => data.write_into(buf),
+            ParticleData::DustColorTransition(data) => data.write_into(buf),
+            ParticleData::FallingDust(data) => data.write_into(buf),
+            ParticleData::Item(data) => data.write_into(buf),
+            ParticleData::Vibration(data) => data.write_into(buf),
+            ParticleData::SculkCharge(data) => data.write_into(buf),
+            ParticleData::Shriek(data) => data.write_into(buf),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl McBufReadable for ParticleData {
@@ -256,7 +471,147 @@ impl McBufReadable for ParticleData {
 }
 
 impl McBufWritable for ParticleData {
-    fn write_into(&self, _buf: &mut impl Write) -> Result<(), std::io::Error> {
-        todo!()
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        (self.kind() as u32).var_write_into(buf)?;
+        self.write_data(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_particle_kind_ids() {
+        assert_eq!(ParticleKind::AmbientEntityEffect as u32, 0);
+        assert_eq!(ParticleKind::Dust as u32, 14);
+        assert_eq!(ParticleKind::SonicBoom as u32, 24);
+        assert_eq!(ParticleKind::FallingDust as u32, 25);
+        assert_eq!(ParticleKind::Scrape as u32, 91);
+        assert_eq!(ParticleKind::Shriek as u32, 92);
+        assert_eq!(ParticleData::Scrape.kind(), ParticleKind::Scrape);
+        assert!(ParticleKind::try_from(93).is_err());
+    }
+
+    #[test]
+    fn test_read_particles_after_sonic_boom() {
+        // sonic boom doesn't have any data
+        let data = ParticleData::read_from(&mut Cursor::new(&[24][..])).unwrap();
+        assert_eq!(data.kind(), ParticleKind::SonicBoom);
+
+        // falling dust has a block state
+        let data = ParticleData::read_from(&mut Cursor::new(&[25, 1][..])).unwrap();
+        assert!(matches!(
+            data,
+            ParticleData::FallingDust(BlockParticle { block_state: 1 })
+        ));
+
+        let mut buf = Vec::new();
+        ParticleData::Shriek(ShriekParticle { delay: 5 })
+            .write_into(&mut buf)
+            .unwrap();
+        assert_eq!(buf, vec![92, 5]);
+        let data = ParticleData::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert!(matches!(
+            data,
+            ParticleData::Shriek(ShriekParticle { delay: 5 })
+        ));
+
+        let mut buf = Vec::new();
+        ParticleData::SculkCharge(SculkChargeParticle { roll: 1. })
+            .write_into(&mut buf)
+            .unwrap();
+        assert_eq!(buf[0], 30);
+        let data = ParticleData::read_from(&mut Cursor::new(&buf)).unwrap();
+        match data {
+            ParticleData::SculkCharge(p) => assert_eq!(p.roll, 1.),
+            _ => panic!("Expected a sculk charge particle"),
+        }
+    }
+
+    #[test]
+    fn test_particle_data_round_trip() {
+        let mut buf = Vec::new();
+        ParticleData::Dust(DustParticle {
+            red: 1.,
+            green: 0.5,
+            blue: 0.,
+            scale: 2.,
+        })
+        .write_into(&mut buf)
+        .unwrap();
+        assert_eq!(buf[0], 14);
+
+        let data = ParticleData::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(data.kind(), ParticleKind::Dust);
+        if let ParticleData::Dust(dust) = data {
+            assert_eq!((dust.red, dust.green, dust.blue), (1., 0.5, 0.));
+            assert_eq!(dust.scale, 2.);
+        } else {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn test_particle_id_is_only_read_once() {
+        let mut buf = Vec::new();
+        Particle {
+            id: 2,
+            data: ParticleData::Block(BlockParticle { block_state: 1 }),
+        }
+        .write_into(&mut buf)
+        .unwrap();
+        assert_eq!(buf, vec![2, 1]);
+
+        let particle = Particle::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert!(matches!(
+            particle.data,
+            ParticleData::Block(BlockParticle { block_state: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_vibration_particle() {
+        let mut buf = vec![15];
+        buf.extend_from_slice(b"minecraft:block");
+        // the block position, 1 2 3
+        buf.extend_from_slice(&[0, 0, 0, 0x40, 0, 0, 0x30, 0x02]);
+        // the ticks
+        buf.push(20);
+
+        let particle = VibrationParticle::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(
+            particle.destination,
+            PositionSource::Block(BlockPos::new(1, 2, 3))
+        );
+        assert_eq!(particle.ticks, 20);
+        let mut written = Vec::new();
+        particle.write_into(&mut written).unwrap();
+        assert_eq!(written, buf);
+
+        let mut buf = vec![16];
+        buf.extend_from_slice(b"minecraft:entity");
+        // the entity id
+        buf.push(5);
+        // the y offset, 1.5
+        buf.extend_from_slice(&[0x3f, 0xc0, 0, 0]);
+        // the ticks
+        buf.push(20);
+
+        let particle = VibrationParticle::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(
+            particle.destination,
+            PositionSource::Entity {
+                id: 5,
+                y_offset: 1.5
+            }
+        );
+        let mut written = Vec::new();
+        particle.write_into(&mut written).unwrap();
+        assert_eq!(written, buf);
+
+        let mut buf = vec![15];
+        buf.extend_from_slice(b"minecraft:stone");
+        assert!(VibrationParticle::read_from(&mut Cursor::new(&buf)).is_err());
     }
 }
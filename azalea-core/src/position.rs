@@ -1,4 +1,4 @@
-use crate::ResourceLocation;
+use crate::{Direction, ResourceLocation};
 use azalea_buf::{BufReadError, McBufReadable, McBufWritable};
 use std::{
     io::{Cursor, Write},
@@ -52,7 +52,8 @@ where
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockPos {
     pub x: i32,
     pub y: i32,
@@ -67,6 +68,18 @@ impl BlockPos {
     pub fn below(&self) -> Self {
         self.add(0, -1, 0)
     }
+
+    /// The position next to this one in the given direction.
+    pub fn offset(&self, direction: Direction) -> Self {
+        let normal = direction.normal();
+        self.add(normal.x, normal.y, normal.z)
+    }
+
+    /// The six positions that share a face with this one, in the same order
+    /// as [`Direction::ALL`].
+    pub fn neighbors(&self) -> [BlockPos; 6] {
+        Direction::ALL.map(|direction| self.offset(direction))
+    }
 }
 
 impl Rem<i32> for BlockPos {
@@ -124,10 +137,26 @@ impl ChunkPos {
     pub fn new(x: i32, z: i32) -> Self {
         ChunkPos { x, z }
     }
+
+    /// Iterate over the sections in this chunk, from the bottom of the world
+    /// to the top. `min_y` and `height` should be the ones for the dimension.
+    pub fn sections(&self, min_y: i32, height: u32) -> impl Iterator<Item = ChunkSectionPos> {
+        let chunk_pos = *self;
+        let section_count = height.div_ceil(16);
+        (0..section_count)
+            .map(move |index| ChunkSectionPos::from_section_index(chunk_pos, index, min_y))
+    }
+
+    /// Iterate over every block position in this chunk, section by section
+    /// from the bottom of the world to the top.
+    pub fn blocks(&self, min_y: i32, height: u32) -> impl Iterator<Item = BlockPos> {
+        self.sections(min_y, height)
+            .flat_map(|section| section.iter_block_positions())
+    }
 }
 
 /// The coordinates of a chunk section in the world.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ChunkSectionPos {
     pub x: i32,
     pub y: i32,
@@ -141,9 +170,47 @@ impl ChunkSectionPos {
     pub fn block_to_section_coord(block: i32) -> i32 {
         block >> 4
     }
+
+    /// Get the position of a section from the chunk it's in and its index in
+    /// the chunk. The section at index 0 is the one at the bottom of the
+    /// world, which is at `min_y`.
+    pub fn from_section_index(chunk_pos: ChunkPos, index: u32, min_y: i32) -> Self {
+        ChunkSectionPos {
+            x: chunk_pos.x,
+            y: Self::block_to_section_coord(min_y) + index as i32,
+            z: chunk_pos.z,
+        }
+    }
+
+    /// The index of this section in its chunk, where 0 is the section at the
+    /// bottom of the world. Returns `None` if the section is below the world.
+    pub fn section_index(&self, min_y: i32) -> Option<u32> {
+        u32::try_from(self.y - Self::block_to_section_coord(min_y)).ok()
+    }
+
+    /// The position of the block in this section with the lowest
+    /// coordinates.
+    pub fn origin(&self) -> BlockPos {
+        *self + ChunkSectionBlockPos::new(0, 0, 0)
+    }
+
+    /// Iterate over the positions of the 4096 blocks in this section. They're
+    /// in the same order as they're stored in the section, so x changes the
+    /// fastest and y the slowest.
+    pub fn iter_block_positions(&self) -> impl Iterator<Item = BlockPos> {
+        let section_pos = *self;
+        (0..4096u16).map(move |index| {
+            section_pos
+                + ChunkSectionBlockPos::new(
+                    (index & 15) as u8,
+                    (index >> 8) as u8,
+                    ((index >> 4) & 15) as u8,
+                )
+        })
+    }
 }
 /// The coordinates of a block inside a chunk.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ChunkBlockPos {
     pub x: u8,
     pub y: i32,
@@ -156,7 +223,7 @@ impl ChunkBlockPos {
     }
 }
 /// The coordinates of a block inside a chunk section.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct ChunkSectionBlockPos {
     /// A number between 0 and 16.
     pub x: u8,
@@ -194,6 +261,7 @@ pub struct GlobalPos {
 
 /// An exact point in the world.
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,
@@ -233,6 +301,17 @@ impl PositionXYZ<f64> for Vec3 {
     }
 }
 
+impl From<BlockPos> for Vec3 {
+    /// Convert the block position to the position of its lower corner.
+    fn from(pos: BlockPos) -> Self {
+        Vec3 {
+            x: pos.x as f64,
+            y: pos.y as f64,
+            z: pos.z as f64,
+        }
+    }
+}
+
 impl From<&BlockPos> for ChunkPos {
     fn from(pos: &BlockPos) -> Self {
         ChunkPos {
@@ -271,9 +350,9 @@ impl From<&BlockPos> for ChunkBlockPos {
 impl From<&BlockPos> for ChunkSectionBlockPos {
     fn from(pos: &BlockPos) -> Self {
         ChunkSectionBlockPos {
-            x: pos.x.rem(16).unsigned_abs() as u8,
-            y: pos.y.rem(16).unsigned_abs() as u8,
-            z: pos.z.rem(16).unsigned_abs() as u8,
+            x: pos.x.rem_euclid(16) as u8,
+            y: pos.y.rem_euclid(16) as u8,
+            z: pos.z.rem_euclid(16) as u8,
         }
     }
 }
@@ -282,7 +361,7 @@ impl From<&ChunkBlockPos> for ChunkSectionBlockPos {
     fn from(pos: &ChunkBlockPos) -> Self {
         ChunkSectionBlockPos {
             x: pos.x,
-            y: pos.y.rem(16).unsigned_abs() as u8,
+            y: pos.y.rem_euclid(16) as u8,
             z: pos.z,
         }
     }
@@ -375,6 +454,18 @@ impl McBufWritable for ChunkSectionPos {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_neighbors() {
+        let pos = BlockPos::new(1, 64, -3);
+        assert_eq!(pos.offset(Direction::Up), BlockPos::new(1, 65, -3));
+        assert_eq!(pos.offset(Direction::North), BlockPos::new(1, 64, -4));
+        let neighbors = pos.neighbors();
+        for (neighbor, direction) in neighbors.iter().zip(Direction::ALL) {
+            assert_eq!(*neighbor, pos.offset(direction));
+            assert_eq!(neighbor.offset(direction.opposite()), pos);
+        }
+    }
+
     #[test]
     fn test_from_block_pos_to_chunk_pos() {
         let block_pos = BlockPos::new(5, 78, -2);
@@ -411,6 +502,70 @@ mod tests {
         assert_eq!(chunk_pos, ChunkPos::new(1, -2));
     }
 
+    #[test]
+    fn test_from_negative_block_pos_to_chunk_section_block_pos() {
+        let block_pos = BlockPos::new(-1, -63, -16);
+        assert_eq!(
+            ChunkSectionBlockPos::from(&block_pos),
+            ChunkSectionBlockPos::new(15, 1, 0)
+        );
+        assert_eq!(
+            ChunkSectionBlockPos::from(&ChunkBlockPos::from(&block_pos)),
+            ChunkSectionBlockPos::new(15, 1, 0)
+        );
+        assert_eq!(
+            ChunkSectionPos::from(block_pos) + ChunkSectionBlockPos::from(&block_pos),
+            block_pos
+        );
+    }
+
+    #[test]
+    fn test_section_index_with_negative_y() {
+        let chunk_pos = ChunkPos::new(2, -3);
+        let bottom = ChunkSectionPos::from_section_index(chunk_pos, 0, -64);
+        assert_eq!(bottom, ChunkSectionPos::new(2, -4, -3));
+        assert_eq!(bottom.origin(), BlockPos::new(32, -64, -48));
+        assert_eq!(bottom.section_index(-64), Some(0));
+
+        let section = ChunkSectionPos::from(BlockPos::new(32, -1, -48));
+        assert_eq!(section.y, -1);
+        assert_eq!(section.section_index(-64), Some(3));
+        assert_eq!(
+            ChunkSectionPos::from(BlockPos::new(32, 0, -48)).section_index(-64),
+            Some(4)
+        );
+        assert_eq!(ChunkSectionPos::new(2, -5, -3).section_index(-64), None);
+    }
+
+    #[test]
+    fn test_chunk_sections() {
+        let sections = ChunkPos::new(0, 0).sections(-64, 384).collect::<Vec<_>>();
+        assert_eq!(sections.len(), 24);
+        assert_eq!(sections[0].y, -4);
+        assert_eq!(sections[23].y, 19);
+    }
+
+    #[test]
+    fn test_iter_block_positions() {
+        let section = ChunkSectionPos::new(-1, -4, 0);
+        let positions = section.iter_block_positions().collect::<Vec<_>>();
+        assert_eq!(positions.len(), 4096);
+        assert_eq!(positions[0], BlockPos::new(-16, -64, 0));
+        assert_eq!(positions[1], BlockPos::new(-15, -64, 0));
+        assert_eq!(positions[16], BlockPos::new(-16, -64, 1));
+        assert_eq!(positions[4095], BlockPos::new(-1, -49, 15));
+        assert!(positions
+            .iter()
+            .all(|pos| ChunkSectionPos::from(*pos) == section));
+    }
+
+    #[test]
+    fn test_chunk_blocks() {
+        let mut blocks = ChunkPos::new(0, 0).blocks(-64, 384);
+        assert_eq!(blocks.next(), Some(BlockPos::new(0, -64, 0)));
+        assert_eq!(blocks.last(), Some(BlockPos::new(15, 319, 15)));
+    }
+
     #[test]
     fn test_read_blockpos_from() {
         let mut buf = Vec::new();
@@ -419,4 +574,21 @@ mod tests {
         let block_pos = BlockPos::read_from(&mut buf).unwrap();
         assert_eq!(block_pos, BlockPos::new(49, -43, -3));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let block_pos = BlockPos::new(49, -43, -3);
+        let json = serde_json::to_string(&block_pos).unwrap();
+        assert_eq!(json, r#"{"x":49,"y":-43,"z":-3}"#);
+        assert_eq!(serde_json::from_str::<BlockPos>(&json).unwrap(), block_pos);
+
+        let vec3 = Vec3 {
+            x: 0.5,
+            y: -64.,
+            z: 1e9,
+        };
+        let json = serde_json::to_string(&vec3).unwrap();
+        assert_eq!(serde_json::from_str::<Vec3>(&json).unwrap(), vec3);
+    }
 }
@@ -1,7 +1,11 @@
 //! A resource, like minecraft:stone
 
 use azalea_buf::{BufReadError, McBufReadable, McBufWritable};
-use std::io::{Cursor, Write};
+use std::{
+    io::{Cursor, Write},
+    str::FromStr,
+};
+use thiserror::Error;
 
 #[derive(Hash, Clone, PartialEq, Eq)]
 pub struct ResourceLocation {
@@ -12,21 +16,33 @@ pub struct ResourceLocation {
 static DEFAULT_NAMESPACE: &str = "minecraft";
 // static REALMS_NAMESPACE: &str = "realms";
 
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ResourceLocationError {
+    #[error("Non [a-z0-9_.-] character in namespace of location: {0}")]
+    InvalidNamespace(String),
+    #[error("Non [a-z0-9/._-] character in path of location: {0}")]
+    InvalidPath(String),
+}
+
 impl ResourceLocation {
-    pub fn new(resource_string: &str) -> Result<ResourceLocation, BufReadError> {
-        let sep_byte_position_option = resource_string.chars().position(|c| c == ':');
-        let (namespace, path) = if let Some(sep_byte_position) = sep_byte_position_option {
-            if sep_byte_position == 0 {
-                (DEFAULT_NAMESPACE, &resource_string[1..])
-            } else {
-                (
-                    &resource_string[..sep_byte_position],
-                    &resource_string[sep_byte_position + 1..],
-                )
-            }
-        } else {
-            (DEFAULT_NAMESPACE, resource_string)
+    /// Parse a resource location like `minecraft:stone`. If there's no
+    /// namespace, it's `minecraft`.
+    pub fn new(resource_string: &str) -> Result<ResourceLocation, ResourceLocationError> {
+        let (namespace, path) = match resource_string.split_once(':') {
+            Some(("", path)) => (DEFAULT_NAMESPACE, path),
+            Some((namespace, path)) => (namespace, path),
+            None => (DEFAULT_NAMESPACE, resource_string),
         };
+        if !namespace.chars().all(is_valid_namespace_char) {
+            return Err(ResourceLocationError::InvalidNamespace(
+                resource_string.to_string(),
+            ));
+        }
+        if !path.chars().all(is_valid_path_char) {
+            return Err(ResourceLocationError::InvalidPath(
+                resource_string.to_string(),
+            ));
+        }
         Ok(ResourceLocation {
             namespace: namespace.to_string(),
             path: path.to_string(),
@@ -34,6 +50,22 @@ impl ResourceLocation {
     }
 }
 
+fn is_valid_namespace_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '_' | '-' | '.')
+}
+
+fn is_valid_path_char(c: char) -> bool {
+    is_valid_namespace_char(c) || c == '/'
+}
+
+impl FromStr for ResourceLocation {
+    type Err = ResourceLocationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ResourceLocation::new(s)
+    }
+}
+
 impl std::fmt::Display for ResourceLocation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}", self.namespace, self.path)
@@ -48,7 +80,7 @@ impl std::fmt::Debug for ResourceLocation {
 impl McBufReadable for ResourceLocation {
     fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
         let location_string = String::read_from(buf)?;
-        ResourceLocation::new(&location_string)
+        ResourceLocation::new(&location_string).map_err(|e| BufReadError::Custom(e.to_string()))
     }
 }
 impl McBufWritable for ResourceLocation {
@@ -86,6 +118,50 @@ mod tests {
         assert_eq!(r.path, "");
     }
 
+    #[test]
+    fn from_str() {
+        let r: ResourceLocation = "textures/block/stone.png".parse().unwrap();
+        assert_eq!(r.namespace, "minecraft");
+        assert_eq!(r.path, "textures/block/stone.png");
+        assert_eq!(r.to_string(), "minecraft:textures/block/stone.png");
+    }
+    #[test]
+    fn invalid_characters() {
+        assert_eq!(
+            ResourceLocation::new("Minecraft:stone"),
+            Err(ResourceLocationError::InvalidNamespace(
+                "Minecraft:stone".to_string()
+            ))
+        );
+        assert_eq!(
+            ResourceLocation::new("minecraft:stone block"),
+            Err(ResourceLocationError::InvalidPath(
+                "minecraft:stone block".to_string()
+            ))
+        );
+        // slashes are only allowed in the path
+        assert!(ResourceLocation::new("a/b:c").is_err());
+        assert!(ResourceLocation::new("a:b:c").is_err());
+    }
+    #[test]
+    fn non_ascii() {
+        assert_eq!(
+            ResourceLocation::new("é:x"),
+            Err(ResourceLocationError::InvalidNamespace("é:x".to_string()))
+        );
+        assert_eq!(
+            ResourceLocation::new("x:é"),
+            Err(ResourceLocationError::InvalidPath("x:é".to_string()))
+        );
+        assert!(ResourceLocation::new("ééé").is_err());
+    }
+    #[test]
+    fn mcbuf_invalid_resource_location() {
+        let mut buf = Vec::new();
+        "not valid!".to_string().write_into(&mut buf).unwrap();
+        assert!(ResourceLocation::read_from(&mut Cursor::new(&buf[..])).is_err());
+    }
+
     #[test]
     fn mcbuf_resource_location() {
         let mut buf = Vec::new();
@@ -1,6 +1,9 @@
 // TODO: have an azalea-inventory or azalea-container crate and put this there
 
+use crate::ResourceLocation;
 use azalea_buf::{BufReadError, McBuf, McBufReadable, McBufWritable};
+use azalea_nbt::Tag;
+use azalea_registry::Item;
 use std::io::{Cursor, Write};
 
 #[derive(Debug, Clone)]
@@ -14,7 +17,158 @@ pub struct SlotData {
     #[var]
     pub id: i32,
     pub count: u8,
-    pub nbt: azalea_nbt::Tag,
+    pub nbt: Tag,
+}
+
+impl SlotData {
+    /// Get the item in this slot, or `None` if the id isn't a valid item.
+    pub fn item(&self) -> Option<Item> {
+        Item::try_from(self.id as u32).ok()
+    }
+
+    /// Get the item's compound tag with the given name, if it has one.
+    fn tag(&self, name: &str) -> Option<&Tag> {
+        self.nbt.as_compound()?.get(name)
+    }
+
+    /// Get the enchantments on the item and their levels. For enchanted
+    /// books, this is the enchantments stored in the book.
+    ///
+    /// Very old versions used numeric ids in the `ench` tag, which are
+    /// skipped since we don't know which enchantments they are.
+    pub fn enchantments(&self) -> Vec<(ResourceLocation, u8)> {
+        let list = match self
+            .tag("Enchantments")
+            .or_else(|| self.tag("StoredEnchantments"))
+            .or_else(|| self.tag("ench"))
+            .and_then(Tag::as_list)
+        {
+            Some(list) => list,
+            None => return Vec::new(),
+        };
+        list.iter()
+            .filter_map(|enchantment| {
+                let enchantment = enchantment.as_compound()?;
+                let id = ResourceLocation::new(enchantment.get("id")?.as_string()?).ok()?;
+                let level = match enchantment.get("lvl")? {
+                    Tag::Byte(level) => *level as i64,
+                    Tag::Short(level) => *level as i64,
+                    Tag::Int(level) => *level as i64,
+                    _ => return None,
+                };
+                Some((id, level.clamp(0, u8::MAX as i64) as u8))
+            })
+            .collect()
+    }
+
+    /// Get the level of an enchantment on the item, or 0 if it doesn't have
+    /// it.
+    pub fn enchantment_level(&self, enchantment: &ResourceLocation) -> u8 {
+        self.enchantments()
+            .into_iter()
+            .find(|(id, _)| id == enchantment)
+            .map(|(_, level)| level)
+            .unwrap_or(0)
+    }
+
+    /// How much durability the item has lost. This is 0 for items that
+    /// don't have durability.
+    pub fn damage(&self) -> u32 {
+        match self.tag("Damage") {
+            Some(Tag::Int(damage)) => (*damage).max(0) as u32,
+            _ => 0,
+        }
+    }
+
+    /// Whether the item never loses durability.
+    pub fn is_unbreakable(&self) -> bool {
+        matches!(self.tag("Unbreakable"), Some(Tag::Byte(b)) if *b != 0)
+    }
+
+    /// How much durability the item has when it's new, or `None` if the
+    /// item can't be damaged.
+    pub fn max_damage(&self) -> Option<u32> {
+        if self.is_unbreakable() {
+            return None;
+        }
+        max_damage(self.item()?)
+    }
+
+    /// How many more times the item can be used before it breaks, or `None`
+    /// if the item can't be damaged.
+    pub fn durability(&self) -> Option<u32> {
+        Some(self.max_damage()?.saturating_sub(self.damage()))
+    }
+}
+
+/// How much durability an item has when it's new, or `None` if it doesn't
+/// lose durability.
+pub fn max_damage(item: Item) -> Option<u32> {
+    let max_damage = match item {
+        Item::WoodenSword
+        | Item::WoodenShovel
+        | Item::WoodenPickaxe
+        | Item::WoodenAxe
+        | Item::WoodenHoe => 59,
+        Item::StoneSword
+        | Item::StoneShovel
+        | Item::StonePickaxe
+        | Item::StoneAxe
+        | Item::StoneHoe => 131,
+        Item::IronSword | Item::IronShovel | Item::IronPickaxe | Item::IronAxe | Item::IronHoe => {
+            250
+        }
+        Item::GoldenSword
+        | Item::GoldenShovel
+        | Item::GoldenPickaxe
+        | Item::GoldenAxe
+        | Item::GoldenHoe => 32,
+        Item::DiamondSword
+        | Item::DiamondShovel
+        | Item::DiamondPickaxe
+        | Item::DiamondAxe
+        | Item::DiamondHoe => 1561,
+        Item::NetheriteSword
+        | Item::NetheriteShovel
+        | Item::NetheritePickaxe
+        | Item::NetheriteAxe
+        | Item::NetheriteHoe => 2031,
+
+        Item::LeatherHelmet => 55,
+        Item::LeatherChestplate => 80,
+        Item::LeatherLeggings => 75,
+        Item::LeatherBoots => 65,
+        Item::ChainmailHelmet | Item::IronHelmet => 165,
+        Item::ChainmailChestplate | Item::IronChestplate => 240,
+        Item::ChainmailLeggings | Item::IronLeggings => 225,
+        Item::ChainmailBoots | Item::IronBoots => 195,
+        Item::GoldenHelmet => 77,
+        Item::GoldenChestplate => 112,
+        Item::GoldenLeggings => 105,
+        Item::GoldenBoots => 91,
+        Item::DiamondHelmet => 363,
+        Item::DiamondChestplate => 528,
+        Item::DiamondLeggings => 495,
+        Item::DiamondBoots => 429,
+        Item::NetheriteHelmet => 407,
+        Item::NetheriteChestplate => 592,
+        Item::NetheriteLeggings => 555,
+        Item::NetheriteBoots => 481,
+        Item::TurtleHelmet => 275,
+
+        Item::Bow => 384,
+        Item::Crossbow => 465,
+        Item::Trident => 250,
+        Item::Shield => 336,
+        Item::FishingRod => 64,
+        Item::FlintAndSteel => 64,
+        Item::Shears => 238,
+        Item::CarrotOnAStick => 25,
+        Item::WarpedFungusOnAStick => 100,
+        Item::Elytra => 432,
+        _ => return None,
+    };
+    Some(max_damage)
 }
 
 impl McBufReadable for Slot {
@@ -31,10 +185,79 @@ impl McBufReadable for Slot {
 impl McBufWritable for Slot {
     fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
         match self {
-            Slot::Empty => 0u8.write_into(buf)?,
-            Slot::Present(i) => i.write_into(buf)?,
+            Slot::Empty => false.write_into(buf)?,
+            Slot::Present(i) => {
+                true.write_into(buf)?;
+                i.write_into(buf)?;
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(item: Item, nbt: Tag) -> SlotData {
+        SlotData {
+            id: item as i32,
+            count: 1,
+            nbt,
+        }
+    }
+
+    #[test]
+    fn test_enchantments() {
+        let pickaxe = slot(
+            Item::DiamondPickaxe,
+            Tag::from(vec![(
+                "Enchantments",
+                Tag::List(vec![
+                    Tag::from(vec![
+                        ("id", Tag::String("minecraft:efficiency".to_string())),
+                        ("lvl", Tag::Short(5)),
+                    ]),
+                    Tag::from(vec![
+                        ("id", Tag::String("minecraft:unbreaking".to_string())),
+                        ("lvl", Tag::Short(3)),
+                    ]),
+                ]),
+            )]),
+        );
+        let enchantments = pickaxe.enchantments();
+        assert_eq!(enchantments.len(), 2);
+        assert_eq!(enchantments[0].0.to_string(), "minecraft:efficiency");
+        assert_eq!(enchantments[0].1, 5);
+        assert_eq!(
+            pickaxe.enchantment_level(&ResourceLocation::new("unbreaking").unwrap()),
+            3
+        );
+        assert_eq!(
+            pickaxe.enchantment_level(&ResourceLocation::new("mending").unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_durability() {
+        let pickaxe = slot(
+            Item::IronPickaxe,
+            Tag::from(vec![("Damage", Tag::Int(200))]),
+        );
+        assert_eq!(pickaxe.damage(), 200);
+        assert_eq!(pickaxe.max_damage(), Some(250));
+        assert_eq!(pickaxe.durability(), Some(50));
+
+        let apple = slot(Item::Apple, Tag::End);
+        assert_eq!(apple.damage(), 0);
+        assert_eq!(apple.max_damage(), None);
+
+        let unbreakable = slot(
+            Item::IronPickaxe,
+            Tag::from(vec![("Unbreakable", Tag::Byte(1))]),
+        );
+        assert_eq!(unbreakable.max_damage(), None);
+    }
+}
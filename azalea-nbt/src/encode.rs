@@ -210,7 +210,12 @@ impl Tag {
 
 impl McBufWritable for Tag {
     fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
-        self.write(buf)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        match self {
+            // no nbt is sent as just the end tag, like in items without nbt
+            Tag::End => buf.write_all(&[0]),
+            _ => self
+                .write(buf)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string())),
+        }
     }
 }
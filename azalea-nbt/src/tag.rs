@@ -151,3 +151,21 @@ impl Tag {
         }
     }
 }
+
+/// Make a compound tag from a list of names and tags.
+///
+/// ```
+/// # use azalea_nbt::Tag;
+/// let tag = Tag::from(vec![("Damage", Tag::Int(5))]);
+/// assert_eq!(tag.as_compound().unwrap()["Damage"], Tag::Int(5));
+/// ```
+impl From<Vec<(&str, Tag)>> for Tag {
+    fn from(entries: Vec<(&str, Tag)>) -> Self {
+        Tag::Compound(
+            entries
+                .into_iter()
+                .map(|(name, tag)| (name.to_string(), tag))
+                .collect(),
+        )
+    }
+}
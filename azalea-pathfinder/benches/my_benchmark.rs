@@ -0,0 +1,141 @@
+use azalea_pathfinder::{ChangedEdge, DStarLite, Edge, Weight};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+const SIZE: i32 = 128;
+
+type Node = (i32, i32);
+type Walls = Arc<Mutex<HashSet<Node>>>;
+
+/// A maze of corridors that are three blocks wide. The walls between them
+/// have a gap at alternating ends, so the path has to snake back and forth
+/// through every corridor.
+fn maze_walls() -> Walls {
+    let mut walls = HashSet::new();
+    for x in (3..SIZE - 1).step_by(4) {
+        let gap = if (x / 4) % 2 == 0 { SIZE - 1 } else { 0 };
+        walls.extend((0..SIZE).filter(|&y| y != gap).map(|y| (x, y)));
+    }
+    Arc::new(Mutex::new(walls))
+}
+
+fn neighbors(walls: &Walls, node: &Node) -> Vec<Edge<Node, u32>> {
+    let walls = walls.lock().unwrap();
+    if walls.contains(node) {
+        return vec![];
+    }
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .map(|(x, y)| (node.0 + x, node.1 + y))
+        .filter(|n| (0..SIZE).contains(&n.0) && (0..SIZE).contains(&n.1))
+        .filter(|n| !walls.contains(n))
+        .map(|target| Edge { target, cost: 1 })
+        .collect()
+}
+
+fn maze_pathfinder<'a>(walls: &Walls) -> DStarLite<'a, Node, u32> {
+    let successor_walls = walls.clone();
+    let predecessor_walls = walls.clone();
+    DStarLite::new(
+        (0, 0),
+        (SIZE - 1, SIZE - 1),
+        Box::new(|a, b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1)),
+        Box::new(move |n| neighbors(&successor_walls, n)),
+        Box::new(move |n| neighbors(&predecessor_walls, n)),
+    )
+}
+
+/// Find where the planned path crosses the middle of the first corridor, so a
+/// wall there makes the path go around it.
+fn node_on_path() -> Node {
+    let walls = maze_walls();
+    let mut pathfinder = maze_pathfinder(&walls);
+    loop {
+        let node = pathfinder.try_next().unwrap().unwrap();
+        if node.1 == SIZE / 2 {
+            return node;
+        }
+    }
+}
+
+/// Put a wall at the node and return the edges that changed.
+fn add_wall(walls: &Walls, wall: Node) -> Vec<ChangedEdge<Node, u32>> {
+    let wall_neighbors: Vec<Node> = neighbors(walls, &wall)
+        .into_iter()
+        .map(|edge| edge.target)
+        .collect();
+    walls.lock().unwrap().insert(wall);
+    let mut changed_edges = Vec::new();
+    for neighbor in wall_neighbors {
+        changed_edges.push(ChangedEdge {
+            u: neighbor,
+            v: wall,
+            c_old: 1,
+            c_new: u32::INFINITY,
+        });
+        changed_edges.push(ChangedEdge {
+            u: wall,
+            v: neighbor,
+            c_old: 1,
+            c_new: u32::INFINITY,
+        });
+    }
+    changed_edges
+}
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("D* Lite maze");
+    let wall = node_on_path();
+
+    group.bench_function("Initial plan", |b| {
+        b.iter_batched(
+            || {
+                let walls = maze_walls();
+                (maze_pathfinder(&walls), walls)
+            },
+            |(mut pathfinder, _walls)| black_box(pathfinder.try_next().unwrap()),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // this should be much faster than the initial plan, since only the nodes
+    // around the new wall have to be updated
+    group.bench_function("Replan after one wall", |b| {
+        b.iter_batched(
+            || {
+                let walls = maze_walls();
+                let mut pathfinder = maze_pathfinder(&walls);
+                pathfinder.compute_shortest_path().unwrap();
+                let changed_edges = add_wall(&walls, wall);
+                pathfinder.updated_edge_costs.extend(changed_edges);
+                (pathfinder, walls)
+            },
+            |(mut pathfinder, _walls)| {
+                pathfinder.update_from_updated_edges();
+                black_box(pathfinder.try_next().unwrap())
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    // what we'd have to do without D* Lite
+    group.bench_function("Full plan after one wall", |b| {
+        b.iter_batched(
+            || {
+                let walls = maze_walls();
+                add_wall(&walls, wall);
+                (maze_pathfinder(&walls), walls)
+            },
+            |(mut pathfinder, _walls)| black_box(pathfinder.try_next().unwrap()),
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,536 @@
+//! An implementation of [D* Lite](http://idm-lab.org/bib/abstracts/papers/aaai02b.pdf),
+//! a pathfinding algorithm that can quickly find a new path when the costs of
+//! edges change.
+//!
+//! The search goes backwards from the goal, so when the start moves or an
+//! edge changes, most of the previous work can be reused.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+};
+use thiserror::Error;
+
+/// The cost of moving between two nodes.
+pub trait Weight: Copy + PartialOrd + Debug {
+    const ZERO: Self;
+    /// The cost of an edge that doesn't exist.
+    const INFINITY: Self;
+
+    /// Add two weights together. If either of them is infinite, the result is
+    /// also infinite.
+    fn add(self, other: Self) -> Self;
+}
+
+macro_rules! impl_weight_for_float {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                const ZERO: Self = 0.;
+                const INFINITY: Self = <$t>::INFINITY;
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+            }
+        )*
+    };
+}
+macro_rules! impl_weight_for_int {
+    ($($t:ty),*) => {
+        $(
+            impl Weight for $t {
+                const ZERO: Self = 0;
+                const INFINITY: Self = <$t>::MAX;
+
+                fn add(self, other: Self) -> Self {
+                    self.saturating_add(other)
+                }
+            }
+        )*
+    };
+}
+impl_weight_for_float!(f32, f64);
+impl_weight_for_int!(u32, u64, usize, i32, i64);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge<N, W> {
+    pub target: N,
+    pub cost: W,
+}
+
+/// An edge whose cost changed since the last time the path was calculated.
+/// If the edge was added or removed, the old or new cost is
+/// [`Weight::INFINITY`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangedEdge<N, W> {
+    /// The node that the edge starts from.
+    pub u: N,
+    /// The node that the edge goes to.
+    pub v: N,
+    pub c_old: W,
+    pub c_new: W,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("No path found")]
+pub struct NoPathError;
+
+pub type HeuristicFn<'a, N, W> = Box<dyn Fn(&N, &N) -> W + Send + 'a>;
+/// Get the edges that go out of a node.
+pub type SuccessorsFn<'a, N, W> = Box<dyn Fn(&N) -> Vec<Edge<N, W>> + Send + 'a>;
+/// Get the edges that go into a node. The target of each edge is the node it
+/// comes from.
+pub type PredecessorsFn<'a, N, W> = Box<dyn Fn(&N) -> Vec<Edge<N, W>> + Send + 'a>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Priority<W>(W, W);
+
+impl<W: Weight> PartialOrd for Priority<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.0.partial_cmp(&other.0)? {
+            Ordering::Equal => self.1.partial_cmp(&other.1),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+struct QueueEntry<N, W> {
+    priority: Priority<W>,
+    node: N,
+}
+
+impl<N, W: Weight> PartialEq for QueueEntry<N, W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<N, W: Weight> Eq for QueueEntry<N, W> {}
+impl<N, W: Weight> PartialOrd for QueueEntry<N, W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<N, W: Weight> Ord for QueueEntry<N, W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed so the BinaryHeap gives us the lowest priority first
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+pub struct DStarLite<'a, N, W> {
+    /// The node we're currently at. This gets moved forward by
+    /// [`DStarLite::try_next`].
+    pub start: N,
+    pub goal: N,
+    /// The edges that changed since the path was calculated. Push to this
+    /// and then call [`DStarLite::update_from_updated_edges`].
+    pub updated_edge_costs: Vec<ChangedEdge<N, W>>,
+    /// The maximum number of nodes that can be expanded every time the path
+    /// is calculated before giving up. This is useful for graphs that are
+    /// too big to search entirely.
+    pub max_expansions: Option<usize>,
+
+    start_last: N,
+    k_m: W,
+    g: HashMap<N, W>,
+    rhs: HashMap<N, W>,
+    queue: BinaryHeap<QueueEntry<N, W>>,
+    /// The current priority of every node in the queue. Entries in `queue`
+    /// that don't match this are outdated and get skipped.
+    queued: HashMap<N, Priority<W>>,
+
+    heuristic: HeuristicFn<'a, N, W>,
+    successors: SuccessorsFn<'a, N, W>,
+    predecessors: PredecessorsFn<'a, N, W>,
+}
+
+impl<'a, N, W> DStarLite<'a, N, W>
+where
+    N: Eq + Hash + Copy + Debug,
+    W: Weight,
+{
+    /// Create a new pathfinder. The heuristic has to never overestimate the
+    /// cost between two nodes.
+    pub fn new(
+        start: N,
+        goal: N,
+        heuristic: HeuristicFn<'a, N, W>,
+        successors: SuccessorsFn<'a, N, W>,
+        predecessors: PredecessorsFn<'a, N, W>,
+    ) -> Self {
+        let mut pathfinder = DStarLite {
+            start,
+            goal,
+            updated_edge_costs: Vec::new(),
+            max_expansions: None,
+            start_last: start,
+            k_m: W::ZERO,
+            g: HashMap::new(),
+            rhs: HashMap::new(),
+            queue: BinaryHeap::new(),
+            queued: HashMap::new(),
+            heuristic,
+            successors,
+            predecessors,
+        };
+        pathfinder.rhs.insert(goal, W::ZERO);
+        let priority = Priority((pathfinder.heuristic)(&start, &goal), W::ZERO);
+        pathfinder.insert(goal, priority);
+        pathfinder
+    }
+
+    fn g(&self, node: &N) -> W {
+        *self.g.get(node).unwrap_or(&W::INFINITY)
+    }
+
+    fn rhs(&self, node: &N) -> W {
+        *self.rhs.get(node).unwrap_or(&W::INFINITY)
+    }
+
+    fn calculate_key(&self, node: &N) -> Priority<W> {
+        let g_rhs = min(self.g(node), self.rhs(node));
+        Priority(
+            g_rhs.add((self.heuristic)(&self.start, node)).add(self.k_m),
+            g_rhs,
+        )
+    }
+
+    fn insert(&mut self, node: N, priority: Priority<W>) {
+        self.queued.insert(node, priority);
+        self.queue.push(QueueEntry { priority, node });
+    }
+
+    /// Get the node with the lowest priority in the queue without removing
+    /// it.
+    fn top(&mut self) -> Option<(N, Priority<W>)> {
+        while let Some(entry) = self.queue.peek() {
+            if self.queued.get(&entry.node) == Some(&entry.priority) {
+                return Some((entry.node, entry.priority));
+            }
+            // the node was removed or its priority changed
+            self.queue.pop();
+        }
+        None
+    }
+
+    fn update_vertex(&mut self, node: N) {
+        let consistent = self.g(&node) == self.rhs(&node);
+        if consistent {
+            self.queued.remove(&node);
+        } else {
+            let priority = self.calculate_key(&node);
+            self.insert(node, priority);
+        }
+    }
+
+    /// The lowest cost of going to the goal through one of the node's
+    /// successors.
+    fn min_successor_cost(&self, node: &N) -> W {
+        (self.successors)(node)
+            .into_iter()
+            .map(|edge| edge.cost.add(self.g(&edge.target)))
+            .fold(W::INFINITY, min)
+    }
+
+    /// Find the shortest path from the start to the goal, reusing as much of
+    /// the previous search as possible. You usually don't have to call this
+    /// yourself since [`DStarLite::try_next`] does it.
+    pub fn compute_shortest_path(&mut self) -> Result<(), NoPathError> {
+        let mut expansions = 0;
+        while let Some((u, k_old)) = self.top() {
+            let start_key = self.calculate_key(&self.start);
+            if !(k_old < start_key || self.rhs(&self.start) > self.g(&self.start)) {
+                break;
+            }
+            if let Some(max_expansions) = self.max_expansions {
+                if expansions >= max_expansions {
+                    return Err(NoPathError);
+                }
+            }
+            expansions += 1;
+
+            let k_new = self.calculate_key(&u);
+            if k_old < k_new {
+                self.insert(u, k_new);
+            } else if self.g(&u) > self.rhs(&u) {
+                let g_u = self.rhs(&u);
+                self.g.insert(u, g_u);
+                self.queued.remove(&u);
+                for edge in (self.predecessors)(&u) {
+                    let s = edge.target;
+                    if s != self.goal {
+                        let rhs = min(self.rhs(&s), edge.cost.add(g_u));
+                        self.rhs.insert(s, rhs);
+                    }
+                    self.update_vertex(s);
+                }
+            } else {
+                let g_old = self.g(&u);
+                self.g.insert(u, W::INFINITY);
+                for edge in (self.predecessors)(&u) {
+                    let s = edge.target;
+                    if self.rhs(&s) == edge.cost.add(g_old) && s != self.goal {
+                        let rhs = self.min_successor_cost(&s);
+                        self.rhs.insert(s, rhs);
+                    }
+                    self.update_vertex(s);
+                }
+                if self.rhs(&u) == g_old && u != self.goal {
+                    let rhs = self.min_successor_cost(&u);
+                    self.rhs.insert(u, rhs);
+                }
+                self.update_vertex(u);
+            }
+        }
+
+        if self.rhs(&self.start) == W::INFINITY {
+            Err(NoPathError)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Move the start to the next node in the path and return it, or return
+    /// `None` if we're already at the goal.
+    ///
+    /// If an edge's cost went up without being added to
+    /// [`DStarLite::updated_edge_costs`], the costs we calculated are wrong
+    /// and following them could go back and forth between two nodes forever.
+    /// We return an error instead when that happens.
+    pub fn try_next(&mut self) -> Result<Option<N>, NoPathError> {
+        if self.start == self.goal {
+            return Ok(None);
+        }
+        self.compute_shortest_path()?;
+
+        let mut best: Option<(N, W)> = None;
+        for edge in (self.successors)(&self.start) {
+            let cost = edge.cost.add(self.g(&edge.target));
+            if best.map_or(true, |(_, best_cost)| cost < best_cost) {
+                best = Some((edge.target, cost));
+            }
+        }
+        match best {
+            // the best successor should always get us as close to the goal as
+            // we expected, otherwise the graph changed without us knowing
+            Some((_, cost)) if cost > self.rhs(&self.start) => Err(NoPathError),
+            Some((next, cost)) if cost != W::INFINITY => {
+                self.start = next;
+                Ok(Some(next))
+            }
+            _ => Err(NoPathError),
+        }
+    }
+
+    /// Update the path with the edges in
+    /// [`DStarLite::updated_edge_costs`]. The successors and predecessors
+    /// functions should already return the new costs.
+    pub fn update_from_updated_edges(&mut self) {
+        if self.updated_edge_costs.is_empty() {
+            return;
+        }
+        self.k_m = self
+            .k_m
+            .add((self.heuristic)(&self.start_last, &self.start));
+        self.start_last = self.start;
+
+        for edge in std::mem::take(&mut self.updated_edge_costs) {
+            let ChangedEdge { u, v, c_old, c_new } = edge;
+            if u != self.goal {
+                if c_old > c_new {
+                    let rhs = min(self.rhs(&u), c_new.add(self.g(&v)));
+                    self.rhs.insert(u, rhs);
+                } else if self.rhs(&u) == c_old.add(self.g(&v)) {
+                    let rhs = self.min_successor_cost(&u);
+                    self.rhs.insert(u, rhs);
+                }
+            }
+            self.update_vertex(u);
+        }
+    }
+}
+
+fn min<W: PartialOrd>(a: W, b: W) -> W {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex},
+    };
+
+    type Walls = Arc<Mutex<HashSet<(i32, i32)>>>;
+
+    /// A 10x10 grid where you can move in the four directions, except into
+    /// walls.
+    fn grid_pathfinder<'a>(
+        start: (i32, i32),
+        goal: (i32, i32),
+        walls: &Walls,
+    ) -> DStarLite<'a, (i32, i32), u32> {
+        fn neighbors(walls: &Walls, node: &(i32, i32)) -> Vec<Edge<(i32, i32), u32>> {
+            let walls = walls.lock().unwrap();
+            if walls.contains(node) {
+                return vec![];
+            }
+            [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .into_iter()
+                .map(|(x, y)| (node.0 + x, node.1 + y))
+                .filter(|n| (0..10).contains(&n.0) && (0..10).contains(&n.1))
+                .filter(|n| !walls.contains(n))
+                .map(|target| Edge { target, cost: 1 })
+                .collect()
+        }
+
+        let successor_walls = walls.clone();
+        let predecessor_walls = walls.clone();
+        DStarLite::new(
+            start,
+            goal,
+            Box::new(|a, b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1)),
+            Box::new(move |n| neighbors(&successor_walls, n)),
+            Box::new(move |n| neighbors(&predecessor_walls, n)),
+        )
+    }
+
+    fn walk(pathfinder: &mut DStarLite<(i32, i32), u32>) -> Result<Vec<(i32, i32)>, NoPathError> {
+        let mut path = vec![pathfinder.start];
+        while let Some(next) = pathfinder.try_next()? {
+            path.push(next);
+            assert!(path.len() < 100, "path is too long: {:?}", path);
+        }
+        Ok(path)
+    }
+
+    #[test]
+    fn test_straight_path() {
+        let walls = Walls::default();
+        let mut pathfinder = grid_pathfinder((0, 0), (0, 5), &walls);
+        let path = walk(&mut pathfinder).unwrap();
+        assert_eq!(path, (0..=5).map(|y| (0, y)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_path_around_wall() {
+        let walls = Walls::default();
+        // a wall from (0, 2) to (8, 2), so we have to go around it at x=9
+        walls.lock().unwrap().extend((0..9).map(|x| (x, 2)));
+        let mut pathfinder = grid_pathfinder((0, 0), (0, 4), &walls);
+        let path = walk(&mut pathfinder).unwrap();
+        assert_eq!(path.len(), 9 + 9 + 4 + 1);
+        assert!(path.contains(&(9, 2)));
+        assert_eq!(path.last(), Some(&(0, 4)));
+    }
+
+    #[test]
+    fn test_no_path() {
+        let walls = Walls::default();
+        walls.lock().unwrap().extend((0..10).map(|x| (x, 2)));
+        let mut pathfinder = grid_pathfinder((0, 0), (0, 4), &walls);
+        assert_eq!(pathfinder.try_next(), Err(NoPathError));
+    }
+
+    #[test]
+    fn test_already_at_goal() {
+        let walls = Walls::default();
+        let mut pathfinder = grid_pathfinder((3, 3), (3, 3), &walls);
+        assert_eq!(pathfinder.try_next(), Ok(None));
+    }
+
+    #[test]
+    fn test_replan_after_wall_added() {
+        let walls = Walls::default();
+        let mut pathfinder = grid_pathfinder((0, 0), (0, 5), &walls);
+        assert_eq!(pathfinder.try_next(), Ok(Some((0, 1))));
+
+        // block the straight path
+        walls.lock().unwrap().insert((0, 3));
+        for neighbor in [(0, 2), (0, 4), (1, 3)] {
+            pathfinder.updated_edge_costs.push(ChangedEdge {
+                u: neighbor,
+                v: (0, 3),
+                c_old: 1,
+                c_new: u32::INFINITY,
+            });
+            pathfinder.updated_edge_costs.push(ChangedEdge {
+                u: (0, 3),
+                v: neighbor,
+                c_old: 1,
+                c_new: u32::INFINITY,
+            });
+        }
+        pathfinder.update_from_updated_edges();
+
+        let path = walk(&mut pathfinder).unwrap();
+        assert!(!path.contains(&(0, 3)));
+        assert_eq!(path.first(), Some(&(0, 1)));
+        assert_eq!(path.last(), Some(&(0, 5)));
+        // going around the wall takes two extra steps
+        assert_eq!(path.len(), 7);
+    }
+
+    #[test]
+    fn test_replan_after_wall_removed() {
+        let walls = Walls::default();
+        walls.lock().unwrap().extend((0..9).map(|x| (x, 2)));
+        let mut pathfinder = grid_pathfinder((0, 1), (0, 4), &walls);
+        pathfinder.compute_shortest_path().unwrap();
+
+        // open a hole in the wall right in front of us
+        walls.lock().unwrap().remove(&(0, 2));
+        for neighbor in [(0, 1), (0, 3), (1, 2)] {
+            pathfinder.updated_edge_costs.push(ChangedEdge {
+                u: neighbor,
+                v: (0, 2),
+                c_old: u32::INFINITY,
+                c_new: 1,
+            });
+            pathfinder.updated_edge_costs.push(ChangedEdge {
+                u: (0, 2),
+                v: neighbor,
+                c_old: u32::INFINITY,
+                c_new: 1,
+            });
+        }
+        pathfinder.update_from_updated_edges();
+
+        let path = walk(&mut pathfinder).unwrap();
+        assert_eq!(path, vec![(0, 1), (0, 2), (0, 3), (0, 4)]);
+    }
+
+    #[test]
+    fn test_unreported_wall_doesnt_loop() {
+        let walls = Walls::default();
+        let mut pathfinder = grid_pathfinder((0, 0), (0, 5), &walls);
+        assert_eq!(pathfinder.try_next(), Ok(Some((0, 1))));
+
+        // block the path without telling the pathfinder
+        walls.lock().unwrap().insert((0, 2));
+        let mut steps = 0;
+        while pathfinder.try_next().is_ok() {
+            steps += 1;
+            assert!(steps < 100, "try_next is going back and forth");
+        }
+    }
+
+    #[test]
+    fn test_max_expansions() {
+        let walls = Walls::default();
+        let mut pathfinder = grid_pathfinder((0, 0), (9, 9), &walls);
+        pathfinder.max_expansions = Some(3);
+        assert_eq!(pathfinder.try_next(), Err(NoPathError));
+    }
+}
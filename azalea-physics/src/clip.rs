@@ -0,0 +1,242 @@
+//! Find the blocks that lines go through, like for finding the block we're
+//! looking at.
+
+use crate::collision::BlockWithShape;
+use azalea_core::{BlockHitResult, BlockPos, Vec3};
+use azalea_world::Dimension;
+
+/// Find the first block the line from `from` to `to` hits, checking each
+/// block it goes through in order.
+///
+/// Blocks are tested with their collision shape, so blocks without one (like
+/// grass and flowers) are passed through. Unloaded blocks are passed through
+/// too.
+pub fn clip(dimension: &Dimension, from: &Vec3, to: &Vec3) -> Option<BlockHitResult> {
+    traverse_blocks(from, to, |pos| {
+        let block_state = dimension.get_block_state(pos)?;
+        block_state.shape().clip(from, to, pos)
+    })
+}
+
+/// Whether there are no blocks with a collision shape in the way of the line
+/// from `from` to `to`. This is what vanilla uses to decide whether mobs can
+/// see their target.
+pub fn has_line_of_sight(dimension: &Dimension, from: &Vec3, to: &Vec3) -> bool {
+    clip(dimension, from, to).is_none()
+}
+
+/// Call `f` with every block position the line from `from` to `to` goes
+/// through, in order, until it returns something.
+pub fn traverse_blocks<T>(
+    from: &Vec3,
+    to: &Vec3,
+    mut f: impl FnMut(&BlockPos) -> Option<T>,
+) -> Option<T> {
+    if from == to {
+        return None;
+    }
+    // nudge the ends a tiny bit outward, so lines that start or end exactly
+    // on the edge of a block still include it
+    let lerp = |delta: f64, start: f64, end: f64| start + delta * (end - start);
+    let end = Vec3 {
+        x: lerp(-1e-7, to.x, from.x),
+        y: lerp(-1e-7, to.y, from.y),
+        z: lerp(-1e-7, to.z, from.z),
+    };
+    let start = Vec3 {
+        x: lerp(-1e-7, from.x, to.x),
+        y: lerp(-1e-7, from.y, to.y),
+        z: lerp(-1e-7, from.z, to.z),
+    };
+
+    let mut pos = BlockPos::from(&start);
+    if let Some(result) = f(&pos) {
+        return Some(result);
+    }
+
+    let delta = Vec3 {
+        x: end.x - start.x,
+        y: end.y - start.y,
+        z: end.z - start.z,
+    };
+    let step = |delta: f64| -> (i32, f64) {
+        let sign = if delta > 0. {
+            1
+        } else if delta < 0. {
+            -1
+        } else {
+            0
+        };
+        let t_delta = if sign == 0 {
+            f64::MAX
+        } else {
+            sign as f64 / delta
+        };
+        (sign, t_delta)
+    };
+    let (step_x, t_delta_x) = step(delta.x);
+    let (step_y, t_delta_y) = step(delta.y);
+    let (step_z, t_delta_z) = step(delta.z);
+
+    // how far along the line (from 0 to 1) we have to go to reach the next
+    // block on each axis
+    let t_max = |step: i32, t_delta: f64, start: f64| {
+        let frac = start - start.floor();
+        t_delta * if step > 0 { 1. - frac } else { frac }
+    };
+    let mut t_max_x = t_max(step_x, t_delta_x, start.x);
+    let mut t_max_y = t_max(step_y, t_delta_y, start.y);
+    let mut t_max_z = t_max(step_z, t_delta_z, start.z);
+
+    while t_max_x <= 1. || t_max_y <= 1. || t_max_z <= 1. {
+        if t_max_x < t_max_y {
+            if t_max_x < t_max_z {
+                pos.x += step_x;
+                t_max_x += t_delta_x;
+            } else {
+                pos.z += step_z;
+                t_max_z += t_delta_z;
+            }
+        } else if t_max_y < t_max_z {
+            pos.y += step_y;
+            t_max_y += t_delta_y;
+        } else {
+            pos.z += step_z;
+            t_max_z += t_delta_z;
+        }
+
+        if let Some(result) = f(&pos) {
+            return Some(result);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_block::BlockState;
+    use azalea_core::{ChunkPos, Direction};
+    use azalea_world::Chunk;
+
+    #[test]
+    fn test_traverse_blocks() {
+        let mut visited = Vec::new();
+        traverse_blocks::<()>(
+            &Vec3 {
+                x: 0.5,
+                y: 0.5,
+                z: 0.5,
+            },
+            &Vec3 {
+                x: 2.5,
+                y: 1.5,
+                z: 0.5,
+            },
+            |pos| {
+                visited.push(*pos);
+                None
+            },
+        );
+        assert_eq!(visited.first(), Some(&BlockPos::new(0, 0, 0)));
+        assert_eq!(visited.last(), Some(&BlockPos::new(2, 1, 0)));
+        // every block is next to the one before it
+        for pair in visited.windows(2) {
+            let distance = (pair[0].x - pair[1].x).abs()
+                + (pair[0].y - pair[1].y).abs()
+                + (pair[0].z - pair[1].z).abs();
+            assert_eq!(distance, 1);
+        }
+    }
+
+    #[test]
+    fn test_clip() {
+        let mut dimension = Dimension::default();
+        dimension
+            .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+            .unwrap();
+        dimension.set_block_state(&BlockPos::new(3, 64, 0), BlockState::Stone);
+        // slabs only fill the bottom half of the block
+        dimension.set_block_state(&BlockPos::new(1, 64, 5), BlockState::StoneSlab_BottomFalse);
+
+        let from = Vec3 {
+            x: 0.5,
+            y: 64.5,
+            z: 0.5,
+        };
+        let hit = clip(
+            &dimension,
+            &from,
+            &Vec3 {
+                x: 10.5,
+                y: 64.5,
+                z: 0.5,
+            },
+        )
+        .unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(3, 64, 0));
+        assert_eq!(hit.direction, Direction::West);
+        assert!((hit.location.x - 3.).abs() < 1e-6);
+
+        // looking over the slab
+        let over_slab = Vec3 {
+            x: 1.5,
+            y: 64.75,
+            z: 10.5,
+        };
+        let from = Vec3 {
+            x: 1.5,
+            y: 64.75,
+            z: 0.5,
+        };
+        assert!(clip(&dimension, &from, &over_slab).is_none());
+        // looking down at the slab
+        let hit = clip(
+            &dimension,
+            &Vec3 {
+                x: 1.5,
+                y: 66.,
+                z: 5.5,
+            },
+            &Vec3 {
+                x: 1.5,
+                y: 60.,
+                z: 5.5,
+            },
+        )
+        .unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(1, 64, 5));
+        assert_eq!(hit.direction, Direction::Up);
+        assert!((hit.location.y - 64.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_has_line_of_sight() {
+        let mut dimension = Dimension::default();
+        dimension
+            .set_chunk(&ChunkPos::new(0, 0), Some(Chunk::default()))
+            .unwrap();
+        dimension.set_block_state(&BlockPos::new(2, 64, 0), BlockState::Stone);
+        // grass doesn't have a collision shape so it can be seen through
+        dimension.set_block_state(&BlockPos::new(0, 64, 2), BlockState::Grass);
+
+        let from = Vec3 {
+            x: 0.5,
+            y: 64.5,
+            z: 0.5,
+        };
+        let behind_stone = Vec3 {
+            x: 4.5,
+            y: 64.5,
+            z: 0.5,
+        };
+        let behind_grass = Vec3 {
+            x: 0.5,
+            y: 64.5,
+            z: 4.5,
+        };
+        assert!(!has_line_of_sight(&dimension, &from, &behind_stone));
+        assert!(has_line_of_sight(&dimension, &from, &behind_grass));
+    }
+}
@@ -85,7 +85,7 @@ impl MovableEntity for EntityMut<'_> {
     /// Move an entity by a given delta, checking for collisions.
     fn move_colliding(
         &mut self,
-        _mover_type: &MoverType,
+        mover_type: &MoverType,
         movement: &Vec3,
     ) -> Result<(), MoveEntityError> {
         // TODO: do all these
@@ -107,7 +107,7 @@ impl MovableEntity for EntityMut<'_> {
         //     this.setDeltaMovement(Vec3.ZERO);
         // }
 
-        // movement = this.maybeBackOffFromEdge(movement, moverType);
+        let movement = &maybe_back_off_from_edge(self.dimension, self, mover_type, movement);
 
         let collide_result = { self.dimension.collide(movement, self) };
 
@@ -134,6 +134,11 @@ impl MovableEntity for EntityMut<'_> {
         let vertical_collision = movement.y != collide_result.y;
         let on_ground = vertical_collision && movement.y < 0.;
         self.on_ground = on_ground;
+        if on_ground {
+            self.fall_distance = 0.;
+        } else if collide_result.y < 0. {
+            self.fall_distance -= collide_result.y as f32;
+        }
 
         // TODO: minecraft checks for a "minor" horizontal collision here
 
@@ -158,6 +163,8 @@ impl MovableEntity for EntityMut<'_> {
 
         if vertical_collision {
             // blockBelow.updateEntityAfterFallOn(this.level, this);
+            // TODO: slime blocks and beds make entities bounce instead
+            self.delta.y = 0.;
         }
 
         if on_ground {
@@ -190,6 +197,89 @@ impl MovableEntity for EntityMut<'_> {
     }
 }
 
+/// How far down an entity can fall without it counting as walking off an
+/// edge while sneaking. This is the same as how high a player can step up.
+const MAX_UP_STEP: f64 = 0.6;
+
+/// How much the movement is shortened by every time we check whether it'd
+/// take a sneaking entity off an edge.
+const EDGE_BACK_OFF_STEP: f64 = 0.05;
+
+/// Shorten the horizontal movement of a sneaking entity on the ground so it
+/// doesn't walk off the edge of the block it's standing on.
+fn maybe_back_off_from_edge(
+    dimension: &Dimension,
+    entity: &EntityData,
+    mover_type: &MoverType,
+    movement: &Vec3,
+) -> Vec3 {
+    // moving up, like when jumping, never gets shortened
+    if !matches!(mover_type, MoverType::Own | MoverType::Player)
+        || entity.flying
+        || movement.y > 0.
+        || !entity.sneaking
+        || !is_above_ground(dimension, entity)
+    {
+        return *movement;
+    }
+
+    let no_collision = |x: f64, z: f64| {
+        dimension
+            .get_block_collisions(
+                Some(entity),
+                entity.bounding_box.move_relative(x, -MAX_UP_STEP, z),
+            )
+            .next()
+            .is_none()
+    };
+    let back_off = |distance: f64| {
+        if (-EDGE_BACK_OFF_STEP..EDGE_BACK_OFF_STEP).contains(&distance) {
+            0.
+        } else if distance > 0. {
+            distance - EDGE_BACK_OFF_STEP
+        } else {
+            distance + EDGE_BACK_OFF_STEP
+        }
+    };
+
+    let mut x = movement.x;
+    let mut z = movement.z;
+    while x != 0. && no_collision(x, 0.) {
+        x = back_off(x);
+    }
+    while z != 0. && no_collision(0., z) {
+        z = back_off(z);
+    }
+    while x != 0. && z != 0. && no_collision(x, z) {
+        x = back_off(x);
+        z = back_off(z);
+    }
+
+    Vec3 {
+        x,
+        y: movement.y,
+        z,
+    }
+}
+
+/// Whether the entity is on the ground or close enough above it that it
+/// could've stepped down.
+fn is_above_ground(dimension: &Dimension, entity: &EntityData) -> bool {
+    entity.on_ground
+        || (entity.fall_distance < MAX_UP_STEP as f32
+            && dimension
+                .get_block_collisions(
+                    Some(entity),
+                    entity.bounding_box.move_relative(
+                        0.,
+                        entity.fall_distance as f64 - MAX_UP_STEP,
+                        0.,
+                    ),
+                )
+                .next()
+                .is_some())
+}
+
 fn collide_bounding_box(
     entity: Option<&EntityData>,
     movement: &Vec3,
@@ -1,6 +1,8 @@
 use super::mergers::IndexMerger;
 use crate::collision::{BitSetDiscreteVoxelShape, DiscreteVoxelShape, AABB};
-use azalea_core::{binary_search, Axis, AxisCycle, EPSILON};
+use azalea_core::{
+    binary_search, Axis, AxisCycle, BlockHitResult, BlockPos, Direction, Vec3, EPSILON,
+};
 use std::{cmp, num::NonZeroU32};
 
 pub struct Shapes {}
@@ -345,6 +347,66 @@ impl VoxelShape {
         self.shape().is_empty()
     }
 
+    /// Get the boxes that make up the shape.
+    pub fn to_aabbs(&self) -> Vec<AABB> {
+        let mut aabbs = Vec::new();
+        self.for_all_boxes(|min_x, min_y, min_z, max_x, max_y, max_z| {
+            aabbs.push(AABB {
+                min_x,
+                min_y,
+                min_z,
+                max_x,
+                max_y,
+                max_z,
+            });
+        });
+        aabbs
+    }
+
+    /// Find where the line from `from` to `to` first hits this shape, if the
+    /// shape is the one for the block at `pos`.
+    pub fn clip(&self, from: &Vec3, to: &Vec3, pos: &BlockPos) -> Option<BlockHitResult> {
+        if self.is_empty() {
+            return None;
+        }
+        let delta = Vec3 {
+            x: to.x - from.x,
+            y: to.y - from.y,
+            z: to.z - from.z,
+        };
+        if delta.length_squared() < EPSILON {
+            return None;
+        }
+        // if we start inside the shape, we hit it right away
+        let start = *from + delta.scale(0.001);
+        if self.shape().is_full_wide(
+            self.find_index(Axis::X, start.x - pos.x as f64),
+            self.find_index(Axis::Y, start.y - pos.y as f64),
+            self.find_index(Axis::Z, start.z - pos.z as f64),
+        ) {
+            let nearest = Direction::ALL
+                .into_iter()
+                .max_by(|a, b| {
+                    let dot = |direction: &Direction| {
+                        let normal = direction.normal();
+                        normal.x as f64 * delta.x
+                            + normal.y as f64 * delta.y
+                            + normal.z as f64 * delta.z
+                    };
+                    dot(a).total_cmp(&dot(b))
+                })
+                .unwrap();
+            return Some(BlockHitResult {
+                location: start,
+                direction: nearest.opposite(),
+                block_pos: *pos,
+                miss: false,
+                inside: true,
+            });
+        }
+        AABB::default().clip_iterable(&self.to_aabbs(), from, to, pos)
+    }
+
     #[must_use]
     pub fn move_relative(&self, x: f64, y: f64, z: f64) -> VoxelShape {
         if self.shape().is_empty() {
@@ -359,6 +421,21 @@ impl VoxelShape {
         ))
     }
 
+    /// Rotate the shape clockwise (when looking down) around the center of
+    /// the block. See [`AABB::rotate_y`].
+    #[must_use]
+    pub fn rotate_y(&self, quarter_turns: u8) -> VoxelShape {
+        if quarter_turns % 4 == 0 || self.is_empty() {
+            return self.clone();
+        }
+
+        self.to_aabbs()
+            .into_iter()
+            .map(|aabb| VoxelShape::from(aabb.rotate_y(quarter_turns)))
+            .reduce(Shapes::or)
+            .unwrap_or_else(empty_shape)
+    }
+
     pub fn get(&self, axis: Axis, index: usize) -> f64 {
         // self.get_coords(axis)[index]
         match self {
@@ -619,7 +696,7 @@ impl CubeVoxelShape {
 
     fn find_index(&self, axis: Axis, coord: f64) -> i32 {
         let n = self.shape().size(axis);
-        (f64::clamp(coord * (n as f64), -1f64, n as f64)) as i32
+        f64::clamp((coord * (n as f64)).floor(), -1f64, n as f64) as i32
     }
 }
 
@@ -690,4 +767,28 @@ mod tests {
         let joined = Shapes::matches_anywhere(&shape, &shape2, |a, b| a && b);
         assert!(joined, "Shapes should intersect");
     }
+
+    #[test]
+    fn test_rotate_y() {
+        // the bottom half of a stair with the top half on the north side
+        let stair = Shapes::or(
+            box_shape(0., 0., 0., 1., 0.5, 1.),
+            box_shape(0., 0.5, 0., 1., 1., 0.5),
+        );
+        let east_stair = Shapes::or(
+            box_shape(0., 0., 0., 1., 0.5, 1.),
+            box_shape(0.5, 0.5, 0., 1., 1., 1.),
+        );
+        let rotated = stair.rotate_y(1);
+        assert!(
+            !Shapes::matches_anywhere(&rotated, &east_stair, |a, b| a != b),
+            "Rotated shape should be the same as the east stair"
+        );
+        assert!(!Shapes::matches_anywhere(
+            &stair.rotate_y(4),
+            &stair,
+            |a, b| a != b
+        ));
+        assert!(empty_shape().rotate_y(1).is_empty());
+    }
 }
@@ -0,0 +1,104 @@
+use azalea_block::{BlockState, LavaBlock, WaterBlock};
+use azalea_core::BlockPos;
+use azalea_world::{entity::EntityMut, Dimension};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FluidKind {
+    Water,
+    Lava,
+}
+
+/// Get the kind of fluid in a block state and its level (0 is a source block,
+/// 1-7 are flowing, and 8 or more is falling), or `None` if the block doesn't
+/// have a fluid.
+// TODO: waterlogged blocks also contain water
+pub fn fluid_at(state: BlockState) -> Option<(FluidKind, u32)> {
+    let state_id = state as u32;
+    let water_id = BlockState::from(WaterBlock::default()) as u32;
+    if (water_id..water_id + 16).contains(&state_id) {
+        return Some((FluidKind::Water, state_id - water_id));
+    }
+    let lava_id = BlockState::from(LavaBlock::default()) as u32;
+    if (lava_id..lava_id + 16).contains(&state_id) {
+        return Some((FluidKind::Lava, state_id - lava_id));
+    }
+    None
+}
+
+/// How high the fluid at the given position goes, from 0 to 1. This is 0 if
+/// the block doesn't have the fluid.
+pub fn fluid_height(dimension: &Dimension, pos: &BlockPos, kind: FluidKind) -> f64 {
+    let level = match dimension.get_block_state(pos).and_then(fluid_at) {
+        Some((fluid_kind, level)) if fluid_kind == kind => level,
+        _ => return 0.,
+    };
+
+    // fluids are always full if there's more of the same fluid above them
+    let above = BlockPos::new(pos.x, pos.y + 1, pos.z);
+    if let Some((above_kind, _)) = dimension.get_block_state(&above).and_then(fluid_at) {
+        if above_kind == kind {
+            return 1.;
+        }
+    }
+
+    let amount = if level == 0 || level >= 8 {
+        8
+    } else {
+        8 - level
+    };
+    amount as f64 / 9.
+}
+
+/// Update how deep the entity is in water and lava.
+pub fn update_fluid_heights(entity: &mut EntityMut) {
+    entity.water_height = submerged_height(entity, FluidKind::Water);
+    entity.lava_height = submerged_height(entity, FluidKind::Lava);
+}
+
+/// Get how deep the entity's bounding box goes into the given fluid.
+fn submerged_height(entity: &EntityMut, kind: FluidKind) -> f64 {
+    let aabb = entity.bounding_box.inflate(-0.001, -0.001, -0.001);
+
+    let min_x = aabb.min_x.floor() as i32;
+    let max_x = aabb.max_x.ceil() as i32;
+    let min_y = aabb.min_y.floor() as i32;
+    let max_y = aabb.max_y.ceil() as i32;
+    let min_z = aabb.min_z.floor() as i32;
+    let max_z = aabb.max_z.ceil() as i32;
+
+    let mut submerged_height = 0f64;
+    for x in min_x..max_x {
+        for y in min_y..max_y {
+            for z in min_z..max_z {
+                let height = fluid_height(entity.dimension, &BlockPos::new(x, y, z), kind);
+                if height == 0. {
+                    continue;
+                }
+                let fluid_top = y as f64 + height;
+                if fluid_top >= aabb.min_y {
+                    submerged_height = submerged_height.max(fluid_top - aabb.min_y);
+                }
+            }
+        }
+    }
+    submerged_height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fluid_at() {
+        assert_eq!(
+            fluid_at(BlockState::from(WaterBlock::default())),
+            Some((FluidKind::Water, 0))
+        );
+        assert_eq!(
+            fluid_at(BlockState::from(LavaBlock::default())),
+            Some((FluidKind::Lava, 0))
+        );
+        assert_eq!(fluid_at(BlockState::Stone), None);
+        assert_eq!(fluid_at(BlockState::Air), None);
+    }
+}
@@ -1,35 +1,44 @@
 #![feature(trait_alias)]
 #![feature(let_chains)]
 
+pub mod clip;
 pub mod collision;
+pub mod fluids;
+mod profile;
 
 use azalea_block::{Block, BlockState};
 use azalea_core::{BlockPos, Vec3};
 use azalea_world::entity::{EntityData, EntityMut};
 use collision::{MovableEntity, MoverType};
+pub use profile::PhysicsProfile;
 
 pub trait HasPhysics {
-    fn travel(&mut self, acceleration: &Vec3);
-    fn ai_step(&mut self);
+    fn travel(&mut self, acceleration: &Vec3, profile: &PhysicsProfile);
+    fn ai_step(&mut self, profile: &PhysicsProfile);
 
-    fn jump_from_ground(&mut self);
+    fn jump_from_ground(&mut self, profile: &PhysicsProfile);
 }
 
 impl HasPhysics for EntityMut<'_> {
     /// Move the entity with the given acceleration while handling friction,
     /// gravity, collisions, and some other stuff.
-    fn travel(&mut self, acceleration: &Vec3) {
+    fn travel(&mut self, acceleration: &Vec3, profile: &PhysicsProfile) {
         // if !self.is_effective_ai() && !self.is_controlled_by_local_instance() {
         //     // this.calculateEntityAnimation(this, this instanceof FlyingAnimal);
         //     return;
         // }
 
-        let gravity: f64 = 0.08;
-
         // TODO: slow falling effect
-        // let is_falling = self.delta.y <= 0.;
+        let is_falling = self.delta.y <= 0.;
 
-        // TODO: fluids
+        if self.is_in_water() {
+            travel_in_water(self, acceleration, profile, is_falling);
+            return;
+        }
+        if self.is_in_lava() {
+            travel_in_lava(self, acceleration, profile, is_falling);
+            return;
+        }
 
         // TODO: elytra
 
@@ -43,16 +52,20 @@ impl HasPhysics for EntityMut<'_> {
         let block_friction = block_below.behavior().friction;
 
         let inertia = if self.on_ground {
-            block_friction * 0.91
+            block_friction * profile.inertia
         } else {
-            0.91
+            profile.inertia
         };
 
         // this applies the current delta
-        let mut movement =
-            handle_relative_friction_and_calculate_movement(self, acceleration, block_friction);
+        let mut movement = handle_relative_friction_and_calculate_movement(
+            self,
+            acceleration,
+            block_friction,
+            profile,
+        );
 
-        movement.y -= gravity;
+        movement.y -= profile.gravity;
 
         // if (this.shouldDiscardFriction()) {
         //     this.setDeltaMovement(movement.x, yMovement, movement.z);
@@ -66,7 +79,7 @@ impl HasPhysics for EntityMut<'_> {
         } else {
             self.delta = Vec3 {
                 x: movement.x * inertia as f64,
-                y: movement.y * 0.98f64,
+                y: movement.y * profile.vertical_drag,
                 z: movement.z * inertia as f64,
             };
         }
@@ -74,9 +87,12 @@ impl HasPhysics for EntityMut<'_> {
 
     /// applies air resistance, calls self.travel(), and some other random
     /// stuff.
-    fn ai_step(&mut self) {
+    fn ai_step(&mut self, profile: &PhysicsProfile) {
         // vanilla does movement interpolation here, doesn't really matter much for a bot though
 
+        // vanilla does this in baseTick, which happens right before aiStep
+        fluids::update_fluid_heights(self);
+
         if self.delta.x.abs() < 0.003 {
             self.delta.x = 0.;
         }
@@ -87,29 +103,58 @@ impl HasPhysics for EntityMut<'_> {
             self.delta.z = 0.;
         }
 
-        if self.jumping {
-            // TODO: jumping in liquids and jump delay
-
-            if self.on_ground {
-                self.jump_from_ground();
+        if self.flying {
+            // flying up and down is done here instead of jumping
+            let vertical_input = match (self.jumping, self.sneaking) {
+                (true, false) => 1.,
+                (false, true) => -1.,
+                _ => 0.,
+            };
+            self.delta.y += vertical_input * profile.flying_speed as f64 * 3.;
+        } else if self.jumping {
+            // TODO: jump delay
+
+            let fluid_height = if self.is_in_lava() {
+                self.lava_height
+            } else {
+                self.water_height
+            };
+            let in_water = self.is_in_water() && fluid_height > 0.;
+            let deep_in_fluid = !self.on_ground || fluid_height > profile.fluid_jump_threshold;
+
+            if (in_water || self.is_in_lava()) && deep_in_fluid {
+                // swim up
+                self.delta.y += profile.swim_up_speed;
+            } else if self.on_ground || (in_water && fluid_height <= profile.fluid_jump_threshold) {
+                self.jump_from_ground(profile);
             }
         }
 
-        self.xxa *= 0.98;
-        self.zza *= 0.98;
-
-        self.travel(&Vec3 {
-            x: self.xxa as f64,
-            y: self.yya as f64,
-            z: self.zza as f64,
-        });
+        self.xxa *= profile.input_drag;
+        self.zza *= profile.input_drag;
+
+        // flying keeps the vertical velocity from before gravity, but slows it
+        // down faster
+        let flying_delta_y = self.flying.then_some(self.delta.y);
+        self.travel(
+            &Vec3 {
+                x: self.xxa as f64,
+                y: self.yya as f64,
+                z: self.zza as f64,
+            },
+            profile,
+        );
+        if let Some(delta_y) = flying_delta_y {
+            self.delta.y = delta_y * 0.6;
+            self.fall_distance = 0.;
+        }
         // freezing
         // pushEntities
         // drowning damage
     }
 
-    fn jump_from_ground(&mut self) {
-        let jump_power: f64 = jump_power(self) as f64 + jump_boost_power(self);
+    fn jump_from_ground(&mut self, profile: &PhysicsProfile) {
+        let jump_power: f64 = jump_power(self, profile) as f64 + jump_boost_power(profile);
         let old_delta_movement = self.delta;
         self.delta = Vec3 {
             x: old_delta_movement.x,
@@ -130,6 +175,64 @@ impl HasPhysics for EntityMut<'_> {
     }
 }
 
+fn travel_in_water(
+    entity: &mut EntityMut,
+    acceleration: &Vec3,
+    profile: &PhysicsProfile,
+    is_falling: bool,
+) {
+    // TODO: sprinting and depth strider
+    let slow_down = profile.water_slow_down;
+
+    entity.move_relative(profile.fluid_speed, acceleration);
+    entity
+        .move_colliding(&MoverType::Own, &entity.delta.clone())
+        .expect("Entity should exist.");
+
+    // TODO: climbing out of water when there's a horizontal collision
+    entity.delta = entity.delta.multiply(slow_down, 0.8, slow_down);
+    entity.delta = fluid_falling_adjusted_movement(profile.gravity, is_falling, &entity.delta);
+}
+
+fn travel_in_lava(
+    entity: &mut EntityMut,
+    acceleration: &Vec3,
+    profile: &PhysicsProfile,
+    is_falling: bool,
+) {
+    entity.move_relative(profile.fluid_speed, acceleration);
+    entity
+        .move_colliding(&MoverType::Own, &entity.delta.clone())
+        .expect("Entity should exist.");
+
+    if entity.lava_height <= profile.fluid_jump_threshold {
+        entity.delta = entity.delta.multiply(0.5, 0.8, 0.5);
+        entity.delta = fluid_falling_adjusted_movement(profile.gravity, is_falling, &entity.delta);
+    } else {
+        entity.delta = entity.delta.scale(0.5);
+    }
+
+    entity.delta.y -= profile.gravity / 4.;
+}
+
+/// Fluids make entities sink slower than they would fall in air.
+fn fluid_falling_adjusted_movement(gravity: f64, is_falling: bool, delta: &Vec3) -> Vec3 {
+    // TODO: no gravity and sprinting
+    let y = if is_falling
+        && (delta.y - 0.005).abs() >= 0.003
+        && (delta.y - gravity / 16.).abs() < 0.003
+    {
+        -0.003
+    } else {
+        delta.y - gravity / 16.
+    };
+    Vec3 {
+        x: delta.x,
+        y,
+        z: delta.z,
+    }
+}
+
 fn get_block_pos_below_that_affects_movement(entity: &EntityData) -> BlockPos {
     BlockPos::new(
         entity.pos().x.floor() as i32,
@@ -143,8 +246,9 @@ fn handle_relative_friction_and_calculate_movement(
     entity: &mut EntityMut,
     acceleration: &Vec3,
     block_friction: f32,
+    profile: &PhysicsProfile,
 ) -> Vec3 {
-    entity.move_relative(get_speed(&*entity, block_friction), acceleration);
+    entity.move_relative(get_speed(&*entity, block_friction, profile), acceleration);
     // entity.delta = entity.handle_on_climbable(entity.delta);
     entity
         .move_colliding(&MoverType::Own, &entity.delta.clone())
@@ -161,14 +265,11 @@ fn handle_relative_friction_and_calculate_movement(
 // private float getFrictionInfluencedSpeed(float friction) {
 //     return this.onGround ? this.getSpeed() * (0.21600002F / (friction * friction * friction)) : this.flyingSpeed;
 // }
-fn get_speed(entity: &EntityData, friction: f32) -> f32 {
-    // TODO: have speed & flying_speed fields in entity
+fn get_speed(entity: &EntityData, friction: f32, profile: &PhysicsProfile) -> f32 {
     if entity.on_ground {
-        let speed: f32 = 0.7;
-        speed * (0.216f32 / (friction * friction * friction))
+        profile.speed * (0.21600002f32 / (friction * friction * friction))
     } else {
-        // entity.flying_speed
-        0.02
+        profile.flying_speed
     }
 }
 
@@ -202,22 +303,12 @@ fn block_jump_factor(entity: &EntityMut) -> f32 {
 // public double getJumpBoostPower() {
 //     return this.hasEffect(MobEffects.JUMP) ? (double)(0.1F * (float)(this.getEffect(MobEffects.JUMP).getAmplifier() + 1)) : 0.0D;
 // }
-fn jump_power(entity: &EntityMut) -> f32 {
-    0.42 * block_jump_factor(entity)
+fn jump_power(entity: &EntityMut, profile: &PhysicsProfile) -> f32 {
+    profile.jump_power * block_jump_factor(entity)
 }
 
-fn jump_boost_power(_entity: &EntityMut) -> f64 {
-    // TODO: potion effects
-    // if let Some(effects) = entity.effects() {
-    //     if let Some(jump_effect) = effects.get(&Effect::Jump) {
-    //         0.1 * (jump_effect.amplifier + 1) as f32
-    //     } else {
-    //         0.
-    //     }
-    // } else {
-    //     0.
-    // }
-    0.
+fn jump_boost_power(profile: &PhysicsProfile) -> f64 {
+    profile.jump_boost
 }
 
 #[cfg(test)]
@@ -245,11 +336,11 @@ mod tests {
         let mut entity = dim.entity_mut(0).unwrap();
         // y should start at 70
         assert_eq!(entity.pos().y, 70.);
-        entity.ai_step();
+        entity.ai_step(&PhysicsProfile::default());
         // delta is applied before gravity, so the first tick only sets the delta
         assert_eq!(entity.pos().y, 70.);
         assert!(entity.delta.y < 0.);
-        entity.ai_step();
+        entity.ai_step(&PhysicsProfile::default());
         // the second tick applies the delta to the position, so now it should go down
         assert!(
             entity.pos().y < 70.,
@@ -257,6 +348,60 @@ mod tests {
             entity.pos().y
         );
     }
+    #[test]
+    fn test_flying() {
+        let mut dim = Dimension::default();
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.,
+                    y: 70.,
+                    z: 0.,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        entity.flying = true;
+        for _ in 0..10 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert_eq!(entity.pos().y, 70.);
+
+        entity.jumping = true;
+        for _ in 0..10 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert!(entity.pos().y > 70.);
+    }
+
+    #[test]
+    fn test_no_gravity_profile() {
+        let mut dim = Dimension::default();
+
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.,
+                    y: 70.,
+                    z: 0.,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        let profile = PhysicsProfile {
+            gravity: 0.,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            entity.ai_step(&profile);
+        }
+        assert_eq!(entity.pos().y, 70.);
+    }
+
     #[test]
     fn test_collision() {
         let mut dim = Dimension::default();
@@ -279,11 +424,11 @@ mod tests {
             "Block state should exist, if this fails that means the chunk wasn't loaded and the block didn't get placed"
         );
         let mut entity = dim.entity_mut(0).unwrap();
-        entity.ai_step();
+        entity.ai_step(&PhysicsProfile::default());
         // delta will change, but it won't move until next tick
         assert_eq!(entity.pos().y, 70.);
         assert!(entity.delta.y < 0.);
-        entity.ai_step();
+        entity.ai_step(&PhysicsProfile::default());
         // the second tick applies the delta to the position, but it also does collision
         assert_eq!(entity.pos().y, 70.);
     }
@@ -315,7 +460,7 @@ mod tests {
         let mut entity = dim.entity_mut(0).unwrap();
         // do a few steps so we fall on the slab
         for _ in 0..20 {
-            entity.ai_step();
+            entity.ai_step(&PhysicsProfile::default());
         }
         assert_eq!(entity.pos().y, 69.5);
     }
@@ -347,11 +492,74 @@ mod tests {
         let mut entity = dim.entity_mut(0).unwrap();
         // do a few steps so we fall on the slab
         for _ in 0..20 {
-            entity.ai_step();
+            entity.ai_step(&PhysicsProfile::default());
         }
         assert_eq!(entity.pos().y, 70.);
     }
 
+    #[test]
+    fn test_sink_slowly_in_water() {
+        let mut dim = Dimension::default();
+        dim.set_chunk(&ChunkPos { x: 0, z: 0 }, Some(Chunk::default()))
+            .unwrap();
+        for y in 60..70 {
+            dim.set_block_state(
+                &BlockPos { x: 0, y, z: 0 },
+                BlockState::from(azalea_block::WaterBlock::default()),
+            );
+        }
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.5,
+                    y: 65.,
+                    z: 0.5,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        for _ in 0..20 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert!(entity.is_in_water());
+        assert!(!entity.is_in_lava());
+        // falling 20 ticks in air would take us down way more than this
+        assert!(entity.pos().y > 63., "sank too fast: {}", entity.pos().y);
+        assert!(entity.pos().y < 65.);
+    }
+
+    #[test]
+    fn test_swim_up_in_water() {
+        let mut dim = Dimension::default();
+        dim.set_chunk(&ChunkPos { x: 0, z: 0 }, Some(Chunk::default()))
+            .unwrap();
+        for y in 60..70 {
+            dim.set_block_state(
+                &BlockPos { x: 0, y, z: 0 },
+                BlockState::from(azalea_block::WaterBlock::default()),
+            );
+        }
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.5,
+                    y: 65.,
+                    z: 0.5,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        entity.jumping = true;
+        for _ in 0..20 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert!(entity.pos().y > 65., "didn't swim up: {}", entity.pos().y);
+    }
+
     #[test]
     fn test_weird_wall_collision() {
         let mut dim = Dimension::default();
@@ -379,8 +587,171 @@ mod tests {
         let mut entity = dim.entity_mut(0).unwrap();
         // do a few steps so we fall on the slab
         for _ in 0..20 {
-            entity.ai_step();
+            entity.ai_step(&PhysicsProfile::default());
         }
         assert_eq!(entity.pos().y, 70.5);
     }
+
+    #[test]
+    fn test_sneaking_stops_at_edge() {
+        let mut dim = Dimension::default();
+        dim.set_chunk(&ChunkPos { x: 0, z: 0 }, Some(Chunk::default()))
+            .unwrap();
+        dim.set_block_state(&BlockPos { x: 0, y: 69, z: 0 }, BlockState::Stone);
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.5,
+                    y: 70.,
+                    z: 0.5,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        // land on the block first so we're on the ground
+        for _ in 0..2 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert!(entity.on_ground);
+
+        entity.sneaking = true;
+        entity
+            .move_colliding(
+                &MoverType::Own,
+                &Vec3 {
+                    x: 1.,
+                    y: 0.,
+                    z: 0.,
+                },
+            )
+            .unwrap();
+        // part of our hitbox can hang over the edge, but not all of it
+        assert!(entity.pos().x > 1., "didn't move: {}", entity.pos().x);
+        assert!(entity.pos().x < 1.3, "walked off: {}", entity.pos().x);
+        assert_eq!(entity.pos().y, 70.);
+    }
+
+    #[test]
+    fn test_sneak_jumping_off_edge() {
+        let mut dim = Dimension::default();
+        dim.set_chunk(&ChunkPos { x: 0, z: 0 }, Some(Chunk::default()))
+            .unwrap();
+        dim.set_block_state(&BlockPos { x: 0, y: 69, z: 0 }, BlockState::Stone);
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.5,
+                    y: 70.,
+                    z: 0.5,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        for _ in 0..2 {
+            entity.ai_step(&PhysicsProfile::default());
+        }
+        assert!(entity.on_ground);
+
+        entity.sneaking = true;
+        entity
+            .move_colliding(
+                &MoverType::Own,
+                &Vec3 {
+                    x: 1.,
+                    y: 0.42,
+                    z: 0.,
+                },
+            )
+            .unwrap();
+        // jumping isn't stopped at the edge
+        assert_eq!(entity.pos().x, 1.5);
+        assert_eq!(entity.pos().y, 70.42);
+    }
+
+    /// Where a player is after every tick in vanilla when it starts on a stone
+    /// floor at (0.5, 1, 0.5) facing south (but not on the ground yet), holds
+    /// forward for 20 ticks, and then lets go and jumps. The values are the
+    /// player's y and z.
+    const VANILLA_WALK_AND_JUMP: [(f64, f64); 35] = [
+        (1.0, 0.5195999999433756),
+        (1.0, 0.5570360003492534),
+        (1.0, 0.6891027650680628),
+        (1.0, 0.8592112303477826),
+        (1.0, 1.0500904665463389),
+        (1.0, 1.2523105449838488),
+        (1.0, 1.4607227240030558),
+        (1.0, 1.6725157903325687),
+        (1.0, 1.886154821347922),
+        (1.0, 2.1008017491988142),
+        (1.0, 2.3159989887858305),
+        (1.0, 2.5314966986156713),
+        (1.0, 2.74715846521715),
+        (1.0, 2.962909806826347),
+        (1.0, 3.178710056395439),
+        (1.0, 3.3945370097137357),
+        (1.0, 3.6103785432807913),
+        (1.0, 3.8262280376645945),
+        (1.0, 4.042081878654846),
+        (1.0, 4.257938092892495),
+        (1.4199999868869781, 4.37579559955571),
+        (1.7531999805212017, 4.440145805668271),
+        (2.0013359791121474, 4.498704494918353),
+        (2.1661092609382138, 4.55199290367169),
+        (2.249187078744681, 4.600485357034771),
+        (2.2522033402537236, 4.64461349086694),
+        (2.176759275064237, 4.68477009381152),
+        (2.0244240882136797, 4.721312603544236),
+        (1.7967356006686916, 4.754566288359373),
+        (1.4952008770059113, 4.784827142413262),
+        (1.1212968405391892, 4.812364520395922),
+        (1.0, 4.83742353508234),
+        (1.0, 4.860227239104178),
+        (1.0, 4.8726780629462985),
+        (1.0, 4.879476213553719),
+    ];
+
+    #[test]
+    fn test_walk_and_jump_matches_vanilla() {
+        let mut dim = Dimension::default();
+        dim.set_chunk(&ChunkPos { x: 0, z: 0 }, Some(Chunk::default()))
+            .unwrap();
+        for x in 0..16 {
+            for z in 0..16 {
+                dim.set_block_state(&BlockPos { x, y: 0, z }, BlockState::Stone);
+            }
+        }
+        dim.add_entity(
+            0,
+            EntityData::new(
+                Uuid::from_u128(0),
+                Vec3 {
+                    x: 0.5,
+                    y: 1.,
+                    z: 0.5,
+                },
+            ),
+        );
+        let mut entity = dim.entity_mut(0).unwrap();
+        let profile = PhysicsProfile::default();
+        for (tick, &(y, z)) in VANILLA_WALK_AND_JUMP.iter().enumerate() {
+            let tick = tick + 1;
+            // the client sets the input every tick, since it decays
+            entity.zza = if tick <= 20 { 1. } else { 0. };
+            entity.jumping = tick == 21;
+            entity.ai_step(&profile);
+            let pos = *entity.pos();
+            assert!(
+                (pos.x - 0.5).abs() < 1e-9 && (pos.y - y).abs() < 1e-9 && (pos.z - z).abs() < 1e-9,
+                "tick {tick}: expected (0.5, {y}, {z}), got {pos:?}"
+            );
+        }
+        // landing resets our vertical velocity, so standing still only has
+        // one tick of gravity
+        assert!(entity.on_ground);
+        assert_eq!(entity.delta.y, -0.08 * 0.9800000190734863);
+    }
 }
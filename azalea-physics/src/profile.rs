@@ -0,0 +1,63 @@
+/// The constants used when simulating how an entity moves. Velocities are in
+/// blocks per tick and accelerations are in blocks per tick per tick.
+///
+/// The default is the same as a player in vanilla.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhysicsProfile {
+    /// How much the entity's vertical velocity decreases every tick while
+    /// it's not in a fluid.
+    pub gravity: f64,
+    /// The vertical velocity is multiplied by this every tick while the
+    /// entity is not in a fluid, which is what makes it have a terminal
+    /// velocity.
+    pub vertical_drag: f64,
+    /// The horizontal velocity is multiplied by this every tick. When the
+    /// entity is on the ground, it's also multiplied by the friction of the
+    /// block below it.
+    pub inertia: f32,
+    /// The movement inputs (`xxa` and `zza`) are multiplied by this every
+    /// tick.
+    pub input_drag: f32,
+    /// How fast the entity accelerates when walking on a block with the
+    /// default friction.
+    pub speed: f32,
+    /// How fast the entity accelerates when it's in the air.
+    pub flying_speed: f32,
+    /// The vertical velocity the entity gets when it jumps, before the block's
+    /// jump factor is applied.
+    pub jump_power: f32,
+    /// Extra vertical velocity the entity gets when it jumps, which isn't
+    /// affected by the block's jump factor. This is what the jump boost
+    /// effect changes.
+    pub jump_boost: f64,
+    /// How fast the entity accelerates when it's in water or lava.
+    pub fluid_speed: f32,
+    /// The horizontal velocity is multiplied by this every tick while the
+    /// entity is in water.
+    pub water_slow_down: f64,
+    /// How much the vertical velocity increases every tick while the entity is
+    /// swimming up.
+    pub swim_up_speed: f64,
+    /// How deep the entity has to be in a fluid (in blocks) before jumping
+    /// makes it swim up instead of jumping normally.
+    pub fluid_jump_threshold: f64,
+}
+
+impl Default for PhysicsProfile {
+    fn default() -> Self {
+        Self {
+            gravity: 0.08,
+            vertical_drag: 0.9800000190734863,
+            inertia: 0.91,
+            input_drag: 0.98,
+            speed: 0.1,
+            flying_speed: 0.02,
+            jump_power: 0.42,
+            jump_boost: 0.,
+            fluid_speed: 0.02,
+            water_slow_down: 0.8,
+            swim_up_speed: 0.04,
+            fluid_jump_threshold: 0.4,
+        }
+    }
+}
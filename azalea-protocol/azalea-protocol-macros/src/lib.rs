@@ -36,6 +36,12 @@ fn as_packet_derive(input: TokenStream, state: proc_macro2::TokenStream) -> Toke
                 Ok(Self::read_from(buf)?.get())
             }
         }
+
+        impl From<#ident> for #state {
+            fn from(packet: #ident) -> Self {
+                packet.get()
+            }
+        }
     };
 
     contents.into()
@@ -112,8 +118,8 @@ impl Parse for PacketIdMap {
         // 0x0e: clientbound_change_difficulty_packet::ClientboundChangeDifficultyPacket,
 
         // 0x0e
-        while let Ok(packet_id) = input.parse::<LitInt>() {
-            let packet_id = packet_id.base10_parse::<u32>()?;
+        while let Ok(packet_id_lit) = input.parse::<LitInt>() {
+            let packet_id = packet_id_lit.base10_parse::<u32>()?;
             // :
             input.parse::<Token![:]>()?;
             // clientbound_change_difficulty_packet
@@ -123,6 +129,28 @@ impl Parse for PacketIdMap {
             // ClientboundChangeDifficultyPacket
             let name: Ident = input.parse()?;
 
+            // a duplicate id would make the second packet impossible to read
+            if let Some(existing) = packets.iter().find(|p: &&PacketIdPair| p.id == packet_id) {
+                return Err(syn::Error::new(
+                    packet_id_lit.span(),
+                    format!(
+                        "Packet id {:#04x} is used by both {} and {}",
+                        packet_id, existing.name, name
+                    ),
+                ));
+            }
+
+            // and listing a packet twice would make it ambiguous which id it has
+            if let Some(existing) = packets.iter().find(|p: &&PacketIdPair| p.name == name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "{} is listed with both {:#04x} and {:#04x}",
+                        name, existing.id, packet_id
+                    ),
+                ));
+            }
+
             packets.push(PacketIdPair {
                 id: packet_id,
                 module,
@@ -183,6 +211,22 @@ impl Parse for DeclareStatePackets {
         })
     }
 }
+/// Declare the packets for a protocol state. This is the only place packet
+/// ids are listed, and it generates the serverbound and clientbound enums
+/// along with reading (dispatching by id), writing, and getting the id of
+/// every packet, so adding a packet is a single line here.
+///
+/// ```ignore
+/// declare_state_packets!(
+///     GamePacket,
+///     Serverbound => {
+///         0x12: serverbound_keep_alive_packet::ServerboundKeepAlivePacket,
+///     },
+///     Clientbound => {
+///         0x20: clientbound_keep_alive_packet::ClientboundKeepAlivePacket,
+///     }
+/// );
+/// ```
 #[proc_macro]
 pub fn declare_state_packets(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeclareStatePackets);
@@ -373,3 +417,44 @@ fn variant_name_from(name: &syn::Ident) -> syn::Ident {
     }
     syn::Ident::new(&variant_name, name.span())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(input: &str) -> String {
+        syn::parse_str::<DeclareStatePackets>(input)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_duplicate_packet_id() {
+        assert_eq!(
+            parse_error(
+                "TestPacket,
+                Serverbound => {},
+                Clientbound => {
+                    0x00: clientbound_foo_packet::ClientboundFooPacket,
+                    0x00: clientbound_bar_packet::ClientboundBarPacket,
+                }"
+            ),
+            "Packet id 0x00 is used by both ClientboundFooPacket and ClientboundBarPacket"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_packet_name() {
+        assert_eq!(
+            parse_error(
+                "TestPacket,
+                Serverbound => {},
+                Clientbound => {
+                    0x00: clientbound_foo_packet::ClientboundFooPacket,
+                    0x01: clientbound_foo_packet::ClientboundFooPacket,
+                }"
+            ),
+            "ClientboundFooPacket is listed with both 0x00 and 0x01"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+use azalea_core::ResourceLocation;
+use azalea_protocol::{
+    compression::Zlib,
+    packets::game::{
+        serverbound_custom_payload_packet::ServerboundCustomPayloadPacket,
+        serverbound_move_player_pos_packet::ServerboundMovePlayerPosPacket, ServerboundGamePacket,
+    },
+    write::{write_packet, write_packet_with_buffer, WriteBuffer},
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use futures::executor::block_on;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// An allocator that counts how many times it's asked for memory, so we can
+/// check that reusing the buffer actually avoids allocating.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Print how many allocations writing the packets once makes.
+fn print_allocations(name: &str, mut write: impl FnMut()) {
+    // the first run can grow the buffers, which is fine
+    write();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    write();
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    println!("{name}: {} allocations", after - before);
+}
+
+fn bench(c: &mut Criterion) {
+    let small: ServerboundGamePacket = ServerboundMovePlayerPosPacket {
+        x: 1.,
+        y: 64.,
+        z: -1.,
+        on_ground: true,
+    }
+    .get();
+    // bigger than the compression threshold
+    let large: ServerboundGamePacket = ServerboundCustomPayloadPacket {
+        identifier: ResourceLocation::new("azalea:bench").unwrap(),
+        data: vec![1; 1024].into(),
+    }
+    .get();
+    let mut stream = Vec::with_capacity(1000 * 1024);
+
+    let mut write_small = |stream: &mut Vec<u8>| {
+        stream.clear();
+        block_on(async {
+            for _ in 0..1000 {
+                write_packet(&small, stream, Some(256), &mut None)
+                    .await
+                    .unwrap();
+            }
+        })
+    };
+    print_allocations("Write 1000 packets", || write_small(&mut stream));
+    c.bench_function("Write 1000 packets", |b| {
+        b.iter(|| write_small(&mut stream))
+    });
+
+    let mut buffer = WriteBuffer::default();
+    let mut write_small_with_buffer = |stream: &mut Vec<u8>| {
+        stream.clear();
+        block_on(async {
+            for _ in 0..1000 {
+                write_packet_with_buffer(&small, &mut buffer, stream, Some(256), &Zlib, &mut None)
+                    .await
+                    .unwrap();
+            }
+        })
+    };
+    print_allocations("Write 1000 packets with a reused buffer", || {
+        write_small_with_buffer(&mut stream)
+    });
+    c.bench_function("Write 1000 packets with a reused buffer", |b| {
+        b.iter(|| write_small_with_buffer(&mut stream))
+    });
+
+    let mut buffer = WriteBuffer::default();
+    let mut write_mixed_with_buffer = |stream: &mut Vec<u8>| {
+        stream.clear();
+        block_on(async {
+            for _ in 0..500 {
+                for packet in [&small, &large] {
+                    write_packet_with_buffer(
+                        packet,
+                        &mut buffer,
+                        stream,
+                        Some(256),
+                        &Zlib,
+                        &mut None,
+                    )
+                    .await
+                    .unwrap();
+                }
+            }
+        })
+    };
+    print_allocations(
+        "Write 1000 small and large packets with a reused buffer",
+        || write_mixed_with_buffer(&mut stream),
+    );
+    c.bench_function(
+        "Write 1000 small and large packets with a reused buffer",
+        |b| b.iter(|| write_mixed_with_buffer(&mut stream)),
+    );
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
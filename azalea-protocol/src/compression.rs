@@ -0,0 +1,139 @@
+//! The algorithms packets can be compressed with.
+//!
+//! The framing around compressed packets (the threshold and the data length
+//! before the packet) is handled by [`crate::read`] and [`crate::write`], so
+//! a [`Compressor`] only has to turn bytes into other bytes.
+
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+use std::io::{self, Read, Write};
+
+/// Something that can compress and decompress packets. Vanilla uses
+/// [`Zlib`], but other algorithms can be used for experimenting with servers
+/// or proxies that support them.
+pub trait Compressor: Send + Sync {
+    /// Compress the data of a packet, adding it to the end of `out`.
+    fn compress(&self, data: &[u8], out: &mut Vec<u8>) -> io::Result<()>;
+
+    /// Decompress the data of a packet. `uncompressed_length` is the length
+    /// the packet says it has, which can be used for allocating.
+    fn decompress(&self, data: &[u8], uncompressed_length: usize) -> io::Result<Vec<u8>>;
+}
+
+/// The zlib compression vanilla uses.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Zlib;
+
+impl Compressor for Zlib {
+    fn compress(&self, data: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        let mut encoder = ZlibEncoder::new(out, Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    fn decompress(&self, data: &[u8], uncompressed_length: usize) -> io::Result<Vec<u8>> {
+        let mut decompressed = Vec::with_capacity(uncompressed_length);
+        ZlibDecoder::new(data).read_to_end(&mut decompressed)?;
+        Ok(decompressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        packets::game::{
+            serverbound_keep_alive_packet::ServerboundKeepAlivePacket, ServerboundGamePacket,
+        },
+        read::{read_packet_with_max_length, DEFAULT_MAX_PACKET_LENGTH},
+        write::{write_packet_with_buffer, WriteBuffer},
+    };
+    use bytes::BytesMut;
+
+    /// A "compressor" that reverses the bytes, so we can tell it was used.
+    struct Reverse;
+
+    impl Compressor for Reverse {
+        fn compress(&self, data: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+            out.extend(data.iter().rev());
+            Ok(())
+        }
+
+        fn decompress(&self, data: &[u8], _uncompressed_length: usize) -> io::Result<Vec<u8>> {
+            Ok(data.iter().rev().copied().collect())
+        }
+    }
+
+    /// A compressor that always fails.
+    struct Broken;
+
+    impl Compressor for Broken {
+        fn compress(&self, _data: &[u8], _out: &mut Vec<u8>) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::Other, "broken"))
+        }
+
+        fn decompress(&self, _data: &[u8], _uncompressed_length: usize) -> io::Result<Vec<u8>> {
+            Err(io::Error::new(io::ErrorKind::Other, "broken"))
+        }
+    }
+
+    #[test]
+    fn test_zlib_round_trip() {
+        let data = b"hello hello hello hello".repeat(10);
+        let mut compressed = Vec::new();
+        Zlib.compress(&data, &mut compressed).unwrap();
+        assert!(compressed.len() < data.len());
+        assert_eq!(Zlib.decompress(&compressed, data.len()).unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_custom_compressor() {
+        let packet: ServerboundGamePacket = ServerboundKeepAlivePacket { id: u64::MAX }.into();
+        let mut written = Vec::new();
+        write_packet_with_buffer(
+            &packet,
+            &mut WriteBuffer::default(),
+            &mut written,
+            Some(1),
+            &Reverse,
+            &mut None,
+        )
+        .await
+        .unwrap();
+        // the packet length, the data length, then the reversed packet
+        assert_eq!(written[..3], [10, 9, 0xff]);
+        assert_eq!(written.last(), Some(&0x12));
+
+        let read = read_packet_with_max_length::<ServerboundGamePacket, _>(
+            &mut &written[..],
+            &mut BytesMut::new(),
+            Some(1),
+            &Reverse,
+            &mut None,
+            DEFAULT_MAX_PACKET_LENGTH,
+        )
+        .await
+        .unwrap();
+        match read {
+            ServerboundGamePacket::KeepAlive(p) => assert_eq!(p.id, u64::MAX),
+            p => panic!("Expected a keep alive packet, got {:?}", p),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compressor_error_is_returned() {
+        let packet: ServerboundGamePacket = ServerboundKeepAlivePacket { id: 1 }.into();
+        let mut written = Vec::new();
+        let result = write_packet_with_buffer(
+            &packet,
+            &mut WriteBuffer::default(),
+            &mut written,
+            Some(1),
+            &Broken,
+            &mut None,
+        )
+        .await;
+        assert_eq!(result.unwrap_err().to_string(), "broken");
+        assert!(written.is_empty());
+    }
+}
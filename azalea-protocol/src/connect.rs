@@ -1,75 +1,225 @@
 //! parse sending and receiving packets with a server.
 
+use crate::compression::{Compressor, Zlib};
 use crate::packets::game::{ClientboundGamePacket, ServerboundGamePacket};
 use crate::packets::handshake::{ClientboundHandshakePacket, ServerboundHandshakePacket};
 use crate::packets::login::{ClientboundLoginPacket, ServerboundLoginPacket};
 use crate::packets::status::{ClientboundStatusPacket, ServerboundStatusPacket};
 use crate::packets::ProtocolPacket;
-use crate::read::{read_packet, ReadPacketError};
-use crate::write::write_packet;
+use crate::read::{read_packet_with_max_length, ReadPacketError, DEFAULT_MAX_PACKET_LENGTH};
+use crate::stats::{ConnectionCounters, ConnectionStats, CountingStream};
+use crate::write::{write_packet_with_buffer, write_raw_packet_with_buffer, WriteBuffer};
 use crate::ServerIpAddress;
 use azalea_crypto::{Aes128CfbDec, Aes128CfbEnc};
 use bytes::BytesMut;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 
-pub struct ReadConnection<R: ProtocolPacket> {
-    pub read_stream: OwnedReadHalf,
+/// A stream we can read packets from that isn't necessarily a TCP stream,
+/// like an in-memory stream in tests.
+pub type BoxedReadStream = Box<dyn AsyncRead + Unpin + Send + Sync>;
+/// A stream we can write packets to that isn't necessarily a TCP stream, like
+/// an in-memory stream in tests.
+pub type BoxedWriteStream = Box<dyn AsyncWrite + Unpin + Send + Sync>;
+
+/// The reading half of a connection. `S` is the stream that's being read
+/// from, which is the read half of a TCP stream by default.
+pub struct ReadConnection<R: ProtocolPacket, S = OwnedReadHalf> {
+    pub read_stream: S,
     buffer: BytesMut,
     pub compression_threshold: Option<u32>,
     pub dec_cipher: Option<Aes128CfbDec>,
+    /// The algorithm compressed packets are decompressed with. This is
+    /// [`Zlib`] by default.
+    pub compressor: Arc<dyn Compressor>,
+    /// Packets that say they're longer than this many bytes make
+    /// [`ReadConnection::read`] return an error instead of being read. This
+    /// is [`DEFAULT_MAX_PACKET_LENGTH`] by default.
+    pub max_packet_length: usize,
+    counters: Arc<ConnectionCounters>,
     _reading: PhantomData<R>,
 }
 
-pub struct WriteConnection<W: ProtocolPacket> {
-    pub write_stream: OwnedWriteHalf,
+/// If this many bytes are waiting to be sent, they're flushed even if
+/// [`WriteConnection::auto_flush`] is off.
+const MAX_UNFLUSHED_LENGTH: usize = 32 * 1024;
+
+/// The writing half of a connection. `S` is the stream that's being written
+/// to, which is the write half of a TCP stream by default.
+///
+/// Packets are sent as soon as they're written by default. Turning off
+/// [`WriteConnection::auto_flush`] keeps them in a buffer until
+/// [`WriteConnection::flush`] is called (or the buffer gets too big), so
+/// several packets can be sent with one write to the stream.
+pub struct WriteConnection<W: ProtocolPacket, S = OwnedWriteHalf> {
+    pub write_stream: S,
+    /// Packets are encoded into this so we don't have to allocate a new
+    /// buffer for every packet.
+    buffer: WriteBuffer,
+    /// Packets that were written but haven't been sent to the stream yet.
+    unflushed: Vec<u8>,
+    /// Whether packets are flushed right after they're written.
+    pub auto_flush: bool,
     pub compression_threshold: Option<u32>,
+    /// The algorithm packets are compressed with. This is [`Zlib`] by
+    /// default.
+    pub compressor: Arc<dyn Compressor>,
     pub enc_cipher: Option<Aes128CfbEnc>,
+    counters: Arc<ConnectionCounters>,
     _writing: PhantomData<W>,
 }
 
-pub struct Connection<R: ProtocolPacket, W: ProtocolPacket> {
-    pub reader: ReadConnection<R>,
-    pub writer: WriteConnection<W>,
+/// A connection that reads `R` packets and writes `W` packets. `RS` and `WS`
+/// are the halves of the stream it uses, which are the halves of a
+/// [`TcpStream`] by default. Use [`Connection::from_stream`] to make a
+/// connection over other streams.
+///
+/// The protocol state is part of the type, so packets from the wrong state
+/// can't be read or written. Every connection starts as a
+/// [`HandshakeConnection`], which becomes a [`StatusConnection`] with
+/// [`HandshakeConnection::status`] or a [`LoginConnection`] with
+/// [`HandshakeConnection::login`], and a login connection becomes a
+/// [`GameConnection`] with [`LoginConnection::game`]. These take `self`, so
+/// the old state can't be used after switching. When you're done, call
+/// [`Connection::close`], or just drop the connection to close it without
+/// sending anything that's still buffered.
+pub struct Connection<R: ProtocolPacket, W: ProtocolPacket, RS = OwnedReadHalf, WS = OwnedWriteHalf>
+{
+    pub reader: ReadConnection<R, RS>,
+    pub writer: WriteConnection<W, WS>,
 }
 
-impl<R> ReadConnection<R>
+/// A connection that's in the handshake state, which is what every connection
+/// starts with.
+pub type HandshakeConnection<RS = OwnedReadHalf, WS = OwnedWriteHalf> =
+    Connection<ClientboundHandshakePacket, ServerboundHandshakePacket, RS, WS>;
+/// A connection that's in the status state, used for pinging servers.
+pub type StatusConnection<RS = OwnedReadHalf, WS = OwnedWriteHalf> =
+    Connection<ClientboundStatusPacket, ServerboundStatusPacket, RS, WS>;
+/// A connection that's logging in to a server. Call [`LoginConnection::game`]
+/// after receiving a `ClientboundGameProfilePacket`.
+pub type LoginConnection<RS = OwnedReadHalf, WS = OwnedWriteHalf> =
+    Connection<ClientboundLoginPacket, ServerboundLoginPacket, RS, WS>;
+/// A connection that's in the game, after logging in.
+pub type GameConnection<RS = OwnedReadHalf, WS = OwnedWriteHalf> =
+    Connection<ClientboundGamePacket, ServerboundGamePacket, RS, WS>;
+
+impl<R, S> ReadConnection<R, S>
 where
     R: ProtocolPacket + Debug,
+    S: AsyncRead + Unpin + Send + Sync,
 {
     pub async fn read(&mut self) -> Result<R, ReadPacketError> {
-        read_packet::<R, _>(
-            &mut self.read_stream,
+        let result = read_packet_with_max_length::<R, _>(
+            &mut CountingStream::new(&mut self.read_stream, &self.counters.bytes_read),
             &mut self.buffer,
             self.compression_threshold,
+            &*self.compressor,
             &mut self.dec_cipher,
+            self.max_packet_length,
         )
-        .await
+        .await;
+        match &result {
+            Ok(_) => {
+                self.counters.packets_read.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(ReadPacketError::IoError { .. } | ReadPacketError::ConnectionClosed) => {}
+            Err(_) => {
+                self.counters.decode_errors.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+
+    /// Get how much data has been sent and received over this connection.
+    pub fn stats(&self) -> ConnectionStats {
+        self.counters.snapshot()
     }
 }
-impl<W> WriteConnection<W>
+impl<W, S> WriteConnection<W, S>
 where
     W: ProtocolPacket + Debug,
+    S: AsyncWrite + Unpin + Send,
 {
     /// Write a packet to the server
     pub async fn write(&mut self, packet: W) -> std::io::Result<()> {
-        write_packet(
+        write_packet_with_buffer(
             &packet,
-            &mut self.write_stream,
+            &mut self.buffer,
+            &mut self.unflushed,
             self.compression_threshold,
+            &*self.compressor,
             &mut self.enc_cipher,
         )
-        .await
+        .await?;
+        self.packet_written().await
+    }
+
+    /// Write a packet with the given id and data to the server, bypassing
+    /// the packet types. Make sure the id is correct for the current state.
+    pub async fn write_raw(&mut self, id: u32, data: &[u8]) -> std::io::Result<()> {
+        write_raw_packet_with_buffer(
+            id,
+            data,
+            &mut self.buffer,
+            &mut self.unflushed,
+            self.compression_threshold,
+            &*self.compressor,
+            &mut self.enc_cipher,
+        )
+        .await?;
+        self.packet_written().await
+    }
+
+    async fn packet_written(&mut self) -> std::io::Result<()> {
+        self.counters
+            .packets_written
+            .fetch_add(1, Ordering::Relaxed);
+        if self.auto_flush || self.unflushed.len() >= MAX_UNFLUSHED_LENGTH {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Send the packets that haven't been sent yet because
+    /// [`WriteConnection::auto_flush`] is off, and flush the stream.
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        if !self.unflushed.is_empty() {
+            CountingStream::new(&mut self.write_stream, &self.counters.bytes_written)
+                .write_all(&self.unflushed)
+                .await?;
+            self.unflushed.clear();
+        }
+        self.write_stream.flush().await
+    }
+
+    /// Send any packets that are still buffered and shut down the writing
+    /// half of the stream, so the server sees the connection end.
+    pub async fn close(mut self) -> std::io::Result<()> {
+        self.flush().await?;
+        self.write_stream.shutdown().await
+    }
+
+    /// Get how much data has been sent and received over this connection.
+    pub fn stats(&self) -> ConnectionStats {
+        self.counters.snapshot()
     }
 }
 
-impl<R, W> Connection<R, W>
+impl<R, W, RS, WS> Connection<R, W, RS, WS>
 where
     R: ProtocolPacket + Debug,
     W: ProtocolPacket + Debug,
+    RS: AsyncRead + Unpin + Send + Sync,
+    WS: AsyncWrite + Unpin + Send,
 {
     pub async fn read(&mut self) -> Result<R, ReadPacketError> {
         self.reader.read().await
@@ -80,57 +230,205 @@ where
         self.writer.write(packet).await
     }
 
-    /// Split the reader and writer into two objects. This doesn't allocate.
-    pub fn into_split(self) -> (ReadConnection<R>, WriteConnection<W>) {
-        (self.reader, self.writer)
+    /// Send any packets that are waiting in the write buffer. See
+    /// [`WriteConnection::flush`].
+    pub async fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush().await
     }
-}
-
-#[derive(Error, Debug)]
-pub enum ConnectionError {
-    #[error("{0}")]
-    Io(#[from] std::io::Error),
-}
-
-impl Connection<ClientboundHandshakePacket, ServerboundHandshakePacket> {
-    pub async fn new(address: &ServerIpAddress) -> Result<Self, ConnectionError> {
-        let ip = address.ip;
-        let port = address.port;
 
-        let stream = TcpStream::connect(format!("{}:{}", ip, port)).await?;
+    /// Get the counters for how much data has been sent and received over
+    /// this connection. They keep updating after the connection is split or
+    /// changes states.
+    pub fn counters(&self) -> Arc<ConnectionCounters> {
+        self.reader.counters.clone()
+    }
 
-        // enable tcp_nodelay
-        stream.set_nodelay(true)?;
+    /// Send any packets that are still buffered and close the connection.
+    /// See [`WriteConnection::close`].
+    pub async fn close(self) -> std::io::Result<()> {
+        self.writer.close().await
+    }
 
-        let (read_stream, write_stream) = stream.into_split();
+    /// Split the reader and writer into two objects. This doesn't allocate.
+    pub fn into_split(self) -> (ReadConnection<R, RS>, WriteConnection<W, WS>) {
+        (self.reader, self.writer)
+    }
+}
 
-        Ok(Connection {
+impl<R, W, RS, WS> Connection<R, W, RS, WS>
+where
+    R: ProtocolPacket + Debug,
+    W: ProtocolPacket + Debug,
+{
+    /// Create a connection from the two halves of a stream that's already
+    /// connected. Nothing is sent, so this can start in any state.
+    pub fn wrap(read_stream: RS, write_stream: WS) -> Self {
+        let counters = Arc::new(ConnectionCounters::default());
+        let compressor: Arc<dyn Compressor> = Arc::new(Zlib);
+        Connection {
             reader: ReadConnection {
                 read_stream,
                 buffer: BytesMut::new(),
                 compression_threshold: None,
                 dec_cipher: None,
+                compressor: compressor.clone(),
+                max_packet_length: DEFAULT_MAX_PACKET_LENGTH,
+                counters: counters.clone(),
                 _reading: PhantomData,
             },
             writer: WriteConnection {
                 write_stream,
+                buffer: WriteBuffer::default(),
+                unflushed: Vec::new(),
+                auto_flush: true,
                 compression_threshold: None,
+                compressor,
                 enc_cipher: None,
+                counters,
                 _writing: PhantomData,
             },
-        })
+        }
     }
+}
 
-    pub fn login(self) -> Connection<ClientboundLoginPacket, ServerboundLoginPacket> {
+impl<R, W, RS, WS> Connection<R, W, RS, WS>
+where
+    R: ProtocolPacket + Debug,
+    W: ProtocolPacket + Debug,
+    RS: AsyncRead + Unpin + Send + Sync + 'static,
+    WS: AsyncWrite + Unpin + Send + Sync + 'static,
+{
+    /// Box the streams, so connections over different kinds of streams can
+    /// be stored in the same place.
+    pub fn boxed(self) -> Connection<R, W, BoxedReadStream, BoxedWriteStream> {
+        Connection {
+            reader: ReadConnection {
+                read_stream: Box::new(self.reader.read_stream),
+                buffer: self.reader.buffer,
+                compression_threshold: self.reader.compression_threshold,
+                compressor: self.reader.compressor,
+                dec_cipher: self.reader.dec_cipher,
+                max_packet_length: self.reader.max_packet_length,
+                counters: self.reader.counters,
+                _reading: PhantomData,
+            },
+            writer: WriteConnection {
+                write_stream: Box::new(self.writer.write_stream),
+                buffer: self.writer.buffer,
+                unflushed: self.writer.unflushed,
+                auto_flush: self.writer.auto_flush,
+                compression_threshold: self.writer.compression_threshold,
+                compressor: self.writer.compressor,
+                enc_cipher: self.writer.enc_cipher,
+                counters: self.writer.counters,
+                _writing: PhantomData,
+            },
+        }
+    }
+}
+
+impl<R, W, S> Connection<R, W, ReadHalf<S>, WriteHalf<S>>
+where
+    R: ProtocolPacket + Debug,
+    W: ProtocolPacket + Debug,
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Create a connection from any stream that's already connected, like a
+    /// TLS stream to a proxy or an in-memory stream. TCP streams should use
+    /// [`HandshakeConnection::new`] instead, since their halves can be used
+    /// without locking each other.
+    pub fn from_stream(stream: S) -> Self {
+        let (read_stream, write_stream) = tokio::io::split(stream);
+        Connection::wrap(read_stream, write_stream)
+    }
+
+    /// Get back the stream that was passed to [`Connection::from_stream`].
+    /// Anything that was read but not handled yet is lost.
+    pub fn into_stream(self) -> S {
+        self.reader.read_stream.unsplit(self.writer.write_stream)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ConnectionError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("Timed out after {0:?} while connecting")]
+    Timeout(Duration),
+}
+
+/// Options for the TCP socket used by [`HandshakeConnection::new_with_options`].
+#[derive(Clone, Debug)]
+pub struct ConnectOptions {
+    /// Whether to disable Nagle's algorithm so small packets are sent
+    /// immediately. This is on by default, since latency matters more than
+    /// bandwidth for things like PvP.
+    pub nodelay: bool,
+    /// How long to wait for the connection to be established before giving
+    /// up. There's no timeout by default other than the operating system's.
+    pub connect_timeout: Option<Duration>,
+    /// If this is set, TCP keepalive is enabled and probes are sent after the
+    /// connection has been idle for this long. It's off by default.
+    pub keepalive: Option<Duration>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            connect_timeout: None,
+            keepalive: None,
+        }
+    }
+}
+
+impl HandshakeConnection {
+    /// Connect to a server. Both IPv4 and IPv6 addresses work.
+    pub async fn new(address: &ServerIpAddress) -> Result<Self, ConnectionError> {
+        Self::new_with_options(address, &ConnectOptions::default()).await
+    }
+
+    /// Connect to a server, configuring the TCP socket with the given
+    /// [`ConnectOptions`].
+    pub async fn new_with_options(
+        address: &ServerIpAddress,
+        options: &ConnectOptions,
+    ) -> Result<Self, ConnectionError> {
+        let connect = TcpStream::connect(SocketAddr::from(address));
+        let stream = match options.connect_timeout {
+            Some(duration) => match tokio::time::timeout(duration, connect).await {
+                Ok(stream) => stream?,
+                Err(_) => return Err(ConnectionError::Timeout(duration)),
+            },
+            None => connect.await?,
+        };
+
+        stream.set_nodelay(options.nodelay)?;
+        if let Some(time) = options.keepalive {
+            let keepalive = socket2::TcpKeepalive::new().with_time(time);
+            socket2::SockRef::from(&stream).set_tcp_keepalive(&keepalive)?;
+        }
+
+        let (read_stream, write_stream) = stream.into_split();
+        Ok(Connection::wrap(read_stream, write_stream))
+    }
+}
+
+impl<RS, WS> HandshakeConnection<RS, WS> {
+    /// Switch to the login state. This should be done after sending a
+    /// `ClientIntentionPacket` with the login intention.
+    pub fn login(self) -> LoginConnection<RS, WS> {
         Connection::from(self)
     }
 
-    pub fn status(self) -> Connection<ClientboundStatusPacket, ServerboundStatusPacket> {
+    /// Switch to the status state. This should be done after sending a
+    /// `ClientIntentionPacket` with the status intention.
+    pub fn status(self) -> StatusConnection<RS, WS> {
         Connection::from(self)
     }
 }
 
-impl Connection<ClientboundLoginPacket, ServerboundLoginPacket> {
+impl<RS, WS> LoginConnection<RS, WS> {
     pub fn set_compression_threshold(&mut self, threshold: i32) {
         // if you pass a threshold of less than 0, compression is disabled
         if threshold >= 0 {
@@ -149,19 +447,22 @@ impl Connection<ClientboundLoginPacket, ServerboundLoginPacket> {
         self.reader.dec_cipher = Some(dec_cipher);
     }
 
-    pub fn game(self) -> Connection<ClientboundGamePacket, ServerboundGamePacket> {
+    /// Switch to the game state. This should be done after the server sends
+    /// a `ClientboundGameProfilePacket`, which has the profile the server
+    /// accepted.
+    pub fn game(self) -> GameConnection<RS, WS> {
         Connection::from(self)
     }
 }
 
 // rust doesn't let us implement From because allegedly it conflicts with
 // `core`'s "impl<T> From<T> for T" so we do this instead
-impl<R1, W1> Connection<R1, W1>
+impl<R1, W1, RS, WS> Connection<R1, W1, RS, WS>
 where
     R1: ProtocolPacket + Debug,
     W1: ProtocolPacket + Debug,
 {
-    fn from<R2, W2>(connection: Connection<R1, W1>) -> Connection<R2, W2>
+    fn from<R2, W2>(connection: Connection<R1, W1, RS, WS>) -> Connection<R2, W2, RS, WS>
     where
         R2: ProtocolPacket + Debug,
         W2: ProtocolPacket + Debug,
@@ -171,15 +472,225 @@ where
                 read_stream: connection.reader.read_stream,
                 buffer: connection.reader.buffer,
                 compression_threshold: connection.reader.compression_threshold,
+                compressor: connection.reader.compressor,
                 dec_cipher: connection.reader.dec_cipher,
+                max_packet_length: connection.reader.max_packet_length,
+                counters: connection.reader.counters,
                 _reading: PhantomData,
             },
             writer: WriteConnection {
                 compression_threshold: connection.writer.compression_threshold,
+                compressor: connection.writer.compressor,
                 write_stream: connection.writer.write_stream,
+                buffer: connection.writer.buffer,
+                unflushed: connection.writer.unflushed,
+                auto_flush: connection.writer.auto_flush,
                 enc_cipher: connection.writer.enc_cipher,
+                counters: connection.writer.counters,
                 _writing: PhantomData,
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::status::{
+        clientbound_pong_response_packet::ClientboundPongResponsePacket,
+        serverbound_ping_request_packet::ServerboundPingRequestPacket,
+    };
+    use crate::write::write_packet;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    async fn test_connect(ip: IpAddr) {
+        let listener = match TcpListener::bind((ip, 0)).await {
+            Ok(listener) => listener,
+            // the machine running the tests might not support this address family
+            Err(_) => return,
+        };
+        let port = listener.local_addr().unwrap().port();
+        let accept = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+        HandshakeConnection::new(&ServerIpAddress { ip, port })
+            .await
+            .unwrap();
+        let (_, peer) = accept.await.unwrap();
+        assert_eq!(peer.ip(), ip);
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_options() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let accept = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+        let options = ConnectOptions {
+            nodelay: false,
+            connect_timeout: Some(Duration::from_secs(5)),
+            keepalive: Some(Duration::from_secs(30)),
+        };
+        let conn = HandshakeConnection::new_with_options(
+            &ServerIpAddress {
+                ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                port,
+            },
+            &options,
+        )
+        .await
+        .unwrap();
+        accept.await.unwrap();
+
+        let socket = socket2::SockRef::from(conn.reader.read_stream.as_ref());
+        assert!(!socket.nodelay().unwrap());
+        assert!(socket.keepalive().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_connect_ipv4() {
+        test_connect(IpAddr::V4(Ipv4Addr::LOCALHOST)).await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_ipv6() {
+        test_connect(IpAddr::V6(Ipv6Addr::LOCALHOST)).await;
+    }
+
+    #[tokio::test]
+    async fn test_close() {
+        let (client_stream, server_stream) = tokio::io::duplex(64);
+        let mut client = StatusConnection::from_stream(client_stream);
+        let mut server =
+            Connection::<ServerboundStatusPacket, ClientboundStatusPacket, _, _>::from_stream(
+                server_stream,
+            );
+
+        client.writer.auto_flush = false;
+        client
+            .write(ServerboundPingRequestPacket { time: 5 }.get())
+            .await
+            .unwrap();
+        client.close().await.unwrap();
+
+        // the buffered packet is sent before the stream is shut down
+        assert!(matches!(
+            server.read().await.unwrap(),
+            ServerboundStatusPacket::PingRequest(_)
+        ));
+        assert!(server.read().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_from_stream() {
+        let (client_stream, server_stream) = tokio::io::duplex(64);
+        let mut client = StatusConnection::from_stream(client_stream);
+        let mut server =
+            Connection::<ServerboundStatusPacket, ClientboundStatusPacket, _, _>::from_stream(
+                server_stream,
+            );
+
+        client
+            .write(ServerboundPingRequestPacket { time: 5 }.get())
+            .await
+            .unwrap();
+        let p = match server.read().await.unwrap() {
+            ServerboundStatusPacket::PingRequest(p) => p,
+            p => panic!("Expected a ping request packet, got {:?}", p),
+        };
+        server
+            .write(ClientboundPongResponsePacket { time: p.time }.get())
+            .await
+            .unwrap();
+        let p = match client.read().await.unwrap() {
+            ClientboundStatusPacket::PongResponse(p) => p,
+            p => panic!("Expected a pong response packet, got {:?}", p),
+        };
+        assert_eq!(p.time, 5);
+
+        // the stream still works after taking it back out of the connection
+        let mut server_stream = server.into_stream();
+        server_stream.write_all(&[1]).await.unwrap();
+        let mut buf = [0];
+        client.into_stream().read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, [1]);
+    }
+
+    #[tokio::test]
+    async fn test_batched_writes() {
+        let (client_stream, server_stream) = tokio::io::duplex(1024);
+        let mut client = StatusConnection::from_stream(client_stream);
+        let mut server =
+            Connection::<ServerboundStatusPacket, ClientboundStatusPacket, _, _>::from_stream(
+                server_stream,
+            );
+
+        client.writer.auto_flush = false;
+        for time in 0..3 {
+            client
+                .write(ServerboundPingRequestPacket { time }.get())
+                .await
+                .unwrap();
+        }
+        // nothing was sent yet
+        assert_eq!(client.writer.stats().packets_written, 3);
+        assert_eq!(client.writer.stats().bytes_written, 0);
+
+        client.flush().await.unwrap();
+        assert_eq!(client.writer.stats().bytes_written, 3 * 10);
+        for time in 0..3 {
+            match server.read().await.unwrap() {
+                ServerboundStatusPacket::PingRequest(p) => assert_eq!(p.time, time),
+                p => panic!("Expected a ping request packet, got {:?}", p),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 10];
+            stream.read_exact(&mut buf).await.unwrap();
+            write_packet(
+                &ClientboundStatusPacket::from(ClientboundPongResponsePacket { time: 1 }),
+                &mut stream,
+                None,
+                &mut None,
+            )
+            .await
+            .unwrap();
+            // a packet with an id that doesn't exist
+            stream.write_all(&[1, 0x7f]).await.unwrap();
+        });
+
+        let conn = HandshakeConnection::new(&ServerIpAddress {
+            ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port,
+        })
+        .await
+        .unwrap();
+        let mut conn = conn.status();
+        conn.write(ServerboundPingRequestPacket { time: 1 }.get())
+            .await
+            .unwrap();
+        conn.read().await.unwrap();
+        assert!(conn.read().await.is_err());
+        server.await.unwrap();
+
+        assert_eq!(
+            conn.reader.stats(),
+            ConnectionStats {
+                packets_read: 1,
+                packets_written: 1,
+                // length + id + u64
+                bytes_read: 10 + 2,
+                bytes_written: 10,
+                decode_errors: 1,
+            }
+        );
+    }
+}
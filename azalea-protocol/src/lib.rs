@@ -4,15 +4,18 @@
 #![feature(error_generic_member_access)]
 #![feature(provide_any)]
 
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 
+pub mod compression;
 #[cfg(feature = "connecting")]
 pub mod connect;
 #[cfg(feature = "packets")]
 pub mod packets;
 pub mod read;
 pub mod resolver;
+#[cfg(feature = "connecting")]
+pub mod stats;
 pub mod write;
 
 #[derive(Debug)]
@@ -27,21 +30,87 @@ pub struct ServerIpAddress {
     pub port: u16,
 }
 
-// impl try_from for ServerAddress
+/// Split a string like `host`, `host:port`, or `[ipv6]:port` into the host
+/// and port. The port defaults to 25565.
+fn split_host_port(string: &str) -> Result<(&str, u16), String> {
+    if string.is_empty() {
+        return Err("Empty string".to_string());
+    }
+    let (host, port) = if let Some(rest) = string.strip_prefix('[') {
+        // [ipv6] or [ipv6]:port
+        let (host, rest) = rest.split_once(']').ok_or("Unclosed [")?;
+        if rest.is_empty() {
+            (host, None)
+        } else {
+            let port = rest.strip_prefix(':').ok_or("Expected : after ]")?;
+            (host, Some(port))
+        }
+    } else if string.matches(':').count() > 1 {
+        // an ipv6 address without brackets can't have a port
+        (string, None)
+    } else {
+        match string.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (string, None),
+        }
+    };
+    if host.is_empty() {
+        return Err("No host specified".to_string());
+    }
+    // default the port to 25565
+    let port = match port {
+        Some(port) => u16::from_str(port).map_err(|_| "Invalid port specified")?,
+        None => 25565,
+    };
+    Ok((host, port))
+}
+
+impl FromStr for ServerAddress {
+    type Err = String;
+
+    /// Convert a Minecraft server address (host:port, the port is optional) to
+    /// a ServerAddress. IPv6 addresses with a port have to be in brackets,
+    /// like `[::1]:25565`.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (host, port) = split_host_port(string)?;
+        Ok(ServerAddress {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
 impl<'a> TryFrom<&'a str> for ServerAddress {
     type Error = String;
 
-    /// Convert a Minecraft server address (host:port, the port is optional) to a ServerAddress
     fn try_from(string: &str) -> Result<Self, Self::Error> {
-        if string.is_empty() {
-            return Err("Empty string".to_string());
-        }
-        let mut parts = string.split(':');
-        let host = parts.next().ok_or("No host specified")?.to_string();
-        // default the port to 25565
-        let port = parts.next().unwrap_or("25565");
-        let port = u16::from_str(port).map_err(|_| "Invalid port specified")?;
-        Ok(ServerAddress { host, port })
+        string.parse()
+    }
+}
+
+impl ServerAddress {
+    /// Resolve the address into an IP address and port, following SRV
+    /// records. This is the same as [`resolver::resolve_address`].
+    pub async fn resolve(&self) -> Result<ServerIpAddress, resolver::ResolverError> {
+        resolver::resolve_address(self).await
+    }
+}
+
+impl From<&ServerIpAddress> for SocketAddr {
+    fn from(address: &ServerIpAddress) -> Self {
+        SocketAddr::new(address.ip, address.port)
+    }
+}
+
+impl FromStr for ServerIpAddress {
+    type Err = String;
+
+    /// Parse an IP address with an optional port, like `127.0.0.1:25565` or
+    /// `[::1]:25565`. Use [`ServerAddress`] if it might be a domain.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (ip, port) = split_host_port(string)?;
+        let ip = IpAddr::from_str(ip).map_err(|_| "Invalid IP address")?;
+        Ok(ServerIpAddress { ip, port })
     }
 }
 
@@ -57,6 +126,7 @@ pub async fn connect(address: ServerAddress) -> Result<(), Box<dyn std::error::E
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
     use crate::{
         packets::login::{
@@ -66,9 +136,49 @@ mod tests {
         read::read_packet,
         write::write_packet,
     };
+    use crate::{ServerAddress, ServerIpAddress};
     use bytes::BytesMut;
     use uuid::Uuid;
 
+    #[test]
+    fn test_parse_server_address() {
+        let address: ServerAddress = "mc.example.com".parse().unwrap();
+        assert_eq!(address.host, "mc.example.com");
+        assert_eq!(address.port, 25565);
+
+        let address: ServerAddress = "localhost:25566".parse().unwrap();
+        assert_eq!(address.host, "localhost");
+        assert_eq!(address.port, 25566);
+
+        let address: ServerAddress = "[::1]:25567".parse().unwrap();
+        assert_eq!(address.host, "::1");
+        assert_eq!(address.port, 25567);
+
+        let address: ServerAddress = "::1".parse().unwrap();
+        assert_eq!(address.host, "::1");
+        assert_eq!(address.port, 25565);
+
+        assert!("localhost:abc".parse::<ServerAddress>().is_err());
+        assert!("localhost:65536".parse::<ServerAddress>().is_err());
+        assert!("[::1".parse::<ServerAddress>().is_err());
+        assert!(":25565".parse::<ServerAddress>().is_err());
+        assert!("".parse::<ServerAddress>().is_err());
+    }
+
+    #[test]
+    fn test_parse_server_ip_address() {
+        let address: ServerIpAddress = "127.0.0.1".parse().unwrap();
+        assert_eq!(address.ip, IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert_eq!(address.port, 25565);
+
+        let address: ServerIpAddress = "[::1]:25566".parse().unwrap();
+        assert_eq!(address.ip, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(address.port, 25566);
+
+        assert!("mc.example.com".parse::<ServerIpAddress>().is_err());
+        assert!("127.0.0.1:-1".parse::<ServerIpAddress>().is_err());
+    }
+
     #[tokio::test]
     async fn test_hello_packet() {
         let packet = ServerboundHelloPacket {
@@ -128,4 +238,32 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_compressed_hello_packet() {
+        let packet = ServerboundHelloPacket {
+            username: "test".to_string(),
+            public_key: None,
+            profile_id: Some(Uuid::from_u128(0)),
+        }
+        .get();
+        let mut stream = Vec::new();
+        write_packet(&packet, &mut stream, Some(0), &mut None)
+            .await
+            .unwrap();
+        let mut stream = Cursor::new(stream);
+
+        let read = read_packet::<ServerboundLoginPacket, _>(
+            &mut stream,
+            &mut BytesMut::new(),
+            Some(0),
+            &mut None,
+        )
+        .await
+        .unwrap();
+        match read {
+            ServerboundLoginPacket::Hello(hello) => assert_eq!(hello.username, "test"),
+            _ => panic!("wrong packet"),
+        }
+    }
 }
@@ -26,13 +26,15 @@ pub struct ClientboundAddEntityPacket {
 
 impl From<&ClientboundAddEntityPacket> for EntityData {
     fn from(p: &ClientboundAddEntityPacket) -> Self {
-        Self::new(
+        let mut entity = Self::new(
             p.uuid,
             Vec3 {
                 x: p.x,
                 y: p.y,
                 z: p.z,
             },
-        )
+        );
+        entity.set_kind(p.entity_type);
+        entity
     }
 }
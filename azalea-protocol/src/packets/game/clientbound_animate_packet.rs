@@ -10,7 +10,7 @@ pub struct ClientboundAnimatePacket {
 
 // minecraft actually uses a u8 for this, but a varint still works and makes it
 // so i don't have to add a special handler
-#[derive(Clone, Debug, Copy, McBuf)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, McBuf)]
 pub enum AnimationAction {
     SwingMainHand = 0,
     Hurt = 1,
@@ -1,6 +1,4 @@
-use azalea_buf::{
-    BufReadError, McBuf, McBufReadable, McBufVarReadable, McBufVarWritable, McBufWritable,
-};
+use azalea_buf::{BufReadError, McBuf, McBufReadable, McBufWritable};
 use azalea_chat::component::Component;
 use azalea_protocol_macros::ClientboundGamePacket;
 use std::io::Cursor;
@@ -13,7 +11,7 @@ pub struct ClientboundBossEventPacket {
     pub operation: Operation,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, McBuf)]
 pub enum Operation {
     Add(AddOperation),
     Remove,
@@ -23,71 +21,21 @@ pub enum Operation {
     UpdateProperties(Properties),
 }
 
-impl McBufReadable for Operation {
-    fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
-        let operation_id = u32::var_read_from(buf)?;
-        Ok(match operation_id {
-            0 => Operation::Add(AddOperation::read_from(buf)?),
-            1 => Operation::Remove,
-            2 => Operation::UpdateProgress(f32::read_from(buf)?),
-            3 => Operation::UpdateName(Component::read_from(buf)?),
-            4 => Operation::UpdateStyle(Style::read_from(buf)?),
-            5 => Operation::UpdateProperties(Properties::read_from(buf)?),
-            _ => {
-                return Err(BufReadError::UnexpectedEnumVariant {
-                    id: operation_id as i32,
-                })
-            }
-        })
-    }
-}
-
-impl McBufWritable for Operation {
-    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
-        match self {
-            Operation::Add(add) => {
-                0u32.var_write_into(buf)?;
-                add.write_into(buf)?;
-            }
-            Operation::Remove => {
-                1u32.var_write_into(buf)?;
-            }
-            Operation::UpdateProgress(progress) => {
-                2u32.var_write_into(buf)?;
-                progress.write_into(buf)?;
-            }
-            Operation::UpdateName(name) => {
-                3u32.var_write_into(buf)?;
-                name.write_into(buf)?;
-            }
-            Operation::UpdateStyle(style) => {
-                4u32.var_write_into(buf)?;
-                style.write_into(buf)?;
-            }
-            Operation::UpdateProperties(properties) => {
-                5u32.var_write_into(buf)?;
-                properties.write_into(buf)?;
-            }
-        }
-        Ok(())
-    }
-}
-
 #[derive(Clone, Debug, McBuf)]
 pub struct AddOperation {
-    name: Component,
-    progress: f32,
-    style: Style,
-    properties: Properties,
+    pub name: Component,
+    pub progress: f32,
+    pub style: Style,
+    pub properties: Properties,
 }
 
 #[derive(Clone, Debug, McBuf)]
 pub struct Style {
-    color: BossBarColor,
-    overlay: BossBarOverlay,
+    pub color: BossBarColor,
+    pub overlay: BossBarOverlay,
 }
 
-#[derive(McBuf, Clone, Copy, Debug)]
+#[derive(McBuf, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BossBarColor {
     Pink = 0,
     Blue = 1,
@@ -98,7 +46,7 @@ pub enum BossBarColor {
     White = 6,
 }
 
-#[derive(McBuf, Clone, Copy, Debug)]
+#[derive(McBuf, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BossBarOverlay {
     Progress = 0,
     Notched6 = 1,
@@ -141,3 +89,24 @@ impl McBufWritable for Properties {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation() {
+        let mut buf = Vec::new();
+        Operation::UpdateProgress(0.5).write_into(&mut buf).unwrap();
+        assert_eq!(buf, vec![2, 0x3f, 0, 0, 0]);
+        assert!(matches!(
+            Operation::read_from(&mut Cursor::new(&buf)).unwrap(),
+            Operation::UpdateProgress(progress) if progress == 0.5
+        ));
+
+        assert!(matches!(
+            Operation::read_from(&mut Cursor::new(&[6])),
+            Err(BufReadError::UnexpectedEnumVariant { id: 6 })
+        ));
+    }
+}
@@ -1,32 +1,62 @@
-// use azalea_brigadier::context::StringRange;
-use azalea_buf::{
-    // BufReadError, McBuf, McBufReadable, McBufVarReadable, McBufVarWritable, McBufWritable,
-    BufReadError,
-    McBufReadable,
-    McBufWritable,
-};
+use azalea_buf::McBuf;
+use azalea_chat::component::Component;
 use azalea_protocol_macros::ClientboundGamePacket;
-use std::io::{Cursor, Write};
 
-#[derive(Clone, Debug, ClientboundGamePacket)]
+/// The server's answer to a
+/// [`ServerboundCommandSuggestionPacket`](super::serverbound_command_suggestion_packet::ServerboundCommandSuggestionPacket)
+/// with the same id.
+#[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundCommandSuggestionsPacket {
     #[var]
     pub id: u32,
-    // pub suggestions: Suggestions,
+    /// Where the text that the suggestions would replace starts in the
+    /// command we sent.
+    #[var]
+    pub start: u32,
+    /// How long the text that the suggestions would replace is.
+    #[var]
+    pub length: u32,
+    pub suggestions: Vec<CommandSuggestion>,
 }
 
-impl McBufReadable for ClientboundCommandSuggestionsPacket {
-    fn read_from(_buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
-        // let id = u32::var_read_from(buf)?;
-        // let start = u32::var_read_from(buf)? as usize;
-        // let length = u32::var_read_from(buf)? as usize;
-        // let stringrange = StringRange::between(start, start + length);
-        todo!("Suggestions aren't implemented in azalea-brigadier yet")
-    }
+#[derive(Clone, Debug, McBuf)]
+pub struct CommandSuggestion {
+    pub text: String,
+    pub tooltip: Option<Component>,
 }
 
-impl McBufWritable for ClientboundCommandSuggestionsPacket {
-    fn write_into(&self, _buf: &mut impl Write) -> Result<(), std::io::Error> {
-        todo!()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_buf::{McBufReadable, McBufWritable};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_suggestions() {
+        let packet = ClientboundCommandSuggestionsPacket {
+            id: 3,
+            start: 1,
+            length: 2,
+            suggestions: vec![
+                CommandSuggestion {
+                    text: "gamemode".to_string(),
+                    tooltip: None,
+                },
+                CommandSuggestion {
+                    text: "give".to_string(),
+                    tooltip: Some("Give an item".to_string().into()),
+                },
+            ],
+        };
+        let mut buf = Vec::new();
+        packet.write_into(&mut buf).unwrap();
+        let read = ClientboundCommandSuggestionsPacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(read.id, 3);
+        assert_eq!(read.suggestions.len(), 2);
+        assert_eq!(read.suggestions[1].text, "give");
+        assert_eq!(
+            read.suggestions[1].tooltip.as_ref().unwrap().to_string(),
+            "Give an item"
+        );
     }
 }
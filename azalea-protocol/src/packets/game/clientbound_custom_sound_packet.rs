@@ -1,8 +1,8 @@
 use azalea_buf::McBuf;
-use azalea_core::ResourceLocation;
+use azalea_core::{ResourceLocation, Vec3};
 use azalea_protocol_macros::ClientboundGamePacket;
 
-use super::clientbound_sound_packet::SoundSource;
+use super::clientbound_sound_packet::{fixed_point_pos, SoundSource};
 
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundCustomSoundPacket {
@@ -18,3 +18,10 @@ pub struct ClientboundCustomSoundPacket {
     pub pitch: f32,
     pub seed: u64,
 }
+
+impl ClientboundCustomSoundPacket {
+    /// Where the sound is being played.
+    pub fn pos(&self) -> Vec3 {
+        fixed_point_pos(self.x, self.y, self.z)
+    }
+}
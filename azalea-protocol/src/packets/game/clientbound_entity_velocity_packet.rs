@@ -1,4 +1,5 @@
 use azalea_buf::McBuf;
+use azalea_core::Vec3;
 use azalea_protocol_macros::ClientboundGamePacket;
 
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
@@ -9,3 +10,15 @@ pub struct ClientboundEntityVelocityPacket {
     pub y_vel: i16,
     pub z_vel: i16,
 }
+
+impl ClientboundEntityVelocityPacket {
+    /// The velocity in blocks per tick. It's sent in units of 1/8000 of a
+    /// block per tick.
+    pub fn velocity(&self) -> Vec3 {
+        Vec3 {
+            x: self.x_vel as f64 / 8000.,
+            y: self.y_vel as f64 / 8000.,
+            z: self.z_vel as f64 / 8000.,
+        }
+    }
+}
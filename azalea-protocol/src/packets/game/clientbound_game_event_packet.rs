@@ -1,24 +1,93 @@
 use azalea_buf::McBuf;
+use azalea_core::GameType;
 use azalea_protocol_macros::ClientboundGamePacket;
 
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundGameEventPacket {
-    pub event: EventType,
+    /// The id of the event. Use [`ClientboundGameEventPacket::game_event`] to
+    /// get what it means.
+    pub event: u8,
     pub param: f32,
 }
 
-#[derive(Clone, Debug, Copy, McBuf)]
-pub enum EventType {
-    NoRespawnBlockAvailable = 0,
-    StartRaining = 1,
-    StopRaining = 2,
-    ChangeGameMode = 3,
-    WinGame = 4,
-    DemoEvent = 5,
-    ArrowHitPlayer = 6,
-    RainLevelChange = 7,
-    ThunderLevelChange = 8,
-    PufferFishSting = 9,
-    GuardianElderEffect = 10,
-    ImmediateRespawn = 11,
+/// The things that can happen in a [`ClientboundGameEventPacket`], with their
+/// parameter decoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    NoRespawnBlockAvailable,
+    StartRaining,
+    StopRaining,
+    ChangeGameMode(GameType),
+    WinGame {
+        show_credits: bool,
+    },
+    /// Something for the demo version of the game, like showing the welcome
+    /// screen (0) or the controls (101 to 104).
+    DemoEvent(f32),
+    ArrowHitPlayer,
+    /// How strong the rain is, from 0 to 1.
+    RainLevelChange(f32),
+    /// How strong the thunder is, from 0 to 1.
+    ThunderLevelChange(f32),
+    PufferFishSting,
+    GuardianElderEffect,
+    ImmediateRespawn(bool),
+    /// An event we don't know about, with its id and parameter.
+    Unknown(u8, f32),
+}
+
+impl ClientboundGameEventPacket {
+    /// Decode what this event is.
+    pub fn game_event(&self) -> GameEvent {
+        match self.event {
+            0 => GameEvent::NoRespawnBlockAvailable,
+            1 => GameEvent::StartRaining,
+            2 => GameEvent::StopRaining,
+            3 => match GameType::from_id(self.param as u8) {
+                Some(game_type) => GameEvent::ChangeGameMode(game_type),
+                None => GameEvent::Unknown(self.event, self.param),
+            },
+            4 => GameEvent::WinGame {
+                show_credits: self.param == 1.,
+            },
+            5 => GameEvent::DemoEvent(self.param),
+            6 => GameEvent::ArrowHitPlayer,
+            7 => GameEvent::RainLevelChange(self.param),
+            8 => GameEvent::ThunderLevelChange(self.param),
+            9 => GameEvent::PufferFishSting,
+            10 => GameEvent::GuardianElderEffect,
+            11 => GameEvent::ImmediateRespawn(self.param == 1.),
+            _ => GameEvent::Unknown(self.event, self.param),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_buf::McBufReadable;
+    use std::io::Cursor;
+
+    fn read(event: u8, param: f32) -> GameEvent {
+        let mut buf = vec![event];
+        buf.extend(param.to_be_bytes());
+        ClientboundGameEventPacket::read_from(&mut Cursor::new(&buf))
+            .unwrap()
+            .game_event()
+    }
+
+    #[test]
+    fn test_game_events() {
+        assert_eq!(read(1, 0.), GameEvent::StartRaining);
+        assert_eq!(read(3, 1.), GameEvent::ChangeGameMode(GameType::CREATIVE));
+        assert_eq!(read(7, 0.5), GameEvent::RainLevelChange(0.5));
+        assert_eq!(read(4, 1.), GameEvent::WinGame { show_credits: true });
+        assert_eq!(read(11, 0.), GameEvent::ImmediateRespawn(false));
+    }
+
+    #[test]
+    fn test_unknown_game_events() {
+        assert_eq!(read(200, 2.), GameEvent::Unknown(200, 2.));
+        assert_eq!(read(3, 9.), GameEvent::Unknown(3, 9.));
+    }
 }
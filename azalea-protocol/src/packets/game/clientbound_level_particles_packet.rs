@@ -1,12 +1,10 @@
 use azalea_buf::{BufReadError, McBufReadable, McBufVarReadable, McBufWritable};
-use azalea_core::ParticleData;
+use azalea_core::{ParticleData, ParticleKind};
 use azalea_protocol_macros::ClientboundGamePacket;
 use std::io::{Cursor, Write};
 
 #[derive(Clone, Debug, ClientboundGamePacket)]
 pub struct ClientboundLevelParticlesPacket {
-    #[var]
-    pub particle_id: u32,
     pub override_limiter: bool,
     pub x: f64,
     pub y: f64,
@@ -16,11 +14,21 @@ pub struct ClientboundLevelParticlesPacket {
     pub z_dist: f32,
     pub max_speed: f32,
     pub count: u32,
+    /// The particle, with any extra data that depends on its type (like the
+    /// color for dust).
     pub data: ParticleData,
 }
 
+impl ClientboundLevelParticlesPacket {
+    /// The type of particle.
+    pub fn kind(&self) -> ParticleKind {
+        self.data.kind()
+    }
+}
+
 impl McBufReadable for ClientboundLevelParticlesPacket {
     fn read_from(buf: &mut Cursor<&[u8]>) -> Result<Self, BufReadError> {
+        // the data comes at the end, but its type is at the start
         let particle_id = u32::var_read_from(buf)?;
         let override_limiter = bool::read_from(buf)?;
         let x = f64::read_from(buf)?;
@@ -35,7 +43,6 @@ impl McBufReadable for ClientboundLevelParticlesPacket {
         let data = ParticleData::read_from_particle_id(buf, particle_id)?;
 
         Ok(Self {
-            particle_id,
             override_limiter,
             x,
             y,
@@ -51,7 +58,56 @@ impl McBufReadable for ClientboundLevelParticlesPacket {
 }
 
 impl McBufWritable for ClientboundLevelParticlesPacket {
-    fn write_into(&self, _buf: &mut impl Write) -> Result<(), std::io::Error> {
-        todo!();
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        self.kind().write_into(buf)?;
+        self.override_limiter.write_into(buf)?;
+        self.x.write_into(buf)?;
+        self.y.write_into(buf)?;
+        self.z.write_into(buf)?;
+        self.x_dist.write_into(buf)?;
+        self.y_dist.write_into(buf)?;
+        self.z_dist.write_into(buf)?;
+        self.max_speed.write_into(buf)?;
+        self.count.write_into(buf)?;
+        self.data.write_data(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_core::DustParticle;
+
+    #[test]
+    fn test_read_write() {
+        let packet = ClientboundLevelParticlesPacket {
+            override_limiter: false,
+            x: 1.,
+            y: 2.,
+            z: 3.,
+            x_dist: 0.,
+            y_dist: 0.,
+            z_dist: 0.,
+            max_speed: 0.,
+            count: 5,
+            data: ParticleData::Dust(DustParticle {
+                red: 1.,
+                green: 0.,
+                blue: 0.,
+                scale: 1.,
+            }),
+        };
+        let mut buf = Vec::new();
+        packet.write_into(&mut buf).unwrap();
+        assert_eq!(buf[0], ParticleKind::Dust as u8);
+
+        let read_packet =
+            ClientboundLevelParticlesPacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(read_packet.kind(), ParticleKind::Dust);
+        assert_eq!((read_packet.x, read_packet.y, read_packet.z), (1., 2., 3.));
+        assert_eq!(read_packet.count, 5);
+        let mut read_buf = Vec::new();
+        read_packet.write_into(&mut read_buf).unwrap();
+        assert_eq!(read_buf, buf);
     }
 }
@@ -13,6 +13,8 @@ pub struct ClientboundPlayerChatPacket {
 
 #[derive(Copy, Clone, Debug, McBuf, PartialEq, Eq)]
 pub enum ChatType {
+    // servers can add their own chat types, which we don't know about
+    #[fallback]
     Chat = 0,
     SayCommand = 1,
     MsgCommandIncoming = 2,
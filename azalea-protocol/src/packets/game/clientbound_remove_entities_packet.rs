@@ -1,6 +1,13 @@
 use azalea_buf::McBuf;
 use azalea_protocol_macros::ClientboundGamePacket;
 
+/// Remove entities from the world.
+///
+/// This is a list of ids in every version except 1.17 (protocol 755), where
+/// it was briefly replaced with a packet that only has a single id. We only
+/// decode packets from [`PROTOCOL_VERSION`](crate::packets::PROTOCOL_VERSION),
+/// and servers that speak a different version reject us while logging in, so
+/// the single id variant is never read.
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundRemoveEntitiesPacket {
     #[var]
@@ -1,8 +0,0 @@
-use azalea_buf::McBuf;
-use azalea_protocol_macros::ClientboundGamePacket;
-
-#[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
-pub struct ClientboundSetChunkCacheRadiusPacket {
-    #[var]
-    pub radius: u32,
-}
@@ -1,5 +1,6 @@
 use azalea_buf::{BufReadError, McBuf};
 use azalea_buf::{McBufReadable, McBufWritable};
+pub use azalea_core::EquipmentSlot;
 use azalea_core::Slot;
 use azalea_protocol_macros::ClientboundGamePacket;
 use std::io::Cursor;
@@ -54,26 +55,36 @@ impl McBufWritable for EquipmentSlots {
     }
 }
 
-#[derive(Clone, Debug, Copy, McBuf)]
-pub enum EquipmentSlot {
-    MainHand = 0,
-    OffHand = 1,
-    Feet = 2,
-    Legs = 3,
-    Chest = 4,
-    Head = 5,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl EquipmentSlot {
-    pub fn from_byte(byte: u8) -> Option<Self> {
-        match byte {
-            0 => Some(EquipmentSlot::MainHand),
-            1 => Some(EquipmentSlot::OffHand),
-            2 => Some(EquipmentSlot::Feet),
-            3 => Some(EquipmentSlot::Legs),
-            4 => Some(EquipmentSlot::Chest),
-            5 => Some(EquipmentSlot::Head),
-            _ => None,
+    #[test]
+    fn test_read_write_multiple_slots() {
+        let buf = vec![
+            7,    // entity id
+            0x80, // main hand, and there's another slot after it
+            1, 5, 1, 0, // an item with id 5, a count of 1, and no nbt
+            5, // head, which is the last slot
+            0, // no item
+        ];
+        let packet = ClientboundSetEquipmentPacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(packet.entity, 7);
+        assert_eq!(packet.slots.slots.len(), 2);
+        match &packet.slots.slots[0] {
+            (EquipmentSlot::MainHand, Slot::Present(item)) => {
+                assert_eq!(item.id, 5);
+                assert_eq!(item.count, 1);
+            }
+            slot => panic!("Expected an item in the main hand, got {:?}", slot),
         }
+        assert!(matches!(
+            packet.slots.slots[1],
+            (EquipmentSlot::Head, Slot::Empty)
+        ));
+
+        let mut written = Vec::new();
+        packet.write_into(&mut written).unwrap();
+        assert_eq!(written, buf);
     }
 }
@@ -4,5 +4,6 @@ use azalea_protocol_macros::ClientboundGamePacket;
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundSetTimePacket {
     pub game_time: u64,
-    pub day_time: u64,
+    /// The time of day. This is negative if the daylight cycle is disabled.
+    pub day_time: i64,
 }
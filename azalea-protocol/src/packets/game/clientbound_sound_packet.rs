@@ -1,19 +1,39 @@
 use azalea_buf::McBuf;
+use azalea_core::Vec3;
 use azalea_protocol_macros::ClientboundGamePacket;
 
 #[derive(Clone, Debug, McBuf, ClientboundGamePacket)]
 pub struct ClientboundSoundPacket {
     pub sound: azalea_registry::SoundEvent,
     pub source: SoundSource,
+    /// x position multiplied by 8
     pub x: i32,
+    /// y position multiplied by 8
     pub y: i32,
+    /// z position multiplied by 8
     pub z: i32,
     pub volume: f32,
     pub pitch: f32,
     pub seed: u64,
 }
 
-#[derive(McBuf, Clone, Copy, Debug)]
+impl ClientboundSoundPacket {
+    /// Where the sound is being played.
+    pub fn pos(&self) -> Vec3 {
+        fixed_point_pos(self.x, self.y, self.z)
+    }
+}
+
+/// Convert a position that was multiplied by 8 back into a [`Vec3`].
+pub(crate) fn fixed_point_pos(x: i32, y: i32, z: i32) -> Vec3 {
+    Vec3 {
+        x: x as f64 / 8.,
+        y: y as f64 / 8.,
+        z: z as f64 / 8.,
+    }
+}
+
+#[derive(McBuf, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SoundSource {
     Master = 0,
     Music = 1,
@@ -26,3 +46,41 @@ pub enum SoundSource {
     Ambient = 8,
     Voice = 9,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_buf::McBufReadable;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_sound() {
+        let mut buf = Vec::new();
+        // sound id, source, x, y, z, volume, pitch, seed
+        buf.push(1);
+        buf.push(5);
+        buf.extend((-12i32).to_be_bytes());
+        buf.extend(520i32.to_be_bytes());
+        buf.extend(4i32.to_be_bytes());
+        buf.extend(1f32.to_be_bytes());
+        buf.extend(0.5f32.to_be_bytes());
+        buf.extend(0u64.to_be_bytes());
+
+        let packet = ClientboundSoundPacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(
+            packet.sound,
+            azalea_registry::SoundEvent::try_from(1).unwrap()
+        );
+        assert_eq!(packet.source, SoundSource::Hostile);
+        assert_eq!(
+            packet.pos(),
+            Vec3 {
+                x: -1.5,
+                y: 65.,
+                z: 0.5
+            }
+        );
+        assert_eq!(packet.volume, 1.);
+        assert_eq!(packet.pitch, 0.5);
+    }
+}
@@ -6,6 +6,7 @@ use super::clientbound_player_chat_packet::LastSeenMessagesUpdate;
 
 #[derive(Clone, Debug, McBuf, ServerboundGamePacket)]
 pub struct ServerboundChatCommandPacket {
+    #[max_len(256)]
     pub command: String,
     // TODO: Choose a real timestamp type
     pub timestamp: u64,
@@ -5,6 +5,7 @@ use azalea_protocol_macros::ServerboundGamePacket;
 
 #[derive(Clone, Debug, McBuf, ServerboundGamePacket)]
 pub struct ServerboundChatPacket {
+    #[max_len(256)]
     pub message: String,
     pub timestamp: u64,
     pub salt: u64,
@@ -12,3 +13,43 @@ pub struct ServerboundChatPacket {
     pub signed_preview: bool,
     pub last_seen_messages: LastSeenMessagesUpdate,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_buf::{BufReadError, McBufReadable, McBufWritable};
+    use std::io::Cursor;
+
+    fn packet_with_message(message: String) -> ServerboundChatPacket {
+        ServerboundChatPacket {
+            message,
+            timestamp: 0,
+            salt: 0,
+            signature: MessageSignature::default(),
+            signed_preview: false,
+            last_seen_messages: LastSeenMessagesUpdate {
+                last_seen: vec![],
+                last_received: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_message_max_len() {
+        let mut buf = Vec::new();
+        packet_with_message("a".repeat(256))
+            .write_into(&mut buf)
+            .unwrap();
+        let packet = ServerboundChatPacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(packet.message.len(), 256);
+
+        let mut buf = Vec::new();
+        packet_with_message("a".repeat(257))
+            .write_into(&mut buf)
+            .unwrap();
+        assert!(matches!(
+            ServerboundChatPacket::read_from(&mut Cursor::new(&buf)),
+            Err(BufReadError::StringLengthTooLong { .. })
+        ));
+    }
+}
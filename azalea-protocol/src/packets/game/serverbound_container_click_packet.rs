@@ -12,6 +12,8 @@ pub struct ServerboundContainerClickPacket {
     pub button_num: u8,
     pub click_type: ClickType,
     pub changed_slots: HashMap<u16, Slot>,
+    /// The item on the cursor after the click.
+    pub carried_item: Slot,
 }
 
 #[derive(McBuf, Clone, Copy, Debug)]
@@ -24,3 +26,36 @@ pub enum ClickType {
     QuickCraft = 5,
     PickupAll = 6,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_buf::McBufWritable;
+
+    #[test]
+    fn test_write_throw_click() {
+        let packet = ServerboundContainerClickPacket {
+            container_id: 0,
+            state_id: 3,
+            slot_num: 36,
+            button_num: 1,
+            click_type: ClickType::Throw,
+            changed_slots: [(36, Slot::Empty)].into_iter().collect(),
+            carried_item: Slot::Empty,
+        };
+        let mut buf = Vec::new();
+        packet.write_into(&mut buf).unwrap();
+        assert_eq!(
+            buf,
+            vec![
+                0, // container id
+                3, // state id
+                0, 36, // slot number
+                1,  // button number
+                4,  // click type
+                1, 0, 36, 0, // one changed slot, which is 36 and now empty
+                0, // no carried item
+            ]
+        );
+    }
+}
@@ -5,7 +5,7 @@ use std::io::Cursor;
 
 #[derive(Clone, Debug, ServerboundGamePacket)]
 pub struct ServerboundPlayerAbilitiesPacket {
-    is_flying: bool,
+    pub is_flying: bool,
 }
 
 impl McBufReadable for ServerboundPlayerAbilitiesPacket {
@@ -19,7 +19,7 @@ impl McBufReadable for ServerboundPlayerAbilitiesPacket {
 
 impl McBufWritable for ServerboundPlayerAbilitiesPacket {
     fn write_into(&self, buf: &mut impl std::io::Write) -> Result<(), std::io::Error> {
-        let mut byte = 0;
+        let mut byte = 0u8;
         if self.is_flying {
             byte |= 2;
         }
@@ -6,7 +6,7 @@ pub struct ServerboundResourcePackPacket {
     pub action: Action,
 }
 
-#[derive(McBuf, Clone, Copy, Debug)]
+#[derive(McBuf, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Action {
     SuccessfullyLoaded = 0,
     Declined = 1,
@@ -2,7 +2,39 @@ use azalea_auth::game_profile::GameProfile;
 use azalea_buf::McBuf;
 use azalea_protocol_macros::ClientboundLoginPacket;
 
+/// Sent when the server accepted our login, right before we switch to the
+/// game state. The profile has the UUID that the server gave us.
 #[derive(Clone, Debug, McBuf, ClientboundLoginPacket)]
 pub struct ClientboundGameProfilePacket {
     pub game_profile: GameProfile,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_auth::game_profile::ProfilePropertyValue;
+    use azalea_buf::{McBufReadable, McBufWritable};
+    use std::{collections::HashMap, io::Cursor};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_read_write() {
+        let packet = ClientboundGameProfilePacket {
+            game_profile: GameProfile {
+                uuid: Uuid::from_u128(1),
+                name: "bot".to_string(),
+                properties: HashMap::from([(
+                    "textures".to_string(),
+                    ProfilePropertyValue {
+                        value: "abc".to_string(),
+                        signature: None,
+                    },
+                )]),
+            },
+        };
+        let mut buf = Vec::new();
+        packet.write_into(&mut buf).unwrap();
+        let packet2 = ClientboundGameProfilePacket::read_from(&mut Cursor::new(&buf)).unwrap();
+        assert_eq!(packet.game_profile, packet2.game_profile);
+    }
+}
@@ -4,6 +4,7 @@ use uuid::Uuid;
 
 #[derive(Clone, Debug, ServerboundLoginPacket, McBuf, PartialEq, Eq)]
 pub struct ServerboundHelloPacket {
+    #[max_len(16)]
     pub username: String,
     pub public_key: Option<ProfilePublicKeyData>,
     pub profile_id: Option<Uuid>,
@@ -1,16 +1,13 @@
+use crate::compression::{Compressor, Zlib};
 use crate::packets::ProtocolPacket;
 use azalea_buf::BufReadError;
 use azalea_buf::McBufVarReadable;
 use azalea_crypto::Aes128CfbDec;
 use bytes::Buf;
 use bytes::BytesMut;
-use flate2::read::ZlibDecoder;
 use futures::StreamExt;
 use log::{log_enabled, trace};
-use std::{
-    fmt::Debug,
-    io::{Cursor, Read},
-};
+use std::{fmt::Debug, io::Cursor};
 use thiserror::Error;
 use tokio::io::AsyncRead;
 use tokio_util::codec::{BytesCodec, FramedRead};
@@ -66,15 +63,21 @@ pub enum FrameSplitterError {
     },
     #[error("Packet is longer than {max} bytes (is {size})")]
     BadLength { max: usize, size: usize },
+    #[error("Packet is {len} bytes long, which is more than the maximum of {max}")]
+    PacketTooLarge { len: usize, max: usize },
     #[error("Connection reset by peer")]
     ConnectionReset,
     #[error("Connection closed")]
     ConnectionClosed,
 }
 
+/// The longest packet we'll read by default, before decompressing it. Vanilla
+/// never sends packets longer than 2MiB, but we allow more for modded servers.
+pub const DEFAULT_MAX_PACKET_LENGTH: usize = 8 * 1024 * 1024;
+
 /// Read a length, then read that amount of bytes from BytesMut. If there's not
 /// enough data, return None
-fn parse_frame(buffer: &mut BytesMut) -> Result<BytesMut, FrameSplitterError> {
+fn parse_frame(buffer: &mut BytesMut, max_length: usize) -> Result<BytesMut, FrameSplitterError> {
     // copy the buffer first and read from the copy, then once we make sure
     // the packet is all good we read it fully
     let mut buffer_copy = Cursor::new(&buffer[..]);
@@ -87,6 +90,15 @@ fn parse_frame(buffer: &mut BytesMut) -> Result<BytesMut, FrameSplitterError> {
         },
     };
 
+    // check this before waiting for the rest of the packet, so a bad length
+    // can't make us buffer an unlimited amount of data
+    if length > max_length {
+        return Err(FrameSplitterError::PacketTooLarge {
+            len: length,
+            max: max_length,
+        });
+    }
+
     if length > buffer_copy.remaining() {
         return Err(FrameSplitterError::BadLength {
             max: buffer_copy.remaining(),
@@ -106,9 +118,12 @@ fn parse_frame(buffer: &mut BytesMut) -> Result<BytesMut, FrameSplitterError> {
     Ok(data)
 }
 
-fn frame_splitter(buffer: &mut BytesMut) -> Result<Option<Vec<u8>>, FrameSplitterError> {
+fn frame_splitter(
+    buffer: &mut BytesMut,
+    max_length: usize,
+) -> Result<Option<Vec<u8>>, FrameSplitterError> {
     // https://tokio.rs/tokio/tutorial/framing
-    let read_frame = parse_frame(buffer);
+    let read_frame = parse_frame(buffer, max_length);
     match read_frame {
         Ok(frame) => return Ok(Some(frame.to_vec())),
         Err(err) => match err {
@@ -160,6 +175,7 @@ pub enum DecompressionError {
 fn compression_decoder(
     stream: &mut Cursor<&[u8]>,
     compression_threshold: u32,
+    compressor: &dyn Compressor,
 ) -> Result<Vec<u8>, DecompressionError> {
     // Data Length
     let n = u32::var_read_from(stream)?;
@@ -185,11 +201,8 @@ fn compression_decoder(
         }
     }
 
-    let mut decoded_buf = vec![];
-    let mut decoder = ZlibDecoder::new(stream);
-    decoder.read_to_end(&mut decoded_buf)?;
-
-    Ok(decoded_buf)
+    let compressed = &stream.get_ref()[stream.position() as usize..];
+    Ok(compressor.decompress(compressed, n as usize)?)
 }
 
 pub async fn read_packet<'a, P: ProtocolPacket + Debug, R>(
@@ -198,12 +211,38 @@ pub async fn read_packet<'a, P: ProtocolPacket + Debug, R>(
     compression_threshold: Option<u32>,
     cipher: &mut Option<Aes128CfbDec>,
 ) -> Result<P, ReadPacketError>
+where
+    R: AsyncRead + std::marker::Unpin + std::marker::Send + std::marker::Sync,
+{
+    read_packet_with_max_length(
+        stream,
+        buffer,
+        compression_threshold,
+        &Zlib,
+        cipher,
+        DEFAULT_MAX_PACKET_LENGTH,
+    )
+    .await
+}
+
+/// Like [`read_packet`], but packets that say they're longer than
+/// `max_length` are rejected with [`FrameSplitterError::PacketTooLarge`]
+/// instead of [`DEFAULT_MAX_PACKET_LENGTH`], and compressed packets are
+/// decompressed with `compressor` instead of zlib.
+pub async fn read_packet_with_max_length<'a, P: ProtocolPacket + Debug, R>(
+    stream: &'a mut R,
+    buffer: &mut BytesMut,
+    compression_threshold: Option<u32>,
+    compressor: &dyn Compressor,
+    cipher: &mut Option<Aes128CfbDec>,
+    max_length: usize,
+) -> Result<P, ReadPacketError>
 where
     R: AsyncRead + std::marker::Unpin + std::marker::Send + std::marker::Sync,
 {
     let mut framed = FramedRead::new(stream, BytesCodec::new());
     let mut buf = loop {
-        if let Some(buf) = frame_splitter(buffer)? {
+        if let Some(buf) = frame_splitter(buffer, max_length)? {
             // we got a full packet!!
             break buf;
         } else {
@@ -225,7 +264,11 @@ where
     };
 
     if let Some(compression_threshold) = compression_threshold {
-        buf = compression_decoder(&mut Cursor::new(&buf[..]), compression_threshold)?;
+        buf = compression_decoder(
+            &mut Cursor::new(&buf[..]),
+            compression_threshold,
+            compressor,
+        )?;
     }
 
     if log_enabled!(log::Level::Trace) {
@@ -249,7 +292,11 @@ where
 mod tests {
     use super::*;
     use crate::packets::game::{clientbound_player_chat_packet::ChatType, ClientboundGamePacket};
+    use crate::packets::status::serverbound_ping_request_packet::ServerboundPingRequestPacket;
+    use crate::packets::status::ServerboundStatusPacket;
+    use crate::write::write_packet;
     use std::io::Cursor;
+    use tokio::io::{AsyncWrite, AsyncWriteExt, BufReader};
 
     #[tokio::test]
     async fn test_read_packet() {
@@ -279,4 +326,80 @@ mod tests {
             _ => panic!("Wrong packet type"),
         }
     }
+
+    /// Write a packet to one stream and read it back from the other. This is
+    /// generic so the read and write paths can't come to depend on a
+    /// concrete stream like `TcpStream`, since connections can also go over
+    /// things like TLS streams.
+    async fn roundtrip<S>(mut client: S, mut server: S)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + Sync,
+    {
+        write_packet(
+            &ServerboundPingRequestPacket { time: 5 }.get(),
+            &mut client,
+            Some(0),
+            &mut None,
+        )
+        .await
+        .unwrap();
+        client.flush().await.unwrap();
+        let packet = read_packet::<ServerboundStatusPacket, _>(
+            &mut server,
+            &mut BytesMut::new(),
+            Some(0),
+            &mut None,
+        )
+        .await
+        .unwrap();
+        match packet {
+            ServerboundStatusPacket::PingRequest(p) => assert_eq!(p.time, 5),
+            p => panic!("Expected a ping request packet, got {:?}", p),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_packet_over_wrapped_stream() {
+        let (client, server) = tokio::io::duplex(64);
+        roundtrip(BufReader::new(client), BufReader::new(server)).await;
+    }
+
+    #[tokio::test]
+    async fn test_packet_too_large() {
+        // the biggest length a varint can have, and no data after it
+        let mut stream: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x0f];
+        let result = read_packet::<ClientboundGamePacket, _>(
+            &mut stream,
+            &mut BytesMut::new(),
+            None,
+            &mut None,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(ReadPacketError::FrameSplitter {
+                source: FrameSplitterError::PacketTooLarge {
+                    len: 0xffffffff,
+                    max: DEFAULT_MAX_PACKET_LENGTH
+                }
+            })
+        ));
+
+        let mut stream: &[u8] = &[0x40];
+        let result = read_packet_with_max_length::<ClientboundGamePacket, _>(
+            &mut stream,
+            &mut BytesMut::new(),
+            None,
+            &Zlib,
+            &mut None,
+            0x3f,
+        )
+        .await;
+        assert!(matches!(
+            result,
+            Err(ReadPacketError::FrameSplitter {
+                source: FrameSplitterError::PacketTooLarge { len: 0x40, .. }
+            })
+        ));
+    }
 }
@@ -0,0 +1,99 @@
+//! Counters for how much data a connection has sent and received.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A snapshot of the [`ConnectionCounters`] for a connection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub packets_read: u64,
+    pub packets_written: u64,
+    /// The number of bytes received, including framing, compression, and
+    /// encryption.
+    pub bytes_read: u64,
+    /// The number of bytes sent, including framing, compression, and
+    /// encryption.
+    pub bytes_written: u64,
+    /// The number of packets we received that couldn't be decoded.
+    pub decode_errors: u64,
+}
+
+/// The counters for a connection. These are shared between the reading and
+/// writing halves of the connection, and are kept when switching states.
+#[derive(Debug, Default)]
+pub struct ConnectionCounters {
+    pub packets_read: AtomicU64,
+    pub packets_written: AtomicU64,
+    pub bytes_read: AtomicU64,
+    pub bytes_written: AtomicU64,
+    pub decode_errors: AtomicU64,
+}
+
+impl ConnectionCounters {
+    pub fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            packets_read: self.packets_read.load(Ordering::Relaxed),
+            packets_written: self.packets_written.load(Ordering::Relaxed),
+            bytes_read: self.bytes_read.load(Ordering::Relaxed),
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            decode_errors: self.decode_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A stream that adds the number of bytes that go through it to a counter.
+pub(crate) struct CountingStream<'a, S> {
+    inner: &'a mut S,
+    bytes: &'a AtomicU64,
+}
+
+impl<'a, S> CountingStream<'a, S> {
+    pub fn new(inner: &'a mut S, bytes: &'a AtomicU64) -> Self {
+        Self { inner, bytes }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for CountingStream<'_, S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled_before = buf.filled().len();
+        let result = Pin::new(&mut *self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            self.bytes.fetch_add(
+                (buf.filled().len() - filled_before) as u64,
+                Ordering::Relaxed,
+            );
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for CountingStream<'_, S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut *self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            self.bytes.fetch_add(written as u64, Ordering::Relaxed);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.inner).poll_shutdown(cx)
+    }
+}
@@ -1,16 +1,35 @@
-use crate::{packets::ProtocolPacket, read::MAXIMUM_UNCOMPRESSED_LENGTH};
-use async_compression::tokio::bufread::ZlibEncoder;
-use azalea_buf::McBufVarWritable;
+use crate::{
+    compression::{Compressor, Zlib},
+    packets::ProtocolPacket,
+    read::MAXIMUM_UNCOMPRESSED_LENGTH,
+};
+use azalea_buf::{varint_len, McBufVarWritable, MAX_VAR_INT_LENGTH};
 use azalea_crypto::Aes128CfbEnc;
 use std::fmt::Debug;
 use thiserror::Error;
-use tokio::io::{AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
-fn frame_prepender(data: &mut Vec<u8>) -> Result<Vec<u8>, std::io::Error> {
-    let mut buf = Vec::new();
-    (data.len() as u32).var_write_into(&mut buf)?;
-    buf.append(data);
-    Ok(buf)
+/// The space left at the start of the buffer for the packet length and data
+/// length, so they can be added without moving the packet.
+const HEADER_SPACE: usize = MAX_VAR_INT_LENGTH * 2;
+
+/// Write a VarInt right before `end` in the buffer, returning the index it
+/// starts at.
+fn var_int_before(buf: &mut [u8], end: usize, value: u32) -> Result<usize, std::io::Error> {
+    let start = end - varint_len(value);
+    value.var_write_into(&mut &mut buf[start..end])?;
+    Ok(start)
+}
+
+/// The buffers packets are encoded and compressed into. Keeping one of these
+/// between packets means they don't have to be allocated again for every
+/// packet.
+#[derive(Debug, Default)]
+pub struct WriteBuffer {
+    /// The packet, after [`HEADER_SPACE`] bytes for the header.
+    packet: Vec<u8>,
+    /// Packets are compressed into this, and then it's swapped with `packet`.
+    compressed: Vec<u8>,
 }
 
 #[derive(Error, Debug)]
@@ -25,20 +44,25 @@ pub enum PacketEncodeError {
     },
 }
 
+/// Write the packet id and data into the buffer, after the space for the
+/// header.
 fn packet_encoder<P: ProtocolPacket + std::fmt::Debug>(
     packet: &P,
-) -> Result<Vec<u8>, PacketEncodeError> {
-    let mut buf = Vec::new();
-    packet.id().var_write_into(&mut buf)?;
-    packet.write(&mut buf)?;
-    if buf.len() > MAXIMUM_UNCOMPRESSED_LENGTH as usize {
+    buf: &mut Vec<u8>,
+) -> Result<(), PacketEncodeError> {
+    buf.clear();
+    buf.resize(HEADER_SPACE, 0);
+    packet.id().var_write_into(buf)?;
+    packet.write(buf)?;
+    let len = buf.len() - HEADER_SPACE;
+    if len > MAXIMUM_UNCOMPRESSED_LENGTH as usize {
         return Err(PacketEncodeError::TooBig {
-            actual: buf.len(),
+            actual: len,
             maximum: MAXIMUM_UNCOMPRESSED_LENGTH as usize,
             packet_string: format!("{:?}", packet),
         });
     }
-    Ok(buf)
+    Ok(())
 }
 
 #[derive(Error, Debug)]
@@ -47,25 +71,25 @@ pub enum PacketCompressError {
     Io(#[from] std::io::Error),
 }
 
-async fn compression_encoder(
-    data: &[u8],
+/// Compress the packet in the buffer if it's at least as big as the
+/// threshold, and add the data length before it. Returns the index the
+/// packet starts at.
+fn compression_encoder(
+    buf: &mut WriteBuffer,
     compression_threshold: u32,
-) -> Result<Vec<u8>, PacketCompressError> {
-    let n = data.len();
+    compressor: &dyn Compressor,
+) -> Result<usize, PacketCompressError> {
+    let n = buf.packet.len() - HEADER_SPACE;
     // if it's less than the compression threshold, don't compress
     if n < compression_threshold as usize {
-        let mut buf = Vec::new();
-        0.var_write_into(&mut buf)?;
-        buf.write_all(data).await?;
-        Ok(buf)
-    } else {
-        // otherwise, compress
-        let mut deflater = ZlibEncoder::new(data);
-        // write deflated data to buf
-        let mut buf = Vec::new();
-        deflater.read_to_end(&mut buf).await?;
-        Ok(buf)
+        return Ok(var_int_before(&mut buf.packet, HEADER_SPACE, 0)?);
     }
+    // otherwise, compress
+    buf.compressed.clear();
+    buf.compressed.resize(HEADER_SPACE, 0);
+    compressor.compress(&buf.packet[HEADER_SPACE..], &mut buf.compressed)?;
+    std::mem::swap(&mut buf.packet, &mut buf.compressed);
+    Ok(var_int_before(&mut buf.packet, HEADER_SPACE, n as u32)?)
 }
 
 pub async fn write_packet<P, W>(
@@ -78,14 +102,166 @@ where
     P: ProtocolPacket + Debug,
     W: AsyncWrite + Unpin + Send,
 {
-    let mut buf = packet_encoder(packet).unwrap();
+    write_packet_with_buffer(
+        packet,
+        &mut WriteBuffer::default(),
+        stream,
+        compression_threshold,
+        &Zlib,
+        cipher,
+    )
+    .await
+}
+
+/// Like [`write_packet`], but the packet is encoded into the given buffer so
+/// it can be reused for the next packet instead of allocating a new one, and
+/// it's compressed with `compressor` instead of zlib.
+pub async fn write_packet_with_buffer<P, W>(
+    packet: &P,
+    buffer: &mut WriteBuffer,
+    stream: &mut W,
+    compression_threshold: Option<u32>,
+    compressor: &dyn Compressor,
+    cipher: &mut Option<Aes128CfbEnc>,
+) -> std::io::Result<()>
+where
+    P: ProtocolPacket + Debug,
+    W: AsyncWrite + Unpin + Send,
+{
+    packet_encoder(packet, &mut buffer.packet).unwrap();
+    write_encoded_packet(buffer, stream, compression_threshold, compressor, cipher).await
+}
+
+/// Write a packet with the given id and already encoded data. This is useful
+/// for sending packets that azalea doesn't know about.
+pub async fn write_raw_packet<W>(
+    id: u32,
+    data: &[u8],
+    stream: &mut W,
+    compression_threshold: Option<u32>,
+    cipher: &mut Option<Aes128CfbEnc>,
+) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    write_raw_packet_with_buffer(
+        id,
+        data,
+        &mut WriteBuffer::default(),
+        stream,
+        compression_threshold,
+        &Zlib,
+        cipher,
+    )
+    .await
+}
+
+/// Like [`write_raw_packet`], but the packet is encoded into the given buffer
+/// and compressed with `compressor`.
+pub(crate) async fn write_raw_packet_with_buffer<W>(
+    id: u32,
+    data: &[u8],
+    buffer: &mut WriteBuffer,
+    stream: &mut W,
+    compression_threshold: Option<u32>,
+    compressor: &dyn Compressor,
+    cipher: &mut Option<Aes128CfbEnc>,
+) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    buffer.packet.clear();
+    buffer.packet.resize(HEADER_SPACE, 0);
+    id.var_write_into(&mut buffer.packet)?;
+    buffer.packet.extend_from_slice(data);
+    write_encoded_packet(buffer, stream, compression_threshold, compressor, cipher).await
+}
+
+/// Compress, frame, and encrypt a packet that has its id and data encoded
+/// after the header space in the buffer.
+async fn write_encoded_packet<W>(
+    buf: &mut WriteBuffer,
+    stream: &mut W,
+    compression_threshold: Option<u32>,
+    compressor: &dyn Compressor,
+    cipher: &mut Option<Aes128CfbEnc>,
+) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    let mut start = HEADER_SPACE;
     if let Some(threshold) = compression_threshold {
-        buf = compression_encoder(&buf, threshold).await.unwrap();
+        start = compression_encoder(buf, threshold, compressor).map_err(|e| match e {
+            PacketCompressError::Io(e) => e,
+        })?;
     }
-    buf = frame_prepender(&mut buf).unwrap();
+    // prepend the length of the packet
+    let len = buf.packet.len() - start;
+    let start = var_int_before(&mut buf.packet, start, len as u32)?;
+    let packet = &mut buf.packet[start..];
     // if we were given a cipher, encrypt the packet
     if let Some(cipher) = cipher {
-        azalea_crypto::encrypt_packet(cipher, &mut buf);
+        azalea_crypto::encrypt_packet(cipher, packet);
+    }
+    stream.write_all(packet).await
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::game::{
+        serverbound_custom_payload_packet::ServerboundCustomPayloadPacket,
+        serverbound_keep_alive_packet::ServerboundKeepAlivePacket, ServerboundGamePacket,
+    };
+    use azalea_core::ResourceLocation;
+
+    #[tokio::test]
+    async fn test_write_raw_matches_typed() {
+        let packet: ServerboundGamePacket = ServerboundKeepAlivePacket { id: 123 }.into();
+        let mut data = Vec::new();
+        packet.write(&mut data).unwrap();
+
+        let mut typed = Vec::new();
+        write_packet(&packet, &mut typed, Some(256), &mut None)
+            .await
+            .unwrap();
+        let mut raw = Vec::new();
+        write_raw_packet(packet.id(), &data, &mut raw, Some(256), &mut None)
+            .await
+            .unwrap();
+        assert_eq!(typed, raw);
+    }
+
+    #[tokio::test]
+    async fn test_reused_buffer() {
+        let small: ServerboundGamePacket = ServerboundKeepAlivePacket { id: 1 }.into();
+        // big enough to be compressed
+        let big: ServerboundGamePacket = ServerboundCustomPayloadPacket {
+            identifier: ResourceLocation::new("azalea:test").unwrap(),
+            data: vec![1; 1000].into(),
+        }
+        .into();
+
+        let mut expected = Vec::new();
+        for packet in [&small, &big, &small, &big] {
+            write_packet(packet, &mut expected, Some(256), &mut None)
+                .await
+                .unwrap();
+        }
+
+        let mut buffer = WriteBuffer::default();
+        let mut written = Vec::new();
+        for packet in [&small, &big, &small, &big] {
+            write_packet_with_buffer(
+                packet,
+                &mut buffer,
+                &mut written,
+                Some(256),
+                &Zlib,
+                &mut None,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(written, expected);
     }
-    stream.write_all(&buf).await
 }
@@ -0,0 +1,13 @@
+use azalea_nbt::Tag;
+use azalea_registry::BlockEntityType;
+
+/// The extra data for a block that doesn't fit in its block state, like the
+/// text on a sign, the effects of a beacon, or the patterns on a banner.
+///
+/// The data is kept as the NBT the server sent, so it's up to you to read the
+/// tags for the kind of block entity you care about.
+#[derive(Clone, Debug)]
+pub struct BlockEntity {
+    pub kind: BlockEntityType,
+    pub data: Tag,
+}
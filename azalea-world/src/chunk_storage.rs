@@ -1,11 +1,12 @@
 use crate::palette::PalettedContainer;
 use crate::palette::PalettedContainerType;
-use crate::Dimension;
-use azalea_block::BlockState;
+use crate::{BlockEntity, Dimension};
+use azalea_block::{Block, BlockState};
 use azalea_buf::BufReadError;
 use azalea_buf::{McBufReadable, McBufWritable};
 use azalea_core::floor_mod;
 use azalea_core::{BlockPos, ChunkBlockPos, ChunkPos, ChunkSectionBlockPos};
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::io::Cursor;
 use std::{
@@ -29,6 +30,7 @@ pub struct ChunkStorage {
 #[derive(Debug)]
 pub struct Chunk {
     pub sections: Vec<Section>,
+    pub block_entities: HashMap<ChunkBlockPos, BlockEntity>,
 }
 
 #[derive(Clone, Debug)]
@@ -52,6 +54,7 @@ impl Default for Chunk {
     fn default() -> Self {
         Chunk {
             sections: vec![Section::default(); (384 / 16) as usize],
+            block_entities: HashMap::new(),
         }
     }
 }
@@ -98,6 +101,28 @@ impl ChunkStorage {
         Some(chunk.get_and_set(&ChunkBlockPos::from(pos), state, min_y))
     }
 
+    pub fn get_block_entity(&self, pos: &BlockPos) -> Option<BlockEntity> {
+        let chunk_pos = ChunkPos::from(pos);
+        let chunk = self[&chunk_pos].as_ref()?;
+        let chunk = chunk.lock().unwrap();
+        chunk.block_entities.get(&ChunkBlockPos::from(pos)).cloned()
+    }
+
+    /// Set the block entity at the given position, returning whether the chunk
+    /// it's in is loaded.
+    pub fn set_block_entity(&self, pos: &BlockPos, block_entity: BlockEntity) -> bool {
+        let chunk_pos = ChunkPos::from(pos);
+        let chunk = match self[&chunk_pos].as_ref() {
+            Some(chunk) => chunk,
+            None => return false,
+        };
+        let mut chunk = chunk.lock().unwrap();
+        chunk
+            .block_entities
+            .insert(ChunkBlockPos::from(pos), block_entity);
+        true
+    }
+
     pub fn replace_with_packet_data(
         &mut self,
         pos: &ChunkPos,
@@ -154,7 +179,11 @@ impl Chunk {
             let section = Section::read_from(buf)?;
             sections.push(section);
         }
-        Ok(Chunk { sections })
+        // the block entities are sent separately in the chunk packet
+        Ok(Chunk {
+            sections,
+            block_entities: HashMap::new(),
+        })
     }
 
     pub fn section_index(&self, y: i32, min_y: i32) -> u32 {
@@ -181,7 +210,15 @@ impl Chunk {
         // TODO: make sure the section exists
         let section = &mut self.sections[section_index as usize];
         let chunk_section_pos = ChunkSectionBlockPos::from(pos);
-        section.get_and_set(chunk_section_pos, state)
+        let old_state = section.get_and_set(chunk_section_pos, state);
+        // like in vanilla, the block entity stays if only the block's
+        // properties changed
+        if !self.block_entities.is_empty()
+            && Box::<dyn Block>::from(old_state).id() != Box::<dyn Block>::from(state).id()
+        {
+            self.block_entities.remove(pos);
+        }
+        old_state
     }
 
     pub fn set(&mut self, pos: &ChunkBlockPos, state: BlockState, min_y: i32) {
@@ -302,4 +339,48 @@ mod tests {
         assert_eq!(chunk.section_index(-48, -64), 1);
         assert_eq!(chunk.section_index(128, -64), 12);
     }
+
+    #[test]
+    fn test_block_entity_removed_when_block_changes() {
+        let mut storage = ChunkStorage::default();
+        storage[&ChunkPos::new(0, 0)] = Some(Arc::new(Mutex::new(Chunk::default())));
+        let pos = BlockPos::new(1, 2, 3);
+        storage.set_block_state(&pos, BlockState::Stone, -64);
+        assert!(storage.set_block_entity(
+            &pos,
+            BlockEntity {
+                kind: azalea_registry::BlockEntityType::Sign,
+                data: azalea_nbt::Tag::Compound(Default::default()),
+            },
+        ));
+        assert!(storage.get_block_entity(&pos).is_some());
+        // setting the same block again keeps it
+        storage.set_block_state(&pos, BlockState::Stone, -64);
+        assert!(storage.get_block_entity(&pos).is_some());
+        storage.set_block_state(&pos, BlockState::Air, -64);
+        assert!(storage.get_block_entity(&pos).is_none());
+
+        // unloaded chunks can't have block entities
+        assert!(!storage.set_block_entity(
+            &BlockPos::new(100, 0, 0),
+            BlockEntity {
+                kind: azalea_registry::BlockEntityType::Sign,
+                data: azalea_nbt::Tag::Compound(Default::default()),
+            },
+        ));
+    }
+
+    #[test]
+    fn test_get_and_set_with_negative_y() {
+        let mut chunk = Chunk::default();
+        let below_zero = ChunkBlockPos::new(1, -63, 2);
+        let above_zero = ChunkBlockPos::new(1, 1, 2);
+        chunk.set(&below_zero, BlockState::Stone, -64);
+        assert_eq!(chunk.get(&below_zero, -64), BlockState::Stone);
+        assert_eq!(chunk.get(&above_zero, -64), BlockState::Air);
+        assert_eq!(
+            chunk.get(&ChunkBlockPos::new(1, -49, 2), -64),
+            BlockState::Air
+        );
+    }
 }
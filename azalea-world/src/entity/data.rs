@@ -1,4 +1,4 @@
-use azalea_buf::{BufReadError, McBufVarReadable};
+use azalea_buf::{BufReadError, McBufVarReadable, McBufVarWritable};
 use azalea_buf::{McBuf, McBufReadable, McBufWritable};
 use azalea_chat::component::Component;
 use azalea_core::{BlockPos, Direction, GlobalPos, Particle, Slot};
@@ -8,6 +8,19 @@ use uuid::Uuid;
 #[derive(Clone, Debug)]
 pub struct EntityMetadata(Vec<EntityDataItem>);
 
+impl EntityMetadata {
+    /// Iterate over the fields that were sent.
+    pub fn iter(&self) -> std::slice::Iter<'_, EntityDataItem> {
+        self.0.iter()
+    }
+}
+
+impl From<Vec<EntityDataItem>> for EntityMetadata {
+    fn from(items: Vec<EntityDataItem>) -> Self {
+        Self(items)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EntityDataItem {
     // we can't identify what the index is for here because we don't know the
@@ -130,8 +143,103 @@ impl McBufReadable for EntityDataValue {
 }
 
 impl McBufWritable for EntityDataValue {
-    fn write_into(&self, _buf: &mut impl Write) -> Result<(), std::io::Error> {
-        todo!();
+    fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
+        match self {
+            EntityDataValue::Byte(value) => {
+                0u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Int(value) => {
+                1u32.var_write_into(buf)?;
+                value.var_write_into(buf)
+            }
+            EntityDataValue::Float(value) => {
+                2u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::String(value) => {
+                3u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Component(value) => {
+                4u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::OptionalComponent(value) => {
+                5u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::ItemStack(value) => {
+                6u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Boolean(value) => {
+                7u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Rotations { x, y, z } => {
+                8u32.var_write_into(buf)?;
+                x.write_into(buf)?;
+                y.write_into(buf)?;
+                z.write_into(buf)
+            }
+            EntityDataValue::BlockPos(value) => {
+                9u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::OptionalBlockPos(value) => {
+                10u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Direction(value) => {
+                11u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::OptionalUuid(value) => {
+                12u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::OptionalBlockState(value) => {
+                13u32.var_write_into(buf)?;
+                value.unwrap_or(0).var_write_into(buf)
+            }
+            EntityDataValue::CompoundTag(value) => {
+                14u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::Particle(value) => {
+                15u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::VillagerData(value) => {
+                16u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::OptionalUnsignedInt(value) => {
+                17u32.var_write_into(buf)?;
+                value.map_or(0, |v| v + 1).var_write_into(buf)
+            }
+            EntityDataValue::Pose(value) => {
+                18u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::CatVariant(value) => {
+                19u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::FrogVariant(value) => {
+                20u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::GlobalPos(value) => {
+                21u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+            EntityDataValue::PaintingVariant(value) => {
+                22u32.var_write_into(buf)?;
+                value.write_into(buf)
+            }
+        }
     }
 }
 
@@ -1,6 +1,7 @@
 use azalea_core::{Vec3, AABB};
+use azalea_registry::EntityType;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct EntityDimensions {
     pub width: f32,
     pub height: f32,
@@ -21,3 +22,132 @@ impl EntityDimensions {
         }
     }
 }
+
+impl From<EntityType> for EntityDimensions {
+    /// The size of an adult of this type of entity. Some entities, like
+    /// slimes, change size depending on their entity data, which isn't taken
+    /// into account.
+    fn from(kind: EntityType) -> Self {
+        let (width, height) = match kind {
+            EntityType::Allay => (0.35, 0.6),
+            EntityType::AreaEffectCloud => (6.0, 0.5),
+            EntityType::ArmorStand => (0.5, 1.975),
+            EntityType::Arrow => (0.5, 0.5),
+            EntityType::Axolotl => (0.75, 0.42),
+            EntityType::Bat => (0.5, 0.9),
+            EntityType::Bee => (0.7, 0.6),
+            EntityType::Blaze => (0.6, 1.8),
+            EntityType::Boat => (1.375, 0.5625),
+            EntityType::ChestBoat => (1.375, 0.5625),
+            EntityType::Cat => (0.6, 0.7),
+            EntityType::CaveSpider => (0.7, 0.5),
+            EntityType::Chicken => (0.4, 0.7),
+            EntityType::Cod => (0.5, 0.3),
+            EntityType::Cow => (0.9, 1.4),
+            EntityType::Creeper => (0.6, 1.7),
+            EntityType::Dolphin => (0.9, 0.6),
+            EntityType::Donkey => (1.3964844, 1.5),
+            EntityType::DragonFireball => (1.0, 1.0),
+            EntityType::Drowned => (0.6, 1.95),
+            EntityType::ElderGuardian => (1.9975, 1.9975),
+            EntityType::EndCrystal => (2.0, 2.0),
+            EntityType::EnderDragon => (16.0, 8.0),
+            EntityType::Enderman => (0.6, 2.9),
+            EntityType::Endermite => (0.4, 0.3),
+            EntityType::Evoker => (0.6, 1.95),
+            EntityType::EvokerFangs => (0.5, 0.8),
+            EntityType::ExperienceOrb => (0.5, 0.5),
+            EntityType::EyeOfEnder => (0.25, 0.25),
+            EntityType::FallingBlock => (0.98, 0.98),
+            EntityType::FireworkRocket => (0.25, 0.25),
+            EntityType::Fox => (0.6, 0.7),
+            EntityType::Frog => (0.5, 0.5),
+            EntityType::Ghast => (4.0, 4.0),
+            EntityType::Giant => (3.6, 12.0),
+            EntityType::GlowItemFrame => (0.5, 0.5),
+            EntityType::GlowSquid => (0.8, 0.8),
+            EntityType::Goat => (0.9, 1.3),
+            EntityType::Guardian => (0.85, 0.85),
+            EntityType::Hoglin => (1.3964844, 1.4),
+            EntityType::Horse => (1.3964844, 1.6),
+            EntityType::Husk => (0.6, 1.95),
+            EntityType::Illusioner => (0.6, 1.95),
+            EntityType::IronGolem => (1.4, 2.7),
+            EntityType::Item => (0.25, 0.25),
+            EntityType::ItemFrame => (0.5, 0.5),
+            EntityType::Fireball => (1.0, 1.0),
+            EntityType::LeashKnot => (0.375, 0.5),
+            EntityType::LightningBolt => (0.0, 0.0),
+            EntityType::Llama => (0.9, 1.87),
+            EntityType::LlamaSpit => (0.25, 0.25),
+            EntityType::MagmaCube => (2.04, 2.04),
+            EntityType::Marker => (0.0, 0.0),
+            EntityType::Minecart => (0.98, 0.7),
+            EntityType::ChestMinecart => (0.98, 0.7),
+            EntityType::CommandBlockMinecart => (0.98, 0.7),
+            EntityType::FurnaceMinecart => (0.98, 0.7),
+            EntityType::HopperMinecart => (0.98, 0.7),
+            EntityType::SpawnerMinecart => (0.98, 0.7),
+            EntityType::TntMinecart => (0.98, 0.7),
+            EntityType::Mule => (1.3964844, 1.6),
+            EntityType::Mooshroom => (0.9, 1.4),
+            EntityType::Ocelot => (0.6, 0.7),
+            EntityType::Painting => (0.5, 0.5),
+            EntityType::Panda => (1.3, 1.25),
+            EntityType::Parrot => (0.5, 0.9),
+            EntityType::Phantom => (0.9, 0.5),
+            EntityType::Pig => (0.9, 0.9),
+            EntityType::Piglin => (0.6, 1.95),
+            EntityType::PiglinBrute => (0.6, 1.95),
+            EntityType::Pillager => (0.6, 1.95),
+            EntityType::PolarBear => (1.4, 1.4),
+            EntityType::Tnt => (0.98, 0.98),
+            EntityType::Pufferfish => (0.7, 0.7),
+            EntityType::Rabbit => (0.4, 0.5),
+            EntityType::Ravager => (1.95, 2.2),
+            EntityType::Salmon => (0.7, 0.4),
+            EntityType::Sheep => (0.9, 1.3),
+            EntityType::Shulker => (1.0, 1.0),
+            EntityType::ShulkerBullet => (0.3125, 0.3125),
+            EntityType::Silverfish => (0.4, 0.3),
+            EntityType::Skeleton => (0.6, 1.99),
+            EntityType::SkeletonHorse => (1.3964844, 1.6),
+            EntityType::Slime => (2.04, 2.04),
+            EntityType::SmallFireball => (0.3125, 0.3125),
+            EntityType::SnowGolem => (0.7, 1.9),
+            EntityType::Snowball => (0.25, 0.25),
+            EntityType::SpectralArrow => (0.5, 0.5),
+            EntityType::Spider => (1.4, 0.9),
+            EntityType::Squid => (0.8, 0.8),
+            EntityType::Stray => (0.6, 1.99),
+            EntityType::Strider => (0.9, 1.7),
+            EntityType::Tadpole => (0.4, 0.3),
+            EntityType::Egg => (0.25, 0.25),
+            EntityType::EnderPearl => (0.25, 0.25),
+            EntityType::ExperienceBottle => (0.25, 0.25),
+            EntityType::Potion => (0.25, 0.25),
+            EntityType::Trident => (0.5, 0.5),
+            EntityType::TraderLlama => (0.9, 1.87),
+            EntityType::TropicalFish => (0.5, 0.4),
+            EntityType::Turtle => (1.2, 0.4),
+            EntityType::Vex => (0.4, 0.8),
+            EntityType::Villager => (0.6, 1.95),
+            EntityType::Vindicator => (0.6, 1.95),
+            EntityType::WanderingTrader => (0.6, 1.95),
+            EntityType::Warden => (0.9, 2.9),
+            EntityType::Witch => (0.6, 1.95),
+            EntityType::Wither => (0.9, 3.5),
+            EntityType::WitherSkeleton => (0.7, 2.4),
+            EntityType::WitherSkull => (0.3125, 0.3125),
+            EntityType::Wolf => (0.6, 0.85),
+            EntityType::Zoglin => (1.3964844, 1.4),
+            EntityType::Zombie => (0.6, 1.95),
+            EntityType::ZombieHorse => (1.3964844, 1.6),
+            EntityType::ZombieVillager => (0.6, 1.95),
+            EntityType::ZombifiedPiglin => (0.6, 1.95),
+            EntityType::Player => (0.6, 1.8),
+            EntityType::FishingBobber => (0.25, 0.25),
+        };
+        Self { width, height }
+    }
+}
@@ -3,9 +3,11 @@ mod dimensions;
 
 use crate::Dimension;
 use azalea_block::BlockState;
-use azalea_core::{BlockPos, Vec3, AABB};
+use azalea_core::{BlockPos, EquipmentSlot, Slot, Vec3, AABB};
+use azalea_registry::EntityType;
 pub use data::*;
 pub use dimensions::*;
+use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
 use uuid::Uuid;
@@ -124,23 +126,30 @@ impl<'d> EntityMut<'d> {
     }
 
     pub fn input_vector(&self, speed: f32, acceleration: &Vec3) -> Vec3 {
-        let distance = acceleration.length_squared();
-        if distance < 1.0E-7 {
-            return Vec3::default();
-        }
-        let acceleration = if distance > 1.0 {
-            acceleration.normalize()
-        } else {
-            *acceleration
-        }
-        .scale(speed as f64);
-        let y_rot = f32::sin(self.y_rot * 0.017453292f32);
-        let x_rot = f32::cos(self.y_rot * 0.017453292f32);
-        Vec3 {
-            x: acceleration.x * (x_rot as f64) - acceleration.z * (y_rot as f64),
-            y: acceleration.y,
-            z: acceleration.z * (x_rot as f64) + acceleration.x * (y_rot as f64),
-        }
+        input_vector(self.y_rot, speed, acceleration)
+    }
+}
+
+/// Turn a movement input relative to where an entity is facing (x is left
+/// and z is forward) into a movement in the world. The input is normalized if
+/// it's longer than 1, and then scaled by `speed`.
+pub fn input_vector(y_rot: f32, speed: f32, acceleration: &Vec3) -> Vec3 {
+    let distance = acceleration.length_squared();
+    if distance < 1.0E-7 {
+        return Vec3::default();
+    }
+    let acceleration = if distance > 1.0 {
+        acceleration.normalize()
+    } else {
+        *acceleration
+    }
+    .scale(speed as f64);
+    let y_rot_sin = f32::sin(y_rot * 0.017453292f32);
+    let y_rot_cos = f32::cos(y_rot * 0.017453292f32);
+    Vec3 {
+        x: acceleration.x * (y_rot_cos as f64) - acceleration.z * (y_rot_sin as f64),
+        y: acceleration.y,
+        z: acceleration.z * (y_rot_cos as f64) + acceleration.x * (y_rot_sin as f64),
     }
 }
 
@@ -230,9 +239,28 @@ impl Deref for EntityRef<'_> {
     }
 }
 
+/// How far above their feet a player's eyes are when they're standing.
+const PLAYER_EYE_HEIGHT: f64 = 1.62;
+/// How far above their feet a player's eyes are when they're sneaking.
+const PLAYER_SNEAKING_EYE_HEIGHT: f64 = 1.27;
+
+/// The index of the entity data field that has the shared flags, like
+/// whether the entity is on fire or invisible.
+const SHARED_FLAGS_INDEX: u8 = 0;
+const FLAG_ON_FIRE: u8 = 0;
+const FLAG_SHIFT_KEY_DOWN: u8 = 1;
+const FLAG_SPRINTING: u8 = 3;
+const FLAG_SWIMMING: u8 = 4;
+const FLAG_INVISIBLE: u8 = 5;
+const FLAG_GLOWING: u8 = 6;
+const FLAG_FALL_FLYING: u8 = 7;
+
 #[derive(Debug)]
 pub struct EntityData {
     pub uuid: Uuid,
+    /// What type of entity this is. Like the dimensions, this is assumed to
+    /// be a player until we're told otherwise.
+    pub kind: EntityType,
     /// The position of the entity right now.
     /// This can be changde with unsafe_move, but the correct way is with dimension.move_entity
     pos: Vec3,
@@ -255,6 +283,8 @@ pub struct EntityData {
 
     pub on_ground: bool,
     pub last_on_ground: bool,
+    /// How far the entity has fallen since it was last on the ground.
+    pub fall_distance: f32,
 
     /// The width and height of the entity.
     pub dimensions: EntityDimensions,
@@ -264,6 +294,26 @@ pub struct EntityData {
     /// Whether the entity will try to jump every tick
     /// (equivalent to the space key being held down in vanilla).
     pub jumping: bool,
+    /// Whether the entity is sneaking (equivalent to the shift key being held
+    /// down in vanilla). Sneaking players can't walk off the edges of blocks.
+    pub sneaking: bool,
+    /// Whether the entity is flying like players can in creative mode. Flying
+    /// entities aren't affected by gravity, and jumping and sneaking move
+    /// them up and down.
+    pub flying: bool,
+
+    /// How deep the entity's bounding box is in water, in blocks. This is
+    /// updated by azalea-physics every tick.
+    pub water_height: f64,
+    /// How deep the entity's bounding box is in lava, in blocks. This is
+    /// updated by azalea-physics every tick.
+    pub lava_height: f64,
+
+    /// The items the entity is holding and wearing, as far as the server
+    /// told us.
+    equipment: HashMap<EquipmentSlot, Slot>,
+    /// The flags from the first entity data field, as a bitfield.
+    shared_flags: u8,
 }
 
 impl EntityData {
@@ -275,6 +325,7 @@ impl EntityData {
 
         Self {
             uuid,
+            kind: EntityType::Player,
             pos,
             last_pos: pos,
             delta: Vec3::default(),
@@ -291,15 +342,171 @@ impl EntityData {
 
             on_ground: false,
             last_on_ground: false,
+            fall_distance: 0.,
 
             // TODO: have this be based on the entity type
             bounding_box: dimensions.make_bounding_box(&pos),
             dimensions,
 
             jumping: false,
+            sneaking: false,
+            flying: false,
+
+            water_height: 0.,
+            lava_height: 0.,
+
+            equipment: HashMap::new(),
+            shared_flags: 0,
+        }
+    }
+
+    /// The items the entity is holding and wearing. Slots that the server
+    /// never told us about aren't included.
+    pub fn equipment(&self) -> &HashMap<EquipmentSlot, Slot> {
+        &self.equipment
+    }
+
+    /// Set the item in one of the entity's equipment slots.
+    pub fn set_equipment(&mut self, slot: EquipmentSlot, item: Slot) {
+        self.equipment.insert(slot, item);
+    }
+
+    /// Update the entity with the entity data fields the server sent. Only
+    /// the shared flags are kept track of right now.
+    pub fn apply_metadata(&mut self, metadata: &EntityMetadata) {
+        for item in metadata.iter() {
+            if item.index == SHARED_FLAGS_INDEX {
+                if let EntityDataValue::Byte(flags) = item.value {
+                    self.shared_flags = flags;
+                }
+            }
+        }
+    }
+
+    fn shared_flag(&self, flag: u8) -> bool {
+        self.shared_flags & (1 << flag) != 0
+    }
+
+    /// Whether the entity is on fire.
+    pub fn is_on_fire(&self) -> bool {
+        self.shared_flag(FLAG_ON_FIRE)
+    }
+
+    /// Whether the server says the entity is holding shift. For our own
+    /// player, [`EntityData::sneaking`] is what we've told the server
+    /// instead.
+    pub fn is_shift_key_down(&self) -> bool {
+        self.shared_flag(FLAG_SHIFT_KEY_DOWN)
+    }
+
+    /// Whether the entity is sprinting.
+    pub fn is_sprinting(&self) -> bool {
+        self.shared_flag(FLAG_SPRINTING)
+    }
+
+    /// Whether the entity is swimming, like when a player sprints in water.
+    pub fn is_swimming(&self) -> bool {
+        self.shared_flag(FLAG_SWIMMING)
+    }
+
+    /// Whether the entity is invisible, usually from an invisibility potion.
+    pub fn is_invisible(&self) -> bool {
+        self.shared_flag(FLAG_INVISIBLE)
+    }
+
+    /// Whether the entity has the glowing outline, like from a spectral
+    /// arrow or the glowing effect.
+    pub fn is_glowing(&self) -> bool {
+        self.shared_flag(FLAG_GLOWING)
+    }
+
+    /// Whether the entity is flying with an elytra.
+    pub fn is_fall_flying(&self) -> bool {
+        self.shared_flag(FLAG_FALL_FLYING)
+    }
+
+    /// Change the type of the entity, and its size to match.
+    pub fn set_kind(&mut self, kind: EntityType) {
+        self.kind = kind;
+        self.dimensions = EntityDimensions::from(kind);
+        self.bounding_box = self.dimensions.make_bounding_box(&self.pos);
+    }
+
+    /// How far above its feet the entity's eyes are. This is vanilla's
+    /// default of 85% of the entity's height, except for the mobs that
+    /// override it. Babies are treated like adults.
+    pub fn eye_height(&self) -> f64 {
+        let height = self.dimensions.height as f64;
+        match self.kind {
+            EntityType::Player if self.sneaking => PLAYER_SNEAKING_EYE_HEIGHT,
+            EntityType::Player => PLAYER_EYE_HEIGHT,
+            EntityType::Zombie
+            | EntityType::Husk
+            | EntityType::Drowned
+            | EntityType::ZombieVillager
+            | EntityType::Piglin
+            | EntityType::Skeleton
+            | EntityType::Stray => 1.74,
+            EntityType::ZombifiedPiglin => 1.79,
+            EntityType::WitherSkeleton => 2.1,
+            EntityType::Enderman => 2.55,
+            EntityType::Villager | EntityType::WanderingTrader | EntityType::Witch => 1.62,
+            EntityType::Cow | EntityType::Mooshroom => 1.3,
+            EntityType::Ghast => 2.6,
+            EntityType::Spider => 0.65,
+            EntityType::CaveSpider => 0.45,
+            EntityType::Dolphin => 0.3,
+            EntityType::Shulker => 0.5,
+            EntityType::Silverfish | EntityType::Endermite => 0.13,
+            EntityType::Chicken => height * 0.92,
+            EntityType::Sheep => height * 0.95,
+            EntityType::Wolf => height * 0.8,
+            EntityType::Slime | EntityType::MagmaCube => height * 0.625,
+            EntityType::Bat
+            | EntityType::Squid
+            | EntityType::GlowSquid
+            | EntityType::Guardian
+            | EntityType::ElderGuardian => height * 0.5,
+            EntityType::Cod
+            | EntityType::Salmon
+            | EntityType::Pufferfish
+            | EntityType::TropicalFish
+            | EntityType::Tadpole => height * 0.65,
+            EntityType::Phantom => height * 0.35,
+            _ => height * 0.85,
+        }
+    }
+
+    /// The position of the entity's eyes.
+    pub fn eye_pos(&self) -> Vec3 {
+        Vec3 {
+            x: self.pos.x,
+            y: self.pos.y + self.eye_height(),
+            z: self.pos.z,
+        }
+    }
+
+    /// The unit vector pointing in the direction the entity is looking.
+    pub fn view_vector(&self) -> Vec3 {
+        let x_rot = self.x_rot.to_radians() as f64;
+        let y_rot = -self.y_rot.to_radians() as f64;
+        Vec3 {
+            x: y_rot.sin() * x_rot.cos(),
+            y: -x_rot.sin(),
+            z: y_rot.cos() * x_rot.cos(),
         }
     }
 
+    /// Whether any part of the entity is in water.
+    pub fn is_in_water(&self) -> bool {
+        self.water_height > 0.
+    }
+
+    /// Whether any part of the entity is in lava.
+    pub fn is_in_lava(&self) -> bool {
+        self.lava_height > 0.
+    }
+
     #[inline]
     pub fn pos(&self) -> &Vec3 {
         &self.pos
@@ -323,4 +530,37 @@ mod tests {
         let entity_ref: EntityRef = entity.into();
         assert_eq!(entity_ref.uuid, uuid);
     }
+
+    #[test]
+    fn test_shared_flags() {
+        let mut entity = EntityData::new(Uuid::nil(), Vec3::default());
+        assert!(!entity.is_glowing());
+        entity.apply_metadata(&EntityMetadata::from(vec![
+            EntityDataItem {
+                index: 0,
+                value: EntityDataValue::Byte(0b0110_0001),
+            },
+            EntityDataItem {
+                index: 9,
+                value: EntityDataValue::Float(20.),
+            },
+        ]));
+        assert!(entity.is_on_fire());
+        assert!(entity.is_invisible());
+        assert!(entity.is_glowing());
+        assert!(!entity.is_shift_key_down());
+        assert!(!entity.is_sprinting());
+
+        // fields that weren't sent don't change
+        entity.apply_metadata(&EntityMetadata::from(vec![EntityDataItem {
+            index: 9,
+            value: EntityDataValue::Float(10.),
+        }]));
+        assert!(entity.is_glowing());
+        entity.apply_metadata(&EntityMetadata::from(vec![EntityDataItem {
+            index: 0,
+            value: EntityDataValue::Byte(0),
+        }]));
+        assert!(!entity.is_glowing());
+    }
 }
@@ -32,21 +32,28 @@ impl EntityStorage {
         self.data_by_id.insert(id, entity);
     }
 
-    /// Remove an entity from the storage by its id.
+    /// Remove an entity from the storage by its id, returning it if it
+    /// existed.
     #[inline]
-    pub fn remove_by_id(&mut self, id: u32) {
-        if let Some(entity) = self.data_by_id.remove(&id) {
-            let entity_chunk = ChunkPos::from(entity.pos());
-            let entity_uuid = entity.uuid;
-            if self.id_by_chunk.remove(&entity_chunk).is_none() {
-                warn!("Tried to remove entity with id {id} from chunk {entity_chunk:?} but it was not found.");
+    pub fn remove_by_id(&mut self, id: u32) -> Option<EntityData> {
+        let entity = self.data_by_id.remove(&id)?;
+        let entity_chunk = ChunkPos::from(entity.pos());
+        let entity_uuid = entity.uuid;
+        match self.id_by_chunk.get_mut(&entity_chunk) {
+            Some(ids) => {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.id_by_chunk.remove(&entity_chunk);
+                }
             }
-            if self.id_by_uuid.remove(&entity_uuid).is_none() {
-                warn!("Tried to remove entity with id {id} from uuid {entity_uuid:?} but it was not found.");
+            None => {
+                warn!("Tried to remove entity with id {id} from chunk {entity_chunk:?} but it was not found.");
             }
-        } else {
-            warn!("Tried to remove entity with id {id} but it was not found.")
         }
+        if self.id_by_uuid.remove(&entity_uuid).is_none() {
+            warn!("Tried to remove entity with id {id} from uuid {entity_uuid:?} but it was not found.");
+        }
+        Some(entity)
     }
 
     /// Check if there is an entity that exists with the given id.
@@ -119,6 +126,12 @@ impl EntityStorage {
         self.data_by_id.values()
     }
 
+    /// Get an iterator over all entities and their ids.
+    #[inline]
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, u32, EntityData> {
+        self.data_by_id.iter()
+    }
+
     pub fn find_one_entity<F>(&self, mut f: F) -> Option<&EntityData>
     where
         F: FnMut(&EntityData) -> bool,
@@ -166,4 +179,18 @@ mod tests {
         storage.remove_by_id(0);
         assert!(storage.get_by_id(0).is_none());
     }
+
+    #[test]
+    fn test_remove_keeps_other_entities_in_chunk() {
+        let mut storage = EntityStorage::new();
+        storage.insert(0, EntityData::new(Uuid::from_u128(100), Vec3::default()));
+        storage.insert(1, EntityData::new(Uuid::from_u128(101), Vec3::default()));
+
+        assert!(storage.remove_by_id(0).is_some());
+        assert!(storage.remove_by_id(0).is_none());
+        let chunk = ChunkPos::new(0, 0);
+        assert!(storage
+            .find_one_entity_in_chunk(&chunk, |entity| entity.uuid == Uuid::from_u128(101))
+            .is_some());
+    }
 }
@@ -1,18 +1,23 @@
 #![feature(int_roundings)]
 
 mod bit_storage;
+mod block_entity;
 mod chunk_storage;
 pub mod entity;
 mod entity_storage;
+pub mod mining;
 mod palette;
+mod registry_holder;
 
 use azalea_block::BlockState;
 use azalea_buf::BufReadError;
 use azalea_core::{BlockPos, ChunkPos, PositionDelta8, Vec3};
 pub use bit_storage::BitStorage;
+pub use block_entity::BlockEntity;
 pub use chunk_storage::{Chunk, ChunkStorage};
 use entity::{EntityData, EntityMut, EntityRef};
 pub use entity_storage::EntityStorage;
+pub use registry_holder::{Biome, DimensionType, RegistryHolder, RegistryHolderError};
 use std::{
     io::Cursor,
     ops::{Index, IndexMut},
@@ -69,6 +74,18 @@ impl Dimension {
         self.chunk_storage.set_block_state(pos, state, self.min_y())
     }
 
+    /// Get the block entity at the given position, like a chest or sign. This
+    /// is cloned since the chunk it's in is behind a lock.
+    pub fn block_entity(&self, pos: &BlockPos) -> Option<BlockEntity> {
+        self.chunk_storage.get_block_entity(pos)
+    }
+
+    /// Set the block entity at the given position. Returns `false` if the
+    /// chunk isn't loaded.
+    pub fn set_block_entity(&mut self, pos: &BlockPos, block_entity: BlockEntity) -> bool {
+        self.chunk_storage.set_block_entity(pos, block_entity)
+    }
+
     pub fn set_entity_pos(&mut self, entity_id: u32, new_pos: Vec3) -> Result<(), MoveEntityError> {
         let mut entity = self
             .entity_mut(entity_id)
@@ -111,6 +128,11 @@ impl Dimension {
         self.entity_storage.insert(id, entity);
     }
 
+    /// Remove an entity, returning it if it existed.
+    pub fn remove_entity(&mut self, id: u32) -> Option<EntityData> {
+        self.entity_storage.remove_by_id(id)
+    }
+
     pub fn height(&self) -> u32 {
         self.chunk_storage.height
     }
@@ -155,6 +177,23 @@ impl Dimension {
     {
         self.entity_storage.find_one_entity(|entity| f(entity))
     }
+
+    /// Find the entity closest to `pos` that matches the filter, and return
+    /// its id and distance. If several entities are equally close, the one
+    /// with the lowest id wins.
+    pub fn closest_entity<F>(&self, pos: &Vec3, mut f: F) -> Option<(u32, &EntityData, f64)>
+    where
+        F: FnMut(u32, &EntityData) -> bool,
+    {
+        self.entity_storage
+            .iter()
+            .filter(|(&id, entity)| f(id, entity))
+            .map(|(&id, entity)| (id, entity, entity.pos().distance_squared_to(pos)))
+            .min_by(|(a_id, _, a_dist), (b_id, _, b_dist)| {
+                a_dist.total_cmp(b_dist).then(a_id.cmp(b_id))
+            })
+            .map(|(id, entity, dist_sqr)| (id, entity, dist_sqr.sqrt()))
+    }
 }
 
 impl Index<&ChunkPos> for Dimension {
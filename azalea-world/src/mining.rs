@@ -0,0 +1,506 @@
+//! Which tools can mine which blocks, and how fast.
+//!
+//! Vanilla decides this with block tags like `minecraft:mineable/pickaxe` and
+//! `minecraft:needs_iron_tool`, which we don't have yet. The tool for a block
+//! is guessed from the words in its name, and the blocks that need a better
+//! tool are listed explicitly, copied from the `needs_*_tool` tags.
+// TODO: generate this from the block tags
+
+use azalea_block::{Block, BlockState};
+use azalea_registry::Item;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToolKind {
+    Pickaxe,
+    Axe,
+    Shovel,
+    Hoe,
+    Sword,
+    Shears,
+}
+
+/// The material a tool is made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToolTier {
+    Wood,
+    Stone,
+    Iron,
+    Diamond,
+    Netherite,
+    Gold,
+}
+
+impl ToolTier {
+    /// How fast tools of this tier mine the blocks they're made for. Using
+    /// the wrong tool (or no tool) has a speed of 1.
+    pub fn speed(self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.,
+            ToolTier::Stone => 4.,
+            ToolTier::Iron => 6.,
+            ToolTier::Diamond => 8.,
+            ToolTier::Netherite => 9.,
+            ToolTier::Gold => 12.,
+        }
+    }
+
+    /// The mining level of the tier. Blocks like diamond ore only drop items
+    /// if they're mined with a tool that has a high enough level.
+    pub fn level(self) -> u8 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3,
+            ToolTier::Netherite => 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Tool {
+    pub kind: ToolKind,
+    /// The tier of the tool. This is `None` for shears.
+    pub tier: Option<ToolTier>,
+}
+
+impl Tool {
+    /// Get the tool that an item is, or `None` if the item isn't a tool.
+    pub fn from_item(item: Item) -> Option<Tool> {
+        use ToolKind::*;
+        use ToolTier::*;
+        let (kind, tier) = match item {
+            Item::WoodenPickaxe => (Pickaxe, Wood),
+            Item::StonePickaxe => (Pickaxe, Stone),
+            Item::IronPickaxe => (Pickaxe, Iron),
+            Item::DiamondPickaxe => (Pickaxe, Diamond),
+            Item::NetheritePickaxe => (Pickaxe, Netherite),
+            Item::GoldenPickaxe => (Pickaxe, Gold),
+            Item::WoodenAxe => (Axe, Wood),
+            Item::StoneAxe => (Axe, Stone),
+            Item::IronAxe => (Axe, Iron),
+            Item::DiamondAxe => (Axe, Diamond),
+            Item::NetheriteAxe => (Axe, Netherite),
+            Item::GoldenAxe => (Axe, Gold),
+            Item::WoodenShovel => (Shovel, Wood),
+            Item::StoneShovel => (Shovel, Stone),
+            Item::IronShovel => (Shovel, Iron),
+            Item::DiamondShovel => (Shovel, Diamond),
+            Item::NetheriteShovel => (Shovel, Netherite),
+            Item::GoldenShovel => (Shovel, Gold),
+            Item::WoodenHoe => (Hoe, Wood),
+            Item::StoneHoe => (Hoe, Stone),
+            Item::IronHoe => (Hoe, Iron),
+            Item::DiamondHoe => (Hoe, Diamond),
+            Item::NetheriteHoe => (Hoe, Netherite),
+            Item::GoldenHoe => (Hoe, Gold),
+            Item::WoodenSword => (Sword, Wood),
+            Item::StoneSword => (Sword, Stone),
+            Item::IronSword => (Sword, Iron),
+            Item::DiamondSword => (Sword, Diamond),
+            Item::NetheriteSword => (Sword, Netherite),
+            Item::GoldenSword => (Sword, Gold),
+            Item::Shears => {
+                return Some(Tool {
+                    kind: Shears,
+                    tier: None,
+                })
+            }
+            _ => return None,
+        };
+        Some(Tool {
+            kind,
+            tier: Some(tier),
+        })
+    }
+}
+
+/// The names of the wood types, since everything made of them is mined with
+/// an axe.
+const WOOD_TYPES: &[&str] = &[
+    "oak", "spruce", "birch", "jungle", "acacia", "mangrove", "crimson", "warped",
+];
+
+/// Get the kind of tool that mines the block faster, or `None` if no tool
+/// helps.
+pub fn mineable_with(block: BlockState) -> Option<ToolKind> {
+    let id = Box::<dyn Block>::from(block).id();
+    mineable_with_id(id)
+}
+
+fn mineable_with_id(id: &str) -> Option<ToolKind> {
+    if id.starts_with("potted_")
+        || id.ends_with("_sapling")
+        || id.ends_with("_fungus")
+        || id.ends_with("_roots") && id != "mangrove_roots" && id != "muddy_mangrove_roots"
+        || id.starts_with("redstone_") && !id.ends_with("_ore") && id != "redstone_block"
+        || id.starts_with("infested_")
+        || matches!(id, "glowstone" | "sea_lantern" | "spore_blossom")
+    {
+        return None;
+    }
+
+    if id.ends_with("_leaves")
+        || id.ends_with("wart_block")
+        || id.starts_with("sculk")
+        || id.starts_with("moss_")
+        || matches!(
+            id,
+            "hay_block" | "sponge" | "wet_sponge" | "shroomlight" | "target" | "dried_kelp_block"
+        )
+    {
+        return Some(ToolKind::Hoe);
+    }
+
+    if id.contains("dirt")
+        || id.ends_with("concrete_powder")
+        || (id.ends_with("sand") && !id.ends_with("sandstone"))
+        || matches!(
+            id,
+            "grass_block"
+                | "gravel"
+                | "clay"
+                | "snow"
+                | "snow_block"
+                | "powder_snow"
+                | "soul_soil"
+                | "farmland"
+                | "mycelium"
+                | "podzol"
+                | "mud"
+                | "muddy_mangrove_roots"
+        )
+    {
+        return Some(ToolKind::Shovel);
+    }
+
+    if id == "cobweb" {
+        return Some(ToolKind::Sword);
+    }
+
+    let is_wooden = WOOD_TYPES.iter().any(|wood| id.contains(wood))
+        && !id.ends_with("_nylium")
+        && !id.ends_with("_wart_block");
+    if is_wooden
+        || id.ends_with("_log")
+        || id.ends_with("_wood")
+        || id.ends_with("_stem")
+        || id.ends_with("mushroom_block")
+        || id.starts_with("bamboo")
+        || (id.ends_with("chest") && id != "ender_chest")
+        || id.ends_with("campfire")
+        || matches!(
+            id,
+            "crafting_table"
+                | "bookshelf"
+                | "barrel"
+                | "ladder"
+                | "pumpkin"
+                | "carved_pumpkin"
+                | "jack_o_lantern"
+                | "melon"
+                | "note_block"
+                | "jukebox"
+                | "lectern"
+                | "composter"
+                | "loom"
+                | "cartography_table"
+                | "fletching_table"
+                | "smithing_table"
+                | "beehive"
+                | "bee_nest"
+                | "cocoa"
+                | "vine"
+                | "daylight_detector"
+        )
+    {
+        return Some(ToolKind::Axe);
+    }
+
+    const PICKAXE_WORDS: &[&str] = &[
+        "stone",
+        "cobblestone",
+        "sandstone",
+        "blackstone",
+        "ore",
+        "brick",
+        "bricks",
+        "deepslate",
+        "andesite",
+        "diorite",
+        "granite",
+        "basalt",
+        "tuff",
+        "calcite",
+        "dripstone",
+        "netherrack",
+        "nylium",
+        "obsidian",
+        "terracotta",
+        "concrete",
+        "purpur",
+        "prismarine",
+        "quartz",
+        "copper",
+        "amethyst",
+        "iron",
+        "anvil",
+        "furnace",
+        "smoker",
+        "rail",
+        "ice",
+        "lantern",
+        "cauldron",
+    ];
+    // match whole words so spore_blossom doesn't count as an ore
+    if id.split('_').any(|word| PICKAXE_WORDS.contains(&word))
+        || id.ends_with("_block") && !id.contains("slime") && !id.contains("honey")
+        || matches!(
+            id,
+            "ancient_debris"
+                | "ender_chest"
+                | "hopper"
+                | "bell"
+                | "observer"
+                | "dispenser"
+                | "dropper"
+                | "spawner"
+                | "enchanting_table"
+                | "lodestone"
+                | "respawn_anchor"
+                | "chain"
+                | "conduit"
+                | "piston"
+                | "sticky_piston"
+                | "piston_head"
+                | "brewing_stand"
+                | "grindstone"
+                | "stonecutter"
+                | "lightning_rod"
+                | "light_weighted_pressure_plate"
+                | "heavy_weighted_pressure_plate"
+        )
+    {
+        return Some(ToolKind::Pickaxe);
+    }
+
+    None
+}
+
+/// The blocks in the `minecraft:needs_diamond_tool` tag.
+const NEEDS_DIAMOND_TOOL: &[&str] = &[
+    "obsidian",
+    "crying_obsidian",
+    "netherite_block",
+    "respawn_anchor",
+    "ancient_debris",
+];
+
+/// The blocks in the `minecraft:needs_iron_tool` tag.
+const NEEDS_IRON_TOOL: &[&str] = &[
+    "diamond_block",
+    "diamond_ore",
+    "deepslate_diamond_ore",
+    "emerald_ore",
+    "deepslate_emerald_ore",
+    "emerald_block",
+    "gold_block",
+    "raw_gold_block",
+    "gold_ore",
+    "deepslate_gold_ore",
+    "redstone_ore",
+    "deepslate_redstone_ore",
+];
+
+/// The blocks in the `minecraft:needs_stone_tool` tag, except for the copper
+/// blocks since there are so many of them.
+const NEEDS_STONE_TOOL: &[&str] = &[
+    "iron_block",
+    "raw_iron_block",
+    "iron_ore",
+    "deepslate_iron_ore",
+    "lapis_block",
+    "lapis_ore",
+    "deepslate_lapis_ore",
+    "lightning_rod",
+];
+
+/// Blocks that are mined faster with a pickaxe, but still drop their items
+/// when they're mined with anything.
+const PICKAXE_NOT_REQUIRED: &[&str] = &[
+    "rail",
+    "powered_rail",
+    "detector_rail",
+    "activator_rail",
+    "stone_button",
+    "polished_blackstone_button",
+    "piston",
+    "sticky_piston",
+    "piston_head",
+    "pointed_dripstone",
+    "conduit",
+    "ice",
+    "packed_ice",
+    "blue_ice",
+    "frosted_ice",
+    "small_amethyst_bud",
+    "medium_amethyst_bud",
+    "large_amethyst_bud",
+    "amethyst_cluster",
+];
+
+/// Get the mining level a tool has to have for the block to drop anything
+/// when it's mined, or `None` if the block drops its items when it's mined
+/// with anything.
+pub fn required_level(block: BlockState) -> Option<u8> {
+    let id = Box::<dyn Block>::from(block).id();
+    required_level_id(id)
+}
+
+fn required_level_id(id: &str) -> Option<u8> {
+    if mineable_with_id(id) != Some(ToolKind::Pickaxe) || PICKAXE_NOT_REQUIRED.contains(&id) {
+        return None;
+    }
+    if NEEDS_DIAMOND_TOOL.contains(&id) {
+        return Some(ToolTier::Diamond.level());
+    }
+    if NEEDS_IRON_TOOL.contains(&id) {
+        return Some(ToolTier::Iron.level());
+    }
+    if NEEDS_STONE_TOOL.contains(&id) || id.split('_').any(|word| word == "copper") {
+        return Some(ToolTier::Stone.level());
+    }
+    Some(ToolTier::Wood.level())
+}
+
+/// Whether the block drops its items if it's mined with the given tool.
+/// `None` means mining with our hand or an item that isn't a tool.
+pub fn can_harvest(tool: Option<Tool>, block: BlockState) -> bool {
+    let required_level = match required_level(block) {
+        Some(level) => level,
+        None => return true,
+    };
+    match tool {
+        Some(Tool {
+            kind: ToolKind::Pickaxe,
+            tier: Some(tier),
+        }) => tier.level() >= required_level,
+        _ => false,
+    }
+}
+
+/// How fast the tool mines the block. This is 1 for tools that don't help,
+/// and doesn't include enchantments or effects.
+pub fn destroy_speed(tool: Option<Tool>, block: BlockState) -> f32 {
+    let tool = match tool {
+        Some(tool) => tool,
+        None => return 1.,
+    };
+    let mineable_with = mineable_with(block);
+    match tool.kind {
+        ToolKind::Shears => {
+            let id = Box::<dyn Block>::from(block).id();
+            if id == "cobweb" || id.ends_with("_leaves") {
+                15.
+            } else if id.ends_with("_wool") {
+                5.
+            } else {
+                1.
+            }
+        }
+        ToolKind::Sword => {
+            if mineable_with == Some(ToolKind::Sword) {
+                15.
+            } else {
+                1.
+            }
+        }
+        kind if Some(kind) == mineable_with => tool.tier.map(ToolTier::speed).unwrap_or(1.),
+        _ => 1.,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azalea_block::{
+        PistonBlock, PointedDripstoneBlock, PoweredRailBlock, RailBlock, StoneButtonBlock,
+        StonePressurePlateBlock,
+    };
+
+    fn tool(item: Item) -> Option<Tool> {
+        Tool::from_item(item)
+    }
+
+    #[test]
+    fn test_mineable_with() {
+        assert_eq!(mineable_with(BlockState::Stone), Some(ToolKind::Pickaxe));
+        assert_eq!(
+            mineable_with(BlockState::DiamondOre),
+            Some(ToolKind::Pickaxe)
+        );
+        assert_eq!(
+            mineable_with(BlockState::GrassBlock_False),
+            Some(ToolKind::Shovel)
+        );
+        assert_eq!(mineable_with(BlockState::Sand), Some(ToolKind::Shovel));
+        assert_eq!(mineable_with(BlockState::OakPlanks), Some(ToolKind::Axe));
+        assert_eq!(mineable_with(BlockState::Air), None);
+        assert_eq!(mineable_with(BlockState::SporeBlossom), None);
+        assert_eq!(mineable_with(BlockState::SeaLantern), None);
+        assert_eq!(
+            mineable_with(BlockState::from(StoneButtonBlock::default())),
+            Some(ToolKind::Pickaxe)
+        );
+    }
+
+    #[test]
+    fn test_can_harvest() {
+        assert!(can_harvest(None, BlockState::OakPlanks));
+        assert!(!can_harvest(None, BlockState::Stone));
+        assert!(can_harvest(tool(Item::WoodenPickaxe), BlockState::Stone));
+        assert!(!can_harvest(
+            tool(Item::StonePickaxe),
+            BlockState::DiamondOre
+        ));
+        assert!(can_harvest(tool(Item::IronPickaxe), BlockState::DiamondOre));
+        assert!(!can_harvest(tool(Item::IronPickaxe), BlockState::Obsidian));
+        assert!(!can_harvest(
+            tool(Item::WoodenPickaxe),
+            BlockState::IronBlock
+        ));
+        assert!(can_harvest(tool(Item::StonePickaxe), BlockState::IronBlock));
+    }
+
+    #[test]
+    fn test_can_harvest_by_hand() {
+        for block in [
+            BlockState::from(RailBlock::default()),
+            BlockState::from(PoweredRailBlock::default()),
+            BlockState::from(StoneButtonBlock::default()),
+            BlockState::from(PistonBlock::default()),
+            BlockState::from(PointedDripstoneBlock::default()),
+            BlockState::SeaLantern,
+            BlockState::SporeBlossom,
+        ] {
+            assert!(can_harvest(None, block), "{:?}", block);
+        }
+        assert!(!can_harvest(
+            None,
+            BlockState::from(StonePressurePlateBlock::default())
+        ));
+        assert!(!can_harvest(None, BlockState::DripstoneBlock));
+    }
+
+    #[test]
+    fn test_destroy_speed() {
+        assert_eq!(destroy_speed(None, BlockState::Stone), 1.);
+        assert_eq!(destroy_speed(tool(Item::IronAxe), BlockState::Stone), 1.);
+        assert_eq!(
+            destroy_speed(tool(Item::IronPickaxe), BlockState::Stone),
+            6.
+        );
+        assert_eq!(destroy_speed(tool(Item::Shears), BlockState::Cobweb), 15.);
+        assert_eq!(destroy_speed(tool(Item::Apple), BlockState::Stone), 1.);
+    }
+}
@@ -1,4 +1,5 @@
-use azalea_buf::{BufReadError, McBufReadable, McBufVarReadable, McBufWritable};
+use azalea_block::BlockState;
+use azalea_buf::{BufReadError, McBufReadable, McBufVarReadable, McBufVarWritable, McBufWritable};
 use std::io::{Cursor, Write};
 
 use crate::BitStorage;
@@ -97,6 +98,13 @@ impl PalettedContainer {
     fn create_or_reuse_data(&self, bits_per_entry: u8) -> PalettedContainer {
         let new_palette_type =
             PaletteType::from_bits_and_type(bits_per_entry, &self.container_type);
+        // the global palette stores the ids directly, so it needs enough bits
+        // for every possible id
+        let bits_per_entry = if new_palette_type == PaletteType::Global {
+            self.container_type.global_bits()
+        } else {
+            bits_per_entry
+        };
         // note for whoever is trying to optimize this: vanilla has this
         // but it causes a stack overflow since it's not changing the bits per entry
         // i don't know how to fix this properly so glhf
@@ -121,9 +129,6 @@ impl PalettedContainer {
     }
 
     fn on_resize(&mut self, bits_per_entry: u8, value: u32) -> usize {
-        if bits_per_entry > 5 {
-            panic!("bits_per_entry must be <= 5");
-        }
         let mut new_data = self.create_or_reuse_data(bits_per_entry);
         new_data.copy_from(&self.palette, &self.storage);
         *self = new_data;
@@ -225,13 +230,13 @@ impl McBufWritable for Palette {
     fn write_into(&self, buf: &mut impl Write) -> Result<(), std::io::Error> {
         match self {
             Palette::SingleValue(value) => {
-                value.write_into(buf)?;
+                value.var_write_into(buf)?;
             }
             Palette::Linear(values) => {
-                values.write_into(buf)?;
+                values.var_write_into(buf)?;
             }
             Palette::Hashmap(values) => {
-                values.write_into(buf)?;
+                values.var_write_into(buf)?;
             }
             Palette::Global => {}
         }
@@ -297,6 +302,18 @@ impl PalettedContainerType {
     fn size(&self) -> usize {
         1 << (self.size_bits() * 3)
     }
+
+    /// The number of bits that the global palette uses for each entry, which
+    /// is enough to fit any id in the registry.
+    fn global_bits(&self) -> u8 {
+        match self {
+            PalettedContainerType::BlockStates => {
+                (u32::BITS - BlockState::max_state().leading_zeros()) as u8
+            }
+            // there are 64 biomes in vanilla
+            PalettedContainerType::Biomes => 6,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -357,4 +374,122 @@ mod tests {
         palette_container.set_at_index(16, 16); // 5 bits
         assert_eq!(palette_container.bits_per_entry, 5);
     }
+
+    #[test]
+    fn test_resize_to_global() {
+        let mut palette_container =
+            PalettedContainer::new(&PalettedContainerType::BlockStates).unwrap();
+
+        for i in 0..300 {
+            palette_container.set_at_index(i, i as u32 * 50);
+        }
+        assert_eq!(
+            PaletteType::from(&palette_container.palette),
+            PaletteType::Global
+        );
+        assert_eq!(palette_container.bits_per_entry, 15);
+        for i in 0..300 {
+            assert_eq!(palette_container.get_at_index(i), i as u32 * 50);
+        }
+    }
+
+    fn read(data: &[u8], container_type: &'static PalettedContainerType) -> PalettedContainer {
+        PalettedContainer::read_with_type(&mut Cursor::new(data), container_type).unwrap()
+    }
+
+    #[test]
+    fn test_read_single_value() {
+        // 0 bits per entry, the value is 1 (stone), and no data
+        let container = read(&[0, 1, 0], &PalettedContainerType::BlockStates);
+        assert_eq!(
+            PaletteType::from(&container.palette),
+            PaletteType::SingleValue
+        );
+        assert_eq!(container.get(0, 0, 0), 1);
+        assert_eq!(container.get(15, 15, 15), 1);
+    }
+
+    #[test]
+    fn test_read_indirect() {
+        let mut buf = Vec::new();
+        // 4 bits per entry with a palette of air and stone
+        4u8.write_into(&mut buf).unwrap();
+        vec![0u8, 1].write_into(&mut buf).unwrap();
+        // 16 entries fit in each long, and the first long makes every other
+        // block stone
+        let mut data = vec![0u64; 4096 / 16];
+        data[0] = 0x0101_0101_0101_0101;
+        data.write_into(&mut buf).unwrap();
+
+        let container = read(&buf, &PalettedContainerType::BlockStates);
+        assert_eq!(PaletteType::from(&container.palette), PaletteType::Linear);
+        assert_eq!(container.get(0, 0, 0), 1);
+        assert_eq!(container.get(1, 0, 0), 0);
+        assert_eq!(container.get(14, 0, 0), 1);
+        assert_eq!(container.get(15, 0, 0), 0);
+        assert_eq!(container.get(0, 0, 1), 0);
+    }
+
+    #[test]
+    fn test_read_direct() {
+        let mut buf = Vec::new();
+        15u8.write_into(&mut buf).unwrap();
+        // 4 entries fit in each long and the remaining 4 bits are unused, so
+        // the fifth entry starts in the second long
+        let mut data = vec![0u64; 4096 / 4];
+        data[0] = 1 | (20000 << 45);
+        data[1] = 9;
+        data.write_into(&mut buf).unwrap();
+
+        let container = read(&buf, &PalettedContainerType::BlockStates);
+        assert_eq!(PaletteType::from(&container.palette), PaletteType::Global);
+        assert_eq!(container.get_at_index(0), 1);
+        assert_eq!(container.get_at_index(3), 20000);
+        assert_eq!(container.get_at_index(4), 9);
+        assert_eq!(container.get_at_index(5), 0);
+    }
+
+    #[test]
+    fn test_read_biomes() {
+        let mut buf = Vec::new();
+        // biome containers use a linear palette up to 3 bits
+        3u8.write_into(&mut buf).unwrap();
+        vec![0u8, 5, 7].write_into(&mut buf).unwrap();
+        // 21 entries fit in each long
+        let mut data = vec![0u64; 64usize.div_ceil(21)];
+        data[0] = 1 | (2 << 60);
+        data[1] = 2;
+        data.write_into(&mut buf).unwrap();
+
+        let container = read(&buf, &PalettedContainerType::Biomes);
+        assert_eq!(PaletteType::from(&container.palette), PaletteType::Linear);
+        assert_eq!(container.get_at_index(0), 5);
+        assert_eq!(container.get_at_index(20), 7);
+        assert_eq!(container.get_at_index(21), 7);
+        assert_eq!(container.get(3, 3, 3), 0);
+
+        // but anything more than that uses the global palette
+        let mut buf = Vec::new();
+        6u8.write_into(&mut buf).unwrap();
+        vec![0u64; 64usize.div_ceil(10)]
+            .write_into(&mut buf)
+            .unwrap();
+        let container = read(&buf, &PalettedContainerType::Biomes);
+        assert_eq!(PaletteType::from(&container.palette), PaletteType::Global);
+    }
+
+    #[test]
+    fn test_write_read_round_trip() {
+        let mut container = PalettedContainer::new(&PalettedContainerType::BlockStates).unwrap();
+        for i in 0..40 {
+            container.set_at_index(i * 100, i as u32);
+        }
+        let mut buf = Vec::new();
+        container.write_into(&mut buf).unwrap();
+
+        let read_container = read(&buf, &PalettedContainerType::BlockStates);
+        for i in 0..4096 {
+            assert_eq!(read_container.get_at_index(i), container.get_at_index(i));
+        }
+    }
 }
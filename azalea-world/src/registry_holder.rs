@@ -0,0 +1,286 @@
+//! The registries the server sends when we join, which say what dimension
+//! types and biomes exist.
+
+use ahash::AHashMap;
+use azalea_core::ResourceLocation;
+use azalea_nbt::Tag;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RegistryHolderError {
+    #[error("Missing tag {0}")]
+    MissingTag(String),
+    #[error("Tag {0} has the wrong type")]
+    WrongType(String),
+    #[error("Invalid resource location {0}")]
+    InvalidName(String),
+}
+
+/// The properties of a type of dimension, like how tall it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DimensionType {
+    /// The id of the dimension type in the registry.
+    pub id: u32,
+    /// The lowest y coordinate blocks can be at.
+    pub min_y: i32,
+    /// How many blocks tall the dimension is, starting at `min_y`.
+    pub height: u32,
+    /// The height that things like nether portals and chorus fruit can
+    /// teleport to.
+    pub logical_height: u32,
+    pub has_skylight: bool,
+    pub has_ceiling: bool,
+    /// Whether water evaporates and lava spreads faster, like in the nether.
+    pub ultrawarm: bool,
+    pub natural: bool,
+    /// How much coordinates are multiplied by when moving to this dimension,
+    /// which is 8 in the nether.
+    pub coordinate_scale: f64,
+}
+
+impl DimensionType {
+    /// The highest y coordinate blocks can be at, plus one.
+    pub fn max_y(&self) -> i32 {
+        self.min_y + self.height as i32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Biome {
+    pub name: ResourceLocation,
+    /// Whether it rains or snows in the biome, like `rain`, `snow`, or
+    /// `none`.
+    pub precipitation: String,
+    pub temperature: f32,
+    pub downfall: f32,
+}
+
+/// The registries from the login packet that we need to understand the
+/// world, like how tall each dimension is and which biome each id is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegistryHolder {
+    pub dimension_types: HashMap<ResourceLocation, DimensionType>,
+    pub biomes: HashMap<u32, Biome>,
+}
+
+impl RegistryHolder {
+    /// Parse the registries from the `registry_holder` tag in the login
+    /// packet. Registries we don't use are ignored.
+    pub fn from_nbt(tag: &Tag) -> Result<Self, RegistryHolderError> {
+        let mut root = compound(tag, "registry holder")?;
+        // the root compound has an empty name, so it's wrapped in another
+        // compound when it's read
+        if let Some(inner) = root.get("") {
+            root = compound(inner, "\"\"")?;
+        }
+
+        let mut dimension_types = HashMap::new();
+        for entry in registry_entries(root, "minecraft:dimension_type")? {
+            let (name, id, element) = entry?;
+            dimension_types.insert(
+                name,
+                DimensionType {
+                    id,
+                    min_y: get_int(element, "min_y")?,
+                    height: get_int(element, "height")? as u32,
+                    logical_height: get_int(element, "logical_height")? as u32,
+                    has_skylight: get_bool(element, "has_skylight")?,
+                    has_ceiling: get_bool(element, "has_ceiling")?,
+                    ultrawarm: get_bool(element, "ultrawarm")?,
+                    natural: get_bool(element, "natural")?,
+                    coordinate_scale: match get(element, "coordinate_scale")? {
+                        Tag::Double(scale) => *scale,
+                        Tag::Float(scale) => *scale as f64,
+                        _ => return Err(RegistryHolderError::WrongType("coordinate_scale".into())),
+                    },
+                },
+            );
+        }
+
+        let mut biomes = HashMap::new();
+        for entry in registry_entries(root, "minecraft:worldgen/biome")? {
+            let (name, id, element) = entry?;
+            biomes.insert(
+                id,
+                Biome {
+                    name,
+                    precipitation: get(element, "precipitation")?
+                        .as_string()
+                        .ok_or_else(|| RegistryHolderError::WrongType("precipitation".into()))?
+                        .to_string(),
+                    temperature: get_float(element, "temperature")?,
+                    downfall: get_float(element, "downfall")?,
+                },
+            );
+        }
+
+        Ok(RegistryHolder {
+            dimension_types,
+            biomes,
+        })
+    }
+
+    pub fn dimension_type(&self, name: &ResourceLocation) -> Option<&DimensionType> {
+        self.dimension_types.get(name)
+    }
+
+    pub fn biome(&self, id: u32) -> Option<&Biome> {
+        self.biomes.get(&id)
+    }
+}
+
+type Compound = AHashMap<String, Tag>;
+
+fn compound<'a>(tag: &'a Tag, name: &str) -> Result<&'a Compound, RegistryHolderError> {
+    tag.as_compound()
+        .ok_or_else(|| RegistryHolderError::WrongType(name.to_string()))
+}
+
+fn get<'a>(compound: &'a Compound, name: &str) -> Result<&'a Tag, RegistryHolderError> {
+    compound
+        .get(name)
+        .ok_or_else(|| RegistryHolderError::MissingTag(name.to_string()))
+}
+
+fn get_int(compound: &Compound, name: &str) -> Result<i32, RegistryHolderError> {
+    get(compound, name)?
+        .as_int()
+        .copied()
+        .ok_or_else(|| RegistryHolderError::WrongType(name.to_string()))
+}
+
+fn get_float(compound: &Compound, name: &str) -> Result<f32, RegistryHolderError> {
+    get(compound, name)?
+        .as_float()
+        .copied()
+        .ok_or_else(|| RegistryHolderError::WrongType(name.to_string()))
+}
+
+fn get_bool(compound: &Compound, name: &str) -> Result<bool, RegistryHolderError> {
+    get(compound, name)?
+        .as_byte()
+        .map(|b| *b != 0)
+        .ok_or_else(|| RegistryHolderError::WrongType(name.to_string()))
+}
+
+/// Get the name, id, and element of every entry in a registry.
+fn registry_entries<'a>(
+    root: &'a Compound,
+    registry: &str,
+) -> Result<
+    impl Iterator<Item = Result<(ResourceLocation, u32, &'a Compound), RegistryHolderError>>,
+    RegistryHolderError,
+> {
+    let entries = get(compound(get(root, registry)?, registry)?, "value")?
+        .as_list()
+        .ok_or_else(|| RegistryHolderError::WrongType("value".to_string()))?;
+    Ok(entries.iter().map(|entry| {
+        let entry = compound(entry, "registry entry")?;
+        let name = get(entry, "name")?
+            .as_string()
+            .ok_or_else(|| RegistryHolderError::WrongType("name".to_string()))?;
+        let name = ResourceLocation::new(name)
+            .map_err(|_| RegistryHolderError::InvalidName(name.to_string()))?;
+        let id = get_int(entry, "id")? as u32;
+        let element = compound(get(entry, "element")?, "element")?;
+        Ok((name, id, element))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(name: &'static str, entries: Vec<(&str, Tag)>) -> (&'static str, Tag) {
+        (
+            name,
+            Tag::from(vec![
+                ("type", Tag::String(name.to_string())),
+                (
+                    "value",
+                    Tag::List(
+                        entries
+                            .into_iter()
+                            .enumerate()
+                            .map(|(id, (entry_name, element))| {
+                                Tag::from(vec![
+                                    ("name", Tag::String(entry_name.to_string())),
+                                    ("id", Tag::Int(id as i32)),
+                                    ("element", element),
+                                ])
+                            })
+                            .collect(),
+                    ),
+                ),
+            ]),
+        )
+    }
+
+    fn dimension_type(min_y: i32, height: i32, coordinate_scale: f64) -> Tag {
+        Tag::from(vec![
+            ("min_y", Tag::Int(min_y)),
+            ("height", Tag::Int(height)),
+            ("logical_height", Tag::Int(height)),
+            ("has_skylight", Tag::Byte(1)),
+            ("has_ceiling", Tag::Byte(0)),
+            ("ultrawarm", Tag::Byte(0)),
+            ("natural", Tag::Byte(1)),
+            ("coordinate_scale", Tag::Double(coordinate_scale)),
+        ])
+    }
+
+    #[test]
+    fn test_from_nbt() {
+        let tag = Tag::from(vec![(
+            "",
+            Tag::from(vec![
+                registry(
+                    "minecraft:dimension_type",
+                    vec![
+                        ("minecraft:overworld", dimension_type(-64, 384, 1.)),
+                        ("minecraft:the_nether", dimension_type(0, 256, 8.)),
+                    ],
+                ),
+                registry(
+                    "minecraft:worldgen/biome",
+                    vec![(
+                        "minecraft:plains",
+                        Tag::from(vec![
+                            ("precipitation", Tag::String("rain".to_string())),
+                            ("temperature", Tag::Float(0.8)),
+                            ("downfall", Tag::Float(0.4)),
+                        ]),
+                    )],
+                ),
+            ]),
+        )]);
+        let registries = RegistryHolder::from_nbt(&tag).unwrap();
+
+        let overworld = registries
+            .dimension_type(&ResourceLocation::new("minecraft:overworld").unwrap())
+            .unwrap();
+        assert_eq!(overworld.min_y, -64);
+        assert_eq!(overworld.max_y(), 320);
+        let nether = registries
+            .dimension_type(&ResourceLocation::new("minecraft:the_nether").unwrap())
+            .unwrap();
+        assert_eq!(nether.id, 1);
+        assert_eq!(nether.height, 256);
+        assert_eq!(nether.coordinate_scale, 8.);
+
+        let plains = registries.biome(0).unwrap();
+        assert_eq!(plains.name.to_string(), "minecraft:plains");
+        assert_eq!(plains.precipitation, "rain");
+    }
+
+    #[test]
+    fn test_missing_registry() {
+        assert_eq!(
+            RegistryHolder::from_nbt(&Tag::Compound(AHashMap::new())),
+            Err(RegistryHolderError::MissingTag(
+                "minecraft:dimension_type".to_string()
+            ))
+        );
+    }
+}